@@ -1,9 +1,14 @@
 use crate::{
-    accounting::{BODBalance, Balance, Order, Position},
+    accounting::{BODBalance, Balance, Order, Position, Wallet},
+    execution::emergency::{self, EmergencyFilter, EmergencyReport},
     responses::{
-        account::{GetAccountsResp, GetOrdersResp, StreamOrdersResp, StreamPositionsResp},
+        account::{
+            GetAccountsResp, GetOrdersResp, StreamOrdersResp, StreamPositionsResp,
+            StreamWalletsResp,
+        },
         ApiResponse,
     },
+    stream::{SharedStream, SharedStreamConfig},
     Client, Error,
 };
 use async_stream::try_stream;
@@ -169,7 +174,7 @@ impl Account {
     /// NOTE: Same as `get_orders` but for multiple accounts
     /// NOTE: For internal use only. Use `Account::get_orders_by_id()`
     /// to access this functionality.
-    async fn get_orders_for_accounts<S: Into<String>>(
+    pub(crate) async fn get_orders_for_accounts<S: Into<String>>(
         client: &Client,
         account_ids: Vec<S>,
     ) -> Result<Vec<Order>, Error> {
@@ -505,6 +510,23 @@ impl Account {
         Order::stream_into(client, &self.account_id, callback).await
     }
 
+    /// Stream `Order`(s) for the given `Account` through a [`SharedStream`],
+    /// so many subscribers can share a single stream connection.
+    ///
+    /// <div class="warning">WARNING: There's a max of 10 concurrent streams allowed.</div>
+    ///
+    /// NOTE: Clone the returned [`SharedStream`] to add subscribers, the
+    /// stream is closed once every subscriber is dropped.
+    pub fn share_orders_stream(
+        &self,
+        client: &Client,
+        config: SharedStreamConfig,
+    ) -> SharedStream<StreamOrdersResp> {
+        let endpoint = format!("brokerage/stream/accounts/{}/orders", self.account_id);
+
+        SharedStream::open(client, endpoint, config)
+    }
+
     /// Stream `Order`(s) by order id's for the given `Account`.
     ///
     /// <div class="warning">WARNING: There's a max of 10 concurrent streams allowed.</div>
@@ -1152,6 +1174,110 @@ impl Account {
     ) -> Result<(), Error> {
         Position::stream_for_accounts_into(client, account_ids, callback).await
     }
+
+    /// Is the `Account` able to hold crypto [`Wallet`]'s?
+    pub fn is_crypto(&self) -> bool {
+        self.account_type == AccountType::Crypto
+            || self
+                .account_detail
+                .as_ref()
+                .is_some_and(|detail| detail.crypto_enabled)
+    }
+
+    /// Get the [`Wallet`]'s of a crypto `Account`.
+    ///
+    /// # Example
+    /// ---
+    ///
+    /// ```ignore
+    /// let account = client.get_account("YOUR_CRYPTO_ACCOUNT_ID").await?;
+    /// let wallets = account.get_wallets(&client).await?;
+    /// println!("Wallets: {wallets:?}");
+    /// ```
+    pub async fn get_wallets(&self, client: &Client) -> Result<Vec<Wallet>, Error> {
+        Wallet::get_by_account(client, &self.account_id).await
+    }
+
+    /// Stream [`Wallet`]'s for a crypto `Account`.
+    ///
+    /// <div class="warning">WARNING: There's a max of 10 concurrent streams allowed.</div>
+    ///
+    /// NOTE: You must pin the stream before polling it.
+    ///
+    /// # Example
+    /// ---
+    ///
+    /// Watch the bitcoin balance of an account.
+    ///
+    /// ```rust,no_run
+    /// # use futures::StreamExt;
+    /// # use tradestation::{Client, Error, accounting::Account, responses::account::StreamWalletsResp};
+    /// # async fn example(account: &Account, client: &Client) -> Result<(), Error> {
+    /// let wallets_stream = account.stream_wallets(client);
+    /// tokio::pin!(wallets_stream);
+    ///
+    /// while let Some(event) = wallets_stream.next().await {
+    ///     match event? {
+    ///         StreamWalletsResp::Wallet(wallet) if wallet.currency == "BTC" => {
+    ///             println!("BTC balance: {}", wallet.balance);
+    ///         }
+    ///         StreamWalletsResp::Heartbeat(heartbeat) if heartbeat.heartbeat > 10 => {
+    ///             return Err(Error::StopStream);
+    ///         }
+    ///         other => println!("{other:?}"),
+    ///     }
+    /// }
+    /// # Ok(()) }
+    /// ```
+    pub fn stream_wallets<'a>(
+        &'a self,
+        client: &'a Client,
+    ) -> impl Stream<Item = Result<StreamWalletsResp, Error>> + 'a {
+        Wallet::stream(client, &self.account_id)
+    }
+
+    /// Stream [`Wallet`]'s for a crypto [`Account`] into a provided callback function.
+    ///
+    /// <div class="warning">WARNING: There's a max of 10 concurrent streams allowed.</div>
+    ///
+    /// # Stopping the stream
+    ///
+    /// To stop the stream gracefully from within the callback, return
+    /// `Err(Error::StopStream)`. This is treated as a control signal and will
+    /// terminate the stream without propagating an error. Any other error
+    /// returned from the callback will abort the stream and be returned to
+    /// the caller.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error`] if the underlying stream cannot be established,
+    /// if JSON parsing of a stream event fails, or if the `callback`
+    /// returns an error.
+    ///
+    /// # Example
+    /// ---
+    /// Stream events on all wallets for an account.
+    ///
+    /// ```rust,no_run
+    /// # use tradestation::{client::Client, accounting::{Account}, responses::account::StreamWalletsResp, Error};
+    /// # async fn example(account: &Account, client: &Client) -> Result<(), Error> {
+    /// account.stream_wallets_into(
+    ///     client,
+    ///     |stream_event: StreamWalletsResp| -> Result<(), Error> {
+    ///         println!("Wallets Stream Event: {stream_event:?}");
+    ///         Ok(())
+    ///     }
+    /// ).await?;
+    ///
+    /// #  Ok(()) }
+    /// ```
+    pub async fn stream_wallets_into(
+        &self,
+        client: &Client,
+        callback: impl FnMut(StreamWalletsResp) -> Result<(), Error>,
+    ) -> Result<(), Error> {
+        Wallet::stream_into(client, &self.account_id, callback).await
+    }
 }
 
 /// Trait to allow calling methods on multiple accounts `Vec<Account>`.
@@ -1641,6 +1767,84 @@ pub trait MultipleAccounts {
     /// }
     /// ```
     fn stream_positions<'a>(&'a self, client: &'a Client) -> Self::StreamPositions<'a>;
+
+    /// The future returned by [`MultipleAccounts::get_wallets`].
+    ///
+    /// Resolves to the wallets within the crypto accounts or a thread-safe boxed error.
+    type GetWalletsFuture<'a>: Future<Output = Result<Vec<Wallet>, Box<dyn StdErrorTrait + Send + Sync>>>
+        + Send
+        + 'a
+    where
+        Self: 'a;
+    /// Get the [`Wallet`]'s for multiple `Account`(s).
+    ///
+    /// NOTE: Only crypto `Account`(s) are included, see [`Account::is_crypto`].
+    ///
+    /// # Example
+    /// ---
+    ///
+    /// ```ignore
+    /// let accounts = client.get_accounts().await?;
+    /// let wallets = accounts.get_wallets(&client).await?;
+    /// println!("Wallets: {wallets:?}");
+    /// ```
+    fn get_wallets<'a>(&'a self, client: &'a Client) -> Self::GetWalletsFuture<'a>;
+
+    /// The future returned by [`MultipleAccounts::cancel_all_orders`] and [`MultipleAccounts::flatten_all`].
+    ///
+    /// Resolves to a report of every order canceled and position closed or a thread-safe boxed error.
+    type EmergencyFuture<'a>: Future<Output = Result<EmergencyReport, Box<dyn StdErrorTrait + Send + Sync>>>
+        + Send
+        + 'a
+    where
+        Self: 'a;
+    /// Cancel every open `Order` the filter applies to in multiple `Account`(s), concurrently.
+    ///
+    /// NOTE: Failed cancellations are reported in the [`EmergencyReport`],
+    /// only failing to fetch the orders is an error.
+    ///
+    /// # Example
+    /// ---
+    ///
+    /// ```ignore
+    /// let accounts = client.get_accounts().await?;
+    /// let report = accounts
+    ///     .cancel_all_orders(&client, &EmergencyFilter::new().symbol("TSLA"))
+    ///     .await?;
+    /// println!("Canceled {} orders", report.cancellations.len());
+    /// ```
+    fn cancel_all_orders<'a>(
+        &'a self,
+        client: &'a Client,
+        filter: &'a EmergencyFilter,
+    ) -> Self::EmergencyFuture<'a>;
+
+    /// Cancel every open `Order` the filter applies to in multiple `Account`(s), then close
+    /// every `Position` it applies to with a market order, both concurrently.
+    ///
+    /// NOTE: Failed cancellations and closing orders are reported in the
+    /// [`EmergencyReport`], only failing to fetch the orders or positions is an error.
+    ///
+    /// NOTE: Closing orders bypass the kill switch and risk policy, but
+    /// in dry-run mode they're only confirmed, see [`crate::execution::emergency`].
+    ///
+    /// # Example
+    /// ---
+    ///
+    /// ```ignore
+    /// let accounts = client.get_accounts().await?;
+    /// let report = accounts.flatten_all(&client, &EmergencyFilter::new()).await?;
+    /// if !report.is_success() {
+    ///     for failure in report.failures() {
+    ///         eprintln!("{failure}");
+    ///     }
+    /// }
+    /// ```
+    fn flatten_all<'a>(
+        &'a self,
+        client: &'a Client,
+        filter: &'a EmergencyFilter,
+    ) -> Self::EmergencyFuture<'a>;
 }
 impl MultipleAccounts for Vec<Account> {
     /// Find a specific account by a given account id from
@@ -2143,6 +2347,64 @@ impl MultipleAccounts for Vec<Account> {
             }
         })
     }
+
+    type GetWalletsFuture<'a> = Pin<
+        Box<
+            dyn Future<Output = Result<Vec<Wallet>, Box<dyn StdErrorTrait + Send + Sync>>>
+                + Send
+                + 'a,
+        >,
+    >;
+    /// Get the [`Wallet`]'s for multiple `Account`(s).
+    fn get_wallets<'a>(&'a self, client: &'a Client) -> Self::GetWalletsFuture<'a> {
+        Box::pin(async move {
+            let mut wallets = Vec::new();
+            for account in self.iter().filter(|account| account.is_crypto()) {
+                wallets.extend(account.get_wallets(client).await?);
+            }
+
+            Ok(wallets)
+        })
+    }
+
+    type EmergencyFuture<'a> = Pin<
+        Box<
+            dyn Future<Output = Result<EmergencyReport, Box<dyn StdErrorTrait + Send + Sync>>>
+                + Send
+                + 'a,
+        >,
+    >;
+    fn cancel_all_orders<'a>(
+        &'a self,
+        client: &'a Client,
+        filter: &'a EmergencyFilter,
+    ) -> Self::EmergencyFuture<'a> {
+        let account_ids: Vec<&str> = self
+            .iter()
+            .map(|account| account.account_id.as_str())
+            .collect();
+
+        Box::pin(async move {
+            let report = emergency::cancel_all_in_accounts(client, account_ids, filter).await?;
+            Ok(report)
+        })
+    }
+
+    fn flatten_all<'a>(
+        &'a self,
+        client: &'a Client,
+        filter: &'a EmergencyFilter,
+    ) -> Self::EmergencyFuture<'a> {
+        let account_ids: Vec<&str> = self
+            .iter()
+            .map(|account| account.account_id.as_str())
+            .collect();
+
+        Box::pin(async move {
+            let report = emergency::flatten_accounts(client, account_ids, filter).await?;
+            Ok(report)
+        })
+    }
 }
 
 #[derive(Clone, Debug, Deserialize, Serialize)]
@@ -2224,6 +2486,9 @@ pub enum AccountType {
 
     /// Delivery Vs Payment Account
     DVP,
+
+    /// Crypto Account
+    Crypto,
 }
 
 #[derive(Clone, Debug, Serialize)]
@@ -9,6 +9,8 @@
 //! - [Stream Orders](#endpoint-stream-orders): Stream orders for the given accounts.
 //! - [Stream Orders By Order ID](#endpoint-stream-orders-by-order-id): Stream orders for the given accounts, filtered by given Order ID's.
 //! - [Stream Positions](#endpoint-stream-positions): Stream positions for the given accounts.
+//! - [Get Wallets](#endpoint-get-wallets): Fetches the crypto wallets for a given crypto account.
+//! - [Stream Wallets](#endpoint-stream-wallets): Stream crypto wallets for a given crypto account.
 //!
 //! # Endpoint: Get Accounts
 //! - Fetches the list of Brokerage Accounts available for the current user.
@@ -311,6 +313,60 @@
 //! }
 //! # Ok(()) }
 //! ```
+//!
+//! ---
+//!
+//! # Endpoint: Get Wallets
+//! - Fetches the balance of each cryptocurrency held in a crypto account.
+//! - Wallet Reference: [`crate::accounting::Wallet`]
+//! - **Example**: Load the wallets of every crypto account.
+//!
+//! ```rust,no_run
+//! # use tradestation::{Error, Client};
+//! # use tradestation::accounting::MultipleAccounts;
+//! # async fn get_wallets_example(client: &Client) -> Result<(), Error> {
+//! let wallets = client.get_wallets("YOUR_CRYPTO_ACCOUNT_ID").await?;
+//!
+//! let accounts = client.get_accounts().await?;
+//! let all_wallets = accounts.get_wallets(client).await?;
+//!
+//! println!(
+//!     "Found {} wallets in one account and {} total wallets",
+//!     wallets.len(),
+//!     all_wallets.len()
+//! );
+//! # Ok(()) }
+//! ```
+//!
+//! ---
+//!
+//! # Endpoint: Stream Wallets
+//! - Streams wallet events for a crypto account.
+//! - Stream Response Reference: [`crate::responses::account::StreamWalletsResp`]
+//! - **Example**: Watch wallet balances and stop after repeated inactivity.
+//!
+//! ```rust,no_run
+//! # use futures::StreamExt;
+//! # use tradestation::{Error, Client};
+//! # use tradestation::responses::account::StreamWalletsResp;
+//! # async fn stream_wallets_example(client: &Client) -> Result<(), Error> {
+//! let account = client.get_account("YOUR_CRYPTO_ACCOUNT_ID").await?;
+//! let wallets_stream = account.stream_wallets(client);
+//! tokio::pin!(wallets_stream);
+//!
+//! while let Some(event) = wallets_stream.next().await {
+//!     match event? {
+//!         StreamWalletsResp::Wallet(wallet) => {
+//!             println!("{} balance: {}", wallet.currency, wallet.balance);
+//!         }
+//!         StreamWalletsResp::Heartbeat(heartbeat) if heartbeat.heartbeat > 10 => {
+//!             return Err(Error::StopStream);
+//!         }
+//!         other => println!("{other:?}"),
+//!     }
+//! }
+//! # Ok(()) }
+//! ```
 
 /// Types and functionality for working with brokerage accounts.
 pub mod accounts;
@@ -324,12 +380,20 @@ pub mod orders;
 /// Types and functionality for working with account positions.
 pub mod positions;
 
+/// Types and functionality for classifying order statuses, and following their transitions.
+pub mod status;
+
+/// Types and functionality for working with crypto account wallets.
+pub mod wallets;
+
 pub use accounts::{Account, MultipleAccounts};
 pub use balances::{
     BODBalance, BODBalanceDetail, BODCurrencyDetails, Balance, BalanceDetail, CurrencyDetails,
 };
 pub use orders::{
-    AssetType, LogicOp, OptionType, Order, OrderAction, OrderRelationship, OrderStage, OrderStatus,
-    OrderType, Predicate, TickTrigger,
+    ActivationRuleType, AssetType, LogicOp, OptionType, Order, OrderAction, OrderRelationship,
+    OrderStage, OrderStatus, OrderType, Predicate, TickTrigger,
 };
 pub use positions::{Position, PositionType};
+pub use status::{OrderStateMachine, OrderStatusCategory};
+pub use wallets::Wallet;
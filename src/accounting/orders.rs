@@ -1,4 +1,5 @@
 use crate::{
+    execution::ActivationTriggerKey,
     responses::{
         account::{GetOrdersResp, StreamOrdersResp},
         ApiResponse,
@@ -120,7 +121,7 @@ pub struct Order {
 }
 impl Order {
     /// Fetches orders for the given `Account`.
-    pub(super) async fn get_all_by_account<S: Into<String>>(
+    pub(crate) async fn get_all_by_account<S: Into<String>>(
         client: &Client,
         account_id: S,
     ) -> Result<Vec<Order>, Error> {
@@ -258,7 +259,7 @@ impl Order {
     }
 
     /// Stream `Order`(s) by order id's for the given `Account`.
-    pub(super) fn stream_by_ids<'a>(
+    pub(crate) fn stream_by_ids<'a>(
         client: &'a Client,
         order_ids: Vec<&'a str>,
         account_id: &'a str,
@@ -507,7 +508,7 @@ pub struct TrailingStop {
     pub percent: Option<String>,
 }
 
-#[derive(Clone, Debug, Deserialize, Serialize)]
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
 /// Types of `Order`(s).
 pub enum OrderType {
     /// Limit Order
@@ -523,23 +524,26 @@ pub enum OrderType {
     StopLimit,
 }
 
-#[derive(Clone, Debug, Deserialize, Serialize)]
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
 #[serde(rename_all = "PascalCase")]
 /// Allows you to specify a time that an `Order` will be placed.
+///
+/// NOTE: Create one from a UTC timestamp with [`TimeActivationRule::at`],
+/// [`TimeActivationRule::from_epoch_seconds`], or [`TimeActivationRule::parse`].
 pub struct TimeActivationRule {
     /// Timestamp represented as an RFC3339 formatted date.
-    time_utc: String,
+    pub time_utc: String,
 }
 
 #[derive(Clone, Debug, Deserialize, Serialize)]
 #[serde(rename_all = "PascalCase")]
 /// Allows you to specify when an order will be placed
 /// based on the price action of one or more symbols.
+///
+/// NOTE: Build these with [`crate::execution::activation::MarketActivationRuleBuilder`].
 pub struct MarketActivationRule {
     /// The type of activation rule.
-    ///
-    /// NOTE: Currently only supports `"Price"` for now.
-    pub rule_type: String,
+    pub rule_type: ActivationRuleType,
 
     /// The symbol that the rule is based on.
     pub symbol: String,
@@ -548,7 +552,7 @@ pub struct MarketActivationRule {
     pub predicate: Predicate,
 
     /// The ticks behavior for the activation rule.
-    pub tigger_key: TickTrigger,
+    pub trigger_key: ActivationTriggerKey,
 
     /// The price at which the rule will trigger.
     pub price: Option<String>,
@@ -559,7 +563,16 @@ pub struct MarketActivationRule {
     pub logic_operator: Option<LogicOp>,
 }
 
-#[derive(Clone, Debug, Deserialize, Serialize)]
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+/// The types of market activation rules.
+pub enum ActivationRuleType {
+    /// Activate on the price of a symbol.
+    ///
+    /// NOTE: Currently the only type TradeStation supports.
+    Price,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
 /// A logical operator used to combine conditions.
 pub enum LogicOp {
     /// Logical AND.
@@ -569,47 +582,12 @@ pub enum LogicOp {
     Or,
 }
 
-#[derive(Clone, Debug, Deserialize, Serialize)]
-/// Types of tick triggers
-pub enum TickTrigger {
-    /// Single Trade Tick
-    STT,
-
-    /// Single Trade Tick Within NBBO
-    STTN,
-
-    /// Single Bid/Ask Tick
-    SBA,
-
-    /// Single Ask/Bid Tick
-    SAB,
-
-    /// Double Trade Tick
-    DTT,
-
-    /// Double Trade Tick Within NBBO
-    DTTN,
-
-    /// Double Bid/Ask Tick
-    DBA,
-
-    /// Double Ask/Bid Tick
-    DAB,
-
-    /// Triple Trade Tick
-    TTT,
-
-    /// Triple Trade Tick Within NBBO
-    TTTN,
-
-    /// Triple Bid/Ask Tick
-    TBA,
-
-    /// Triple Ask/Bid Tick
-    TAB,
-}
+/// Types of tick triggers.
+///
+/// NOTE: Kept as an alias of [`ActivationTriggerKey`], which is used by the execution endpoints.
+pub type TickTrigger = ActivationTriggerKey;
 
-#[derive(Clone, Debug, Deserialize, Serialize)]
+#[derive(Clone, Copy, Debug, Deserialize, Serialize, PartialEq, Eq)]
 /// Types of comparison predicates
 pub enum Predicate {
     /// Less than
@@ -673,7 +651,7 @@ pub struct OrderLeg {
     pub underlying: Option<String>,
 }
 
-#[derive(Clone, Debug, Deserialize, Serialize)]
+#[derive(Clone, Copy, Debug, Deserialize, Serialize, PartialEq, Eq)]
 /// The type of option
 pub enum OptionType {
     #[serde(rename = "CALL")]
@@ -734,7 +712,7 @@ pub enum OrderAction {
     BuyToCover,
 }
 
-#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq, Hash)]
 /// The different types of asset's.
 pub enum AssetType {
     #[serde(rename = "UNKNOWN")]
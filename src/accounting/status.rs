@@ -0,0 +1,257 @@
+//! Classify [`OrderStatus`] codes, and follow an order's status through the
+//! transitions observed on the order stream.
+//!
+//! ```rust,no_run
+//! # use tradestation::{Client, Error};
+//! use futures::StreamExt;
+//! use tradestation::accounting::{Account, OrderStateMachine};
+//! use tradestation::responses::account::StreamOrdersResp;
+//!
+//! # async fn example(client: &Client, account: &Account) -> Result<(), Error> {
+//! let mut state_machine = OrderStateMachine::new();
+//!
+//! let orders_stream = account.stream_orders_by_id(client, vec!["ORDER_ID"]);
+//! tokio::pin!(orders_stream);
+//! while let Some(stream_resp) = orders_stream.next().await {
+//!     if let StreamOrdersResp::Order(order) = stream_resp? {
+//!         if let Err(error) = state_machine.advance(order.status.clone()) {
+//!             eprintln!("{error}");
+//!         }
+//!
+//!         println!("{}", order.status.description());
+//!         if order.status.is_terminal() {
+//!             break;
+//!         }
+//!     }
+//! }
+//! # Ok(()) }
+//! ```
+
+use crate::{accounting::orders::OrderStatus, Error};
+use tracing::warn;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+/// The standing an [`OrderStatus`] puts an order in.
+pub enum OrderStatusCategory {
+    /// On it's way to, or working in, the market without any fills.
+    Open,
+
+    /// Open, with a cancel or replace request on it's way.
+    PendingChange,
+
+    /// Part of the order was filled, and the rest is still working.
+    PartiallyFilled,
+
+    /// The order was filled.
+    Filled,
+
+    /// The order was canceled, possibly after being partially filled.
+    Cancelled,
+
+    /// The order was rejected.
+    Rejected,
+
+    /// The order expired.
+    Expired,
+
+    /// The order was replaced by another order.
+    Replaced,
+
+    /// A notice about the order which doesn't change it's standing.
+    Informational,
+}
+impl OrderStatusCategory {
+    /// Whether the order is done, and won't change anymore.
+    pub fn is_terminal(&self) -> bool {
+        matches!(
+            self,
+            Self::Filled | Self::Cancelled | Self::Rejected | Self::Expired | Self::Replaced
+        )
+    }
+}
+
+impl OrderStatus {
+    /// The standing the status puts an order in.
+    pub fn category(&self) -> OrderStatusCategory {
+        match self {
+            Self::ACK
+            | Self::DON
+            | Self::PLA
+            | Self::OPN
+            | Self::DIS
+            | Self::CND
+            | Self::STP
+            | Self::OSO
+            | Self::SUS => OrderStatusCategory::Open,
+            Self::CHG | Self::CSN | Self::UCN | Self::ECN | Self::REC | Self::RPD | Self::RSN => {
+                OrderStatusCategory::PendingChange
+            }
+            Self::FPR => OrderStatusCategory::PartiallyFilled,
+            Self::FLL | Self::BRF => OrderStatusCategory::Filled,
+            Self::CAN | Self::BRC | Self::OUT | Self::FLP | Self::TSC | Self::SCN => {
+                OrderStatusCategory::Cancelled
+            }
+            Self::REJ | Self::BRO | Self::DOA => OrderStatusCategory::Rejected,
+            Self::EXP => OrderStatusCategory::Expired,
+            Self::UCH => OrderStatusCategory::Replaced,
+            Self::ASS
+            | Self::EXE
+            | Self::COR
+            | Self::LAT
+            | Self::RJC
+            | Self::RJR
+            | Self::STT
+            | Self::OTHER => OrderStatusCategory::Informational,
+        }
+    }
+
+    /// Whether the order is still open (not filled, canceled, rejected, expired, or replaced).
+    pub fn is_open(&self) -> bool {
+        !self.is_terminal()
+    }
+
+    /// Whether the order is done, and won't change anymore.
+    pub fn is_terminal(&self) -> bool {
+        self.category().is_terminal()
+    }
+
+    /// Whether the order was filled.
+    pub fn is_filled(&self) -> bool {
+        self.category() == OrderStatusCategory::Filled
+    }
+
+    /// Whether part of the order was filled, and the rest is still working.
+    pub fn is_partially_filled(&self) -> bool {
+        self.category() == OrderStatusCategory::PartiallyFilled
+    }
+
+    /// Whether the order was canceled.
+    pub fn is_cancelled(&self) -> bool {
+        self.category() == OrderStatusCategory::Cancelled
+    }
+
+    /// Whether the order was rejected.
+    pub fn is_rejected(&self) -> bool {
+        self.category() == OrderStatusCategory::Rejected
+    }
+
+    /// A human readable description of the status.
+    pub fn description(&self) -> &'static str {
+        match self {
+            Self::ACK => "Acknowledged: the order was received",
+            Self::ASS => "Option Assignment: an option position was assigned",
+            Self::BRC => "Bracket Canceled: the order was canceled by it's bracket",
+            Self::BRF => "Bracket Filled: the order was filled as part of a bracket",
+            Self::BRO => "Broken: the order was broken by the exchange",
+            Self::CHG => "Change: a change to the order was requested",
+            Self::CND => "Condition Met: the order's activation conditions were met",
+            Self::COR => "Fill Corrected: a fill of the order was corrected",
+            Self::CSN => "Cancel Sent: a cancel request was sent",
+            Self::DIS => "Dispatched: the order was dispatched to the market",
+            Self::DOA => "Dead: the order was rejected before reaching the market",
+            Self::DON => "Queued: the order is queued to be sent",
+            Self::ECN => "Expiration Cancel Request: the order is being canceled as it expires",
+            Self::EXE => "Option Exercise: an option position was exercised",
+            Self::FPR => {
+                "Partial Fill (Alive): part of the order was filled, and the rest is working"
+            }
+            Self::LAT => "Too Late to Cancel: the order couldn't be canceled in time",
+            Self::OPN => "Sent: the order was sent to the market",
+            Self::OSO => "Order Sends Order: the order is waiting on it's parent order",
+            Self::PLA => "Sending: the order is being sent to the market",
+            Self::REC => "Big Brother Recall Request: the order is being recalled",
+            Self::RJC => "Cancel Request Rejected: the order is still working",
+            Self::RPD => "Replace Pending: a replacement of the order is pending",
+            Self::RSN => "Replace Sent: a replacement of the order was sent",
+            Self::STP => "Stop Hit: the order's stop price was hit",
+            Self::STT => "Status Message: a notice about the order",
+            Self::SUS => "Suspended: the order is suspended",
+            Self::UCN => "Cancel Sent: a cancel request was sent",
+            Self::CAN => "Canceled: the order was canceled",
+            Self::EXP => "Expired: the order expired",
+            Self::OUT => "UROut: the order was canceled",
+            Self::RJR => "Change Request Rejected: the order is unchanged",
+            Self::SCN => "Big Brother Recall: the order was recalled",
+            Self::TSC => "Trade Server Canceled: the order was canceled by the trade server",
+            Self::UCH => "Replaced: the order was replaced",
+            Self::REJ => "Rejected: the order was rejected",
+            Self::FLL => "Filled: the order was filled",
+            Self::FLP => {
+                "Partial Fill (UROut): the order was canceled after being partially filled"
+            }
+            Self::OTHER => "Unmapped: a status not known to this crate",
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+/// Follows an order's [`OrderStatus`] through the transitions
+/// observed on the order stream, flagging impossible ones.
+pub struct OrderStateMachine {
+    status: Option<OrderStatus>,
+}
+impl OrderStateMachine {
+    /// Create a state machine for an order that hasn't been observed yet.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The order's current status, ignoring [`OrderStatusCategory::Informational`] statuses.
+    pub fn status(&self) -> Option<&OrderStatus> {
+        self.status.as_ref()
+    }
+
+    /// Whether the order is done, and won't change anymore.
+    pub fn is_terminal(&self) -> bool {
+        self.status.as_ref().is_some_and(OrderStatus::is_terminal)
+    }
+
+    /// Whether the order can go from one status to another.
+    ///
+    /// NOTE: Repeated and [`OrderStatusCategory::Informational`] statuses are always valid.
+    pub fn is_valid_transition(from: &OrderStatus, to: &OrderStatus) -> bool {
+        if from == to || to.category() == OrderStatusCategory::Informational {
+            return true;
+        }
+
+        match from.category() {
+            category if category.is_terminal() => false,
+            // Fills can't be undone, so the order can't go back to being unfilled
+            OrderStatusCategory::PartiallyFilled => to.category() != OrderStatusCategory::Open,
+            _ => true,
+        }
+    }
+
+    /// Advance the order to the next status observed for it.
+    ///
+    /// NOTE: [`OrderStatusCategory::Informational`] statuses don't change the order's status.
+    ///
+    /// # Errors
+    ///
+    /// [`Error::InvalidOrderTransition`] if the order can't go to the status, like a filled
+    /// order being reopened, in which case the order's status is left unchanged.
+    pub fn advance(&mut self, status: OrderStatus) -> Result<(), Error> {
+        if let Some(current) = &self.status {
+            if !Self::is_valid_transition(current, &status) {
+                warn!(
+                    target: "tradestation::execution",
+                    from = ?current,
+                    to = ?status,
+                    "impossible order status transition"
+                );
+
+                return Err(Error::InvalidOrderTransition(format!(
+                    "{current:?} ({}) can't go to {status:?} ({})",
+                    current.description(),
+                    status.description()
+                )));
+            }
+        }
+
+        if status.category() != OrderStatusCategory::Informational {
+            self.status = Some(status);
+        }
+
+        Ok(())
+    }
+}
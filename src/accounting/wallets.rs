@@ -0,0 +1,142 @@
+use crate::{
+    responses::{
+        account::{GetWalletsResp, StreamWalletsResp},
+        ApiResponse,
+    },
+    Client, Error,
+};
+use futures::{Stream, StreamExt};
+use serde::{Deserialize, Deserializer, Serialize};
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "PascalCase")]
+/// The balance of a single cryptocurrency held in a crypto `Account`.
+pub struct Wallet {
+    #[serde(rename = "AccountID")]
+    /// The `Account` id the `Wallet` belongs to.
+    pub account_id: String,
+
+    /// The cryptocurrency held in the `Wallet`, e.g. `BTC`.
+    pub currency: String,
+
+    #[serde(deserialize_with = "string_from_number")]
+    /// The total balance of the currency held in the `Wallet`.
+    pub balance: String,
+
+    #[serde(default, deserialize_with = "option_string_from_number")]
+    /// The balance of the currency available to trade.
+    pub balance_available_for_trading: Option<String>,
+
+    #[serde(default, deserialize_with = "option_string_from_number")]
+    /// The balance of the currency available to withdraw.
+    pub balance_available_for_withdrawal: Option<String>,
+
+    #[serde(default, deserialize_with = "option_string_from_number")]
+    /// The interest earned on the currency held in the `Wallet`.
+    pub interest: Option<String>,
+
+    #[serde(default, deserialize_with = "option_string_from_number")]
+    /// The balance of the currency currently staked.
+    pub staked_balance: Option<String>,
+}
+impl Wallet {
+    /// Fetches the `Wallet`(s) of a crypto `Account`.
+    pub(super) async fn get_by_account<S: Into<String>>(
+        client: &Client,
+        account_id: S,
+    ) -> Result<Vec<Wallet>, Error> {
+        let endpoint = format!("brokerage/accounts/{}/wallets", account_id.into());
+
+        match client
+            .get(&endpoint)
+            .await?
+            .json::<ApiResponse<GetWalletsResp>>()
+            .await?
+        {
+            ApiResponse::Success(resp) => Ok(resp.wallets),
+            ApiResponse::Error(resp) => Err(Error::from_api_error(resp)),
+        }
+    }
+
+    /// Stream `Wallet`s for the given crypto `Account`.
+    pub(super) fn stream<S: Into<String>>(
+        client: &Client,
+        account_id: S,
+    ) -> impl Stream<Item = Result<StreamWalletsResp, Error>> + '_ {
+        let endpoint = format!("brokerage/stream/accounts/{}/wallets", account_id.into());
+
+        client.stream(endpoint).filter_map(|chunk| async {
+            match chunk {
+                Ok(value) => match serde_json::from_value::<StreamWalletsResp>(value) {
+                    Ok(stream_wallets_chunk) => Some(Ok(stream_wallets_chunk)),
+                    Err(e) => Some(Err(Error::Json(e))),
+                },
+                Err(e) => Some(Err(e)),
+            }
+        })
+    }
+
+    /// Stream [`Wallet`]'s for a given crypto [`crate::accounting::Account`] into a provided callback function.
+    pub(super) async fn stream_into(
+        client: &Client,
+        account_id: impl Into<String>,
+        mut callback: impl FnMut(StreamWalletsResp) -> Result<(), Error>,
+    ) -> Result<(), Error> {
+        let endpoint = format!("brokerage/stream/accounts/{}/wallets", account_id.into());
+
+        client
+            .stream_into(&endpoint, |stream_event| {
+                let parsed_event: StreamWalletsResp = serde_json::from_value(stream_event)?;
+                callback(parsed_event)?;
+
+                Ok(())
+            })
+            .await?;
+
+        Ok(())
+    }
+}
+
+/// Deserialize a balance sent as either a JSON number or string into a string.
+fn string_from_number<'de, D>(deserializer: D) -> Result<String, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    match serde_json::Value::deserialize(deserializer)? {
+        serde_json::Value::String(value) => Ok(value),
+        serde_json::Value::Number(value) => Ok(value.to_string()),
+        other => Err(serde::de::Error::custom(format!(
+            "expected a number or string, got {other}"
+        ))),
+    }
+}
+
+/// Deserialize an optional balance sent as either a JSON number or string into a string.
+fn option_string_from_number<'de, D>(deserializer: D) -> Result<Option<String>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    match serde_json::Value::deserialize(deserializer)? {
+        serde_json::Value::Null => Ok(None),
+        value => string_from_number(value)
+            .map(Some)
+            .map_err(serde::de::Error::custom),
+    }
+}
+
+impl Client {
+    /// Fetches the `Wallet`(s) of a crypto `Account` by it's account id.
+    ///
+    /// # Example
+    /// ---
+    ///
+    /// ```ignore
+    /// let wallets = client.get_wallets("YOUR_CRYPTO_ACCOUNT_ID").await?;
+    /// for wallet in wallets {
+    ///     println!("{}: {}", wallet.currency, wallet.balance);
+    /// }
+    /// ```
+    pub async fn get_wallets<S: Into<String>>(&self, account_id: S) -> Result<Vec<Wallet>, Error> {
+        Wallet::get_by_account(self, account_id).await
+    }
+}
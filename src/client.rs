@@ -1,4 +1,10 @@
 use crate::{
+    execution::{
+        interlock::{LiveTrading, TradingInterlock},
+        risk::SharedRiskPolicy,
+        submission::UnresolvedSubmissions,
+    },
+    market_data::SymbolDetailsCache,
     token::{RefreshedToken, Token},
     Error, Scope,
 };
@@ -6,7 +12,11 @@ use futures::{Stream, TryStreamExt};
 use reqwest::{header, Method, Response};
 use serde::{de::DeserializeOwned, Serialize};
 use serde_json::Value;
-use std::{collections::HashMap, fmt::Display, sync::Arc};
+use std::{
+    collections::HashMap,
+    fmt::Display,
+    sync::{atomic::AtomicBool, Arc},
+};
 use tokio::{
     io::{AsyncBufReadExt, BufReader},
     sync::Mutex,
@@ -42,6 +52,21 @@ pub struct Client {
     /// TradeStation API environment. This is also useful for identifying whether
     /// transactional requests can affect real brokerage accounts.
     pub environment: ClientEnvironment,
+
+    /// Cache of symbol details shared by every clone of this client.
+    pub(crate) symbol_cache: SymbolDetailsCache,
+
+    /// Pre-trade risk policy shared by every clone of this client.
+    pub(crate) risk_policy: SharedRiskPolicy,
+
+    /// Live trading acknowledgement and kill switch shared by every clone of this client.
+    pub(crate) interlock: TradingInterlock,
+
+    /// Whether placing orders only confirms them, shared by every clone of this client.
+    pub(crate) dry_run: Arc<AtomicBool>,
+
+    /// Order submissions which failed without a definitive outcome, shared by every clone of this client.
+    pub(crate) submissions: UnresolvedSubmissions,
 }
 impl Client {
     /// Send an HTTP request to TradeStation's API, with automatic
@@ -557,6 +582,8 @@ pub struct ClientBuilderStep<CurrentStep> {
     scopes: Vec<Scope>,
     base_url: String,
     token: Option<Token>,
+    live_trading: LiveTrading,
+    dry_run: bool,
 }
 
 #[derive(Debug, Default)]
@@ -634,6 +661,8 @@ impl ClientBuilderStep<Configure> {
             token,
             environment: _,
             base_url: _,
+            live_trading,
+            dry_run,
         } = self;
 
         ClientBuilderStep {
@@ -647,6 +676,8 @@ impl ClientBuilderStep<Configure> {
             token,
             environment: Some(environment),
             base_url,
+            live_trading,
+            dry_run,
         }
     }
 }
@@ -673,6 +704,8 @@ impl ClientBuilderStep<Configured> {
             token: _,
             environment,
             base_url,
+            live_trading,
+            dry_run,
         } = self;
 
         ClientBuilderStep {
@@ -686,6 +719,8 @@ impl ClientBuilderStep<Configured> {
             token: Some(token),
             environment,
             base_url,
+            live_trading,
+            dry_run,
         }
     }
 
@@ -704,6 +739,8 @@ impl ClientBuilderStep<Configured> {
             token: _,
             environment,
             base_url,
+            live_trading,
+            dry_run,
         } = self;
 
         ClientBuilderStep {
@@ -717,6 +754,8 @@ impl ClientBuilderStep<Configured> {
             token: self.token,
             environment,
             base_url,
+            live_trading,
+            dry_run,
         }
     }
 }
@@ -822,6 +861,8 @@ impl ClientBuilderStep<Authorize> {
             token: _,
             environment,
             base_url,
+            live_trading,
+            dry_run,
         } = self;
 
         Ok(ClientBuilderStep {
@@ -835,6 +876,8 @@ impl ClientBuilderStep<Authorize> {
             token: Some(token),
             environment,
             base_url,
+            live_trading,
+            dry_run,
         })
     }
 
@@ -860,6 +903,8 @@ impl ClientBuilderStep<Authorize> {
             token: _,
             environment,
             base_url,
+            live_trading,
+            dry_run,
         } = self;
 
         ClientBuilderStep {
@@ -873,10 +918,52 @@ impl ClientBuilderStep<Authorize> {
             token: Some(token),
             environment,
             base_url,
+            live_trading,
+            dry_run,
         }
     }
 }
 impl ClientBuilderStep<Ready> {
+    #[must_use]
+    /// Acknowledge the [`Client`] can place and replace orders in
+    /// [`ClientEnvironment::Live`], affecting real accounts and real money.
+    ///
+    /// NOTE: Without this, a live [`Client`] refuses to place or replace
+    /// orders with [`Error::LiveTradingNotEnabled`]. It has no effect in
+    /// the other environments.
+    pub fn enable_live_trading(mut self) -> Self {
+        self.live_trading.enabled = true;
+        self
+    }
+
+    #[must_use]
+    /// Restrict the accounts live orders can be placed or replaced for.
+    ///
+    /// NOTE: Orders for any other account are refused with
+    /// [`Error::LiveTradingAccountNotAllowed`].
+    pub fn live_trading_accounts<I, S>(mut self, account_ids: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.live_trading
+            .accounts
+            .get_or_insert_with(Default::default)
+            .extend(account_ids.into_iter().map(Into::into));
+        self
+    }
+
+    #[must_use]
+    /// Start the [`Client`] in dry-run mode, where placing orders
+    /// confirms them instead of sending them to the market.
+    ///
+    /// NOTE: See [`crate::execution::dry_run`], and switch modes
+    /// later on with [`Client::set_dry_run`].
+    pub fn dry_run(mut self) -> Self {
+        self.dry_run = true;
+        self
+    }
+
     /// Finish building into a [`Client`].
     pub async fn build(self) -> Result<Client, Error> {
         let environment = self.environment.ok_or_else(|| Error::EnvironmentNotSet)?;
@@ -893,11 +980,18 @@ impl ClientBuilderStep<Ready> {
                 .redirect_uri
                 .unwrap_or_else(|| "http://localhost:8080/".to_string()),
             environment,
+            symbol_cache: SymbolDetailsCache::new(),
+            risk_policy: SharedRiskPolicy::default(),
+            interlock: TradingInterlock::new(self.live_trading),
+            dry_run: Arc::new(AtomicBool::new(self.dry_run)),
+            submissions: UnresolvedSubmissions::default(),
         };
 
         debug!(
             target: "tradestation::client",
             environment = %client.environment,
+            live_trading_enabled = client.live_trading_enabled(),
+            dry_run = client.is_dry_run(),
             "client initialized"
         );
 
@@ -924,7 +1018,8 @@ pub enum ClientEnvironment {
     /// `https://api.tradestation.com/v3`
     ///
     /// NOTE: Transactional requests, such as order placement, can affect real
-    /// brokerage accounts and real money.
+    /// brokerage accounts and real money. Orders are refused unless the client
+    /// is built with [`ClientBuilderStep::enable_live_trading`].
     Live,
 
     /// The TradeStation simulator environment.
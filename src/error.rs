@@ -70,6 +70,112 @@ pub enum Error {
     /// Order Group Type not set when it's required.
     OrderGroupTypeNotSet,
 
+    /// Expiration not set when one was required.
+    ExpirationNotSet,
+
+    /// Option Type not set when one was required.
+    OptionTypeNotSet,
+
+    /// Strike Price not set when one was required.
+    StrikePriceNotSet,
+
+    /// Opening another stream would exceed the max amount of concurrent streams.
+    StreamLimitReached(usize),
+
+    /// A subscriber fell too far behind a shared stream and was disconnected.
+    StreamLagged(u64),
+
+    /// A value that was expected to be a number couldn't be parsed as one.
+    InvalidNumber(String),
+
+    /// A value that was expected to be a timestamp couldn't be parsed as one.
+    InvalidTimestamp(String),
+
+    /// An option type which can't be used, for example `All` when pricing an option.
+    InvalidOptionType(String),
+
+    /// Couldn't solve for an implied volatility matching the option price.
+    ImpliedVolatilityNotFound(String),
+
+    /// A value that was expected to be an option symbol couldn't be parsed as one.
+    InvalidOptionSymbol(String),
+
+    /// A value that was expected to be a futures contract symbol couldn't be parsed as one.
+    InvalidFuturesSymbol(String),
+
+    /// A futures contract has none of the dates needed to schedule it's roll.
+    RollDateNotFound(String),
+
+    /// A price isn't on a valid tick (minimum price movement) of the symbol.
+    PriceNotOnTick(String),
+
+    /// A symbol's price format is missing or has an invalid increment, decimals, or fraction.
+    InvalidPriceFormat(String),
+
+    /// The legs of an options trade don't share the same underlying or expiration.
+    MismatchedOptionLegs(String),
+
+    /// A quantity is too large, like a spread's quantity times the ratio of one of it's legs.
+    QuantityTooLarge(String),
+
+    /// A bracket order's entry and exit orders don't fit together.
+    InvalidBracketOrder(String),
+
+    /// The orders of an [`crate::execution::OrderRequestGroup`] break the rules of it's group type.
+    InvalidOrderGroup(String),
+
+    /// A market or time activation rule is incomplete, or uses an unsupported trigger key.
+    InvalidActivationRule(String),
+
+    /// An order's advanced options conflict with each other, or the order.
+    InvalidAdvancedOptions(String),
+
+    /// An order was blocked by the [`crate::execution::RiskPolicy`] attached to the client.
+    RiskViolation(crate::execution::RiskViolation),
+
+    /// A live [`crate::Client`] tried sending an order without being built
+    /// with [`crate::client::ClientBuilderStep::enable_live_trading`].
+    LiveTradingNotEnabled,
+
+    /// A live [`crate::Client`] tried sending an order for an account it
+    /// wasn't built with in [`crate::client::ClientBuilderStep::live_trading_accounts`].
+    LiveTradingAccountNotAllowed(String),
+
+    /// The [`crate::Client`]'s kill switch is engaged, blocking every new order.
+    KillSwitchEngaged,
+
+    /// An order submission failed, and the order was confirmed to not be placed.
+    ///
+    /// NOTE: Retrying the same order reuses it's `OrderConfirmID`,
+    /// so TradeStation rejects it if the failed submission shows up late.
+    OrderNotPlaced(String),
+
+    /// An order submission failed, and it couldn't be confirmed whether the order was placed.
+    OrderOutcomeUnknown(String),
+
+    /// An order wasn't filled, rejected, canceled, or expired before the timeout waiting for it.
+    OrderWaitTimeout(String),
+
+    /// A dry-run [`crate::orders::OrderTicket`] was followed, but it's order
+    /// was only confirmed, so it never shows up on the order stream.
+    DryRunTicket(String),
+
+    /// An order went from one status to another it can't go to, like a filled order being reopened.
+    InvalidOrderTransition(String),
+
+    /// An emergency control didn't close a position, since an open
+    /// order in it's symbol couldn't be canceled in time.
+    PositionCloseSkipped(String),
+
+    /// A value that was expected to be a crypto pair symbol couldn't be parsed as one.
+    InvalidCryptoSymbol(String),
+
+    /// TradeStation didn't return any details for a symbol.
+    SymbolNotFound(String),
+
+    /// The request fetching a symbol's details failed.
+    SymbolDetailsUnavailable(String),
+
     /// TradeStation API Error for a bad request
     BadRequest(String),
 
@@ -134,6 +240,127 @@ impl std::fmt::Display for Error {
             Self::IoError(e) => write!(f, "Issue reading stream: {e}"),
             Self::SymbolNotSet => write!(f, "ERROR: You need to set the symbol."),
             Self::OptionLegsNotSet => write!(f, "ERROR: You need to set the option legs."),
+            Self::StreamLimitReached(max_streams) => {
+                write!(
+                    f,
+                    "ERROR: This would exceed the max of {max_streams} concurrent streams."
+                )
+            }
+            Self::StreamLagged(missed) => write!(
+                f,
+                "ERROR: Disconnected from shared stream after falling behind by {missed} events."
+            ),
+            Self::InvalidNumber(value) => {
+                write!(f, "ERROR: Couldn't parse `{value}` as a number.")
+            }
+            Self::InvalidTimestamp(value) => {
+                write!(f, "ERROR: Couldn't parse `{value}` as a timestamp.")
+            }
+            Self::InvalidOptionType(option_type) => {
+                write!(f, "ERROR: `{option_type}` is not a valid option type here.")
+            }
+            Self::ImpliedVolatilityNotFound(reason) => {
+                write!(f, "ERROR: Couldn't solve for implied volatility: {reason}")
+            }
+            Self::InvalidOptionSymbol(symbol) => {
+                write!(f, "ERROR: Couldn't parse `{symbol}` as an option symbol.")
+            }
+            Self::InvalidFuturesSymbol(symbol) => {
+                write!(
+                    f,
+                    "ERROR: Couldn't parse `{symbol}` as a futures contract symbol."
+                )
+            }
+            Self::RollDateNotFound(contract) => {
+                write!(
+                    f,
+                    "ERROR: Couldn't find a date to schedule the roll of `{contract}`."
+                )
+            }
+            Self::PriceNotOnTick(price) => {
+                write!(f, "ERROR: The price {price} isn't on a valid tick.")
+            }
+            Self::InvalidPriceFormat(reason) => {
+                write!(f, "ERROR: Invalid price format: {reason}")
+            }
+            Self::MismatchedOptionLegs(reason) => {
+                write!(f, "ERROR: The option legs don't match: {reason}")
+            }
+            Self::QuantityTooLarge(reason) => {
+                write!(f, "ERROR: Quantity is too large: {reason}")
+            }
+            Self::InvalidBracketOrder(reason) => {
+                write!(f, "ERROR: Invalid bracket order: {reason}")
+            }
+            Self::InvalidOrderGroup(reason) => {
+                write!(f, "ERROR: Invalid order group: {reason}")
+            }
+            Self::InvalidActivationRule(reason) => {
+                write!(f, "ERROR: Invalid activation rule: {reason}")
+            }
+            Self::InvalidAdvancedOptions(reason) => {
+                write!(f, "ERROR: Invalid advanced order options: {reason}")
+            }
+            Self::RiskViolation(violation) => {
+                write!(f, "ERROR: Order blocked by risk policy: {violation}")
+            }
+            Self::LiveTradingNotEnabled => {
+                write!(
+                    f,
+                    "ERROR: Live trading isn't enabled, build the client with `enable_live_trading()` to send live orders."
+                )
+            }
+            Self::LiveTradingAccountNotAllowed(account_id) => {
+                write!(
+                    f,
+                    "ERROR: Live trading isn't enabled for the account `{account_id}`."
+                )
+            }
+            Self::KillSwitchEngaged => {
+                write!(
+                    f,
+                    "ERROR: The kill switch is engaged, no new orders can be sent."
+                )
+            }
+            Self::OrderNotPlaced(reason) => {
+                write!(f, "ERROR: Order wasn't placed: {reason}")
+            }
+            Self::OrderOutcomeUnknown(reason) => {
+                write!(
+                    f,
+                    "ERROR: Couldn't confirm whether the order was placed: {reason}"
+                )
+            }
+            Self::OrderWaitTimeout(reason) => {
+                write!(f, "ERROR: Timed out waiting for the order: {reason}")
+            }
+            Self::DryRunTicket(order_confirm_id) => {
+                write!(
+                    f,
+                    "ERROR: `{order_confirm_id}` is a dry-run ticket, it's order was only confirmed."
+                )
+            }
+            Self::InvalidOrderTransition(reason) => {
+                write!(f, "ERROR: Impossible order status transition: {reason}")
+            }
+            Self::PositionCloseSkipped(reason) => {
+                write!(f, "ERROR: Position wasn't closed: {reason}")
+            }
+            Self::InvalidCryptoSymbol(symbol) => {
+                write!(
+                    f,
+                    "ERROR: Couldn't parse `{symbol}` as a crypto pair symbol."
+                )
+            }
+            Self::SymbolNotFound(symbol) => {
+                write!(
+                    f,
+                    "ERROR: Couldn't find any details for the symbol `{symbol}`."
+                )
+            }
+            Self::SymbolDetailsUnavailable(reason) => {
+                write!(f, "ERROR: Couldn't fetch symbol details: {reason}")
+            }
             Self::BadRequest(msg) => write!(f, "TradeStation API ERROR: {msg}"),
             Self::Unauthorized(msg) => write!(f, "TradeStation API ERROR: {msg}"),
             Self::Forbidden(msg) => write!(f, "TradeStation API ERROR: {msg}"),
@@ -156,6 +383,11 @@ impl std::fmt::Display for Error {
             Self::OrderGroupTypeNotSet => {
                 write!(f, "ERROR: order group type not set when it's required.")
             }
+            Self::ExpirationNotSet => write!(f, "ERROR: expiration not set when it's required."),
+            Self::OptionTypeNotSet => write!(f, "ERROR: option_type not set when it's required."),
+            Self::StrikePriceNotSet => {
+                write!(f, "ERROR: strike_price not set when it's required.")
+            }
             Self::EnvironmentNotSet => {
                 write!(f, "ERROR: `environment` is required for `Client`.")
             }
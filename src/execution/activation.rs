@@ -0,0 +1,236 @@
+//! Build the market and time activation rules of conditional orders, which
+//! hold an order until the rules trigger, then send it to the market.
+//!
+//! Attach the rules to an order with [`crate::execution::AdvancedOrderOptions`].
+//!
+//! ```rust
+//! use std::time::{Duration, UNIX_EPOCH};
+//! use tradestation::{
+//!     accounting::{orders::TimeActivationRule, LogicOp, Predicate},
+//!     execution::{ActivationTriggerKey, AdvancedOrderOptions, MarketActivationRuleBuilder},
+//! };
+//!
+//! // Activate once SPY trades above $600, or QQQ trades above $520 on 2 consecutive ticks
+//! let rules = MarketActivationRuleBuilder::new()
+//!     .rule("SPY", Predicate::Gt, "600.00")
+//!     .or("QQQ", Predicate::Gt, "520.00")
+//!     .trigger_key(ActivationTriggerKey::DTT)
+//!     .build()
+//!     .unwrap();
+//! assert_eq!(rules[0].logic_operator, None);
+//! assert_eq!(rules[1].logic_operator, Some(LogicOp::Or));
+//! assert_eq!(rules[1].trigger_key, ActivationTriggerKey::DTT);
+//!
+//! // ... but not before 2024-12-20 14:30:00 UTC
+//! let time = TimeActivationRule::at(UNIX_EPOCH + Duration::from_secs(1_734_705_000));
+//! assert_eq!(time.time_utc, "2024-12-20T14:30:00Z");
+//! assert_eq!(
+//!     TimeActivationRule::parse("2024-12-20T09:30:00-05:00").unwrap(),
+//!     time
+//! );
+//!
+//! let options = AdvancedOrderOptions {
+//!     market_activation_rules: rules,
+//!     time_activation_rules: vec![time],
+//!     ..Default::default()
+//! };
+//! ```
+
+use crate::{
+    accounting::orders::{
+        ActivationRuleType, LogicOp, MarketActivationRule, Predicate, TimeActivationRule,
+    },
+    execution::trigger::ActivationTriggerKey,
+    utils::{format_timestamp, parse_number, parse_timestamp},
+    Client, Error,
+};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+impl TimeActivationRule {
+    /// Create a rule activating the order at a point in time.
+    ///
+    /// NOTE: Times before the unix epoch are clamped to it.
+    pub fn at(time: SystemTime) -> Self {
+        let epoch_seconds = time
+            .duration_since(UNIX_EPOCH)
+            .map(|duration| duration.as_secs() as i64)
+            .unwrap_or_default();
+
+        Self::from_epoch_seconds(epoch_seconds)
+    }
+
+    /// Create a rule activating the order at unix epoch seconds.
+    pub fn from_epoch_seconds(epoch_seconds: i64) -> Self {
+        TimeActivationRule {
+            time_utc: format_timestamp(epoch_seconds),
+        }
+    }
+
+    /// Create a rule activating the order at an RFC3339 timestamp,
+    /// like `"2024-12-20T09:30:00-05:00"`, converted into UTC.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::InvalidTimestamp`] if the timestamp can't be parsed.
+    pub fn parse(timestamp: &str) -> Result<Self, Error> {
+        parse_timestamp(timestamp).map(Self::from_epoch_seconds)
+    }
+
+    /// The unix epoch seconds the rule activates the order at.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::InvalidTimestamp`] if the rule's timestamp can't be parsed.
+    pub fn epoch_seconds(&self) -> Result<i64, Error> {
+        parse_timestamp(&self.time_utc)
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+/// A builder pattern for chaining [`MarketActivationRule`]'s together.
+///
+/// Start with `rule`, then chain more rules with `and` or `or`. Setting
+/// the trigger key applies to the last added rule.
+///
+/// NOTE: Rules use the [`ActivationTriggerKey::STT`] trigger key by default.
+pub struct MarketActivationRuleBuilder {
+    rules: Vec<MarketActivationRule>,
+    error: Option<String>,
+}
+impl MarketActivationRuleBuilder {
+    /// Initialize a new builder for [`MarketActivationRule`]'s.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add the first rule, activating when the symbol's price matches the predicate.
+    pub fn rule(
+        self,
+        symbol: impl Into<String>,
+        predicate: Predicate,
+        price: impl Into<String>,
+    ) -> Self {
+        if !self.rules.is_empty() {
+            return self.invalid("only the first rule can be added with `rule`, use `and` or `or`");
+        }
+
+        self.push(None, symbol.into(), predicate, price.into())
+    }
+
+    /// Add a rule which also has to match for the order to activate.
+    pub fn and(
+        self,
+        symbol: impl Into<String>,
+        predicate: Predicate,
+        price: impl Into<String>,
+    ) -> Self {
+        self.push(Some(LogicOp::And), symbol.into(), predicate, price.into())
+    }
+
+    /// Add a rule which can match instead of the previous rules, for the order to activate.
+    pub fn or(
+        self,
+        symbol: impl Into<String>,
+        predicate: Predicate,
+        price: impl Into<String>,
+    ) -> Self {
+        self.push(Some(LogicOp::Or), symbol.into(), predicate, price.into())
+    }
+
+    /// Set the trigger key of the last added rule.
+    pub fn trigger_key(mut self, trigger_key: ActivationTriggerKey) -> Self {
+        match self.rules.last_mut() {
+            Some(rule) => rule.trigger_key = trigger_key,
+            None => return self.invalid("add a rule before setting it's trigger key"),
+        }
+
+        self
+    }
+
+    /// Finish building the [`MarketActivationRule`]'s.
+    ///
+    /// # Errors
+    ///
+    /// - [`Error::InvalidActivationRule`] if there's no rules, a symbol is empty,
+    ///   or the rules were chained out of order.
+    /// - [`Error::InvalidNumber`] if a price can't be parsed.
+    pub fn build(self) -> Result<Vec<MarketActivationRule>, Error> {
+        if let Some(reason) = self.error {
+            return Err(Error::InvalidActivationRule(reason));
+        }
+        if self.rules.is_empty() {
+            return Err(Error::InvalidActivationRule(
+                "at least 1 rule is required".into(),
+            ));
+        }
+        for rule in &self.rules {
+            if rule.symbol.trim().is_empty() {
+                return Err(Error::InvalidActivationRule(
+                    "every rule needs a symbol".into(),
+                ));
+            }
+            if let Some(price) = &rule.price {
+                parse_number(price)?;
+            }
+        }
+
+        Ok(self.rules)
+    }
+
+    /// Finish building the [`MarketActivationRule`]'s, validating every
+    /// trigger key is one of the [`Client::get_activation_triggers`].
+    ///
+    /// # Errors
+    ///
+    /// Same as [`MarketActivationRuleBuilder::build`], and returns
+    /// [`Error::InvalidActivationRule`] if a trigger key isn't supported.
+    pub async fn build_validated(
+        self,
+        client: &Client,
+    ) -> Result<Vec<MarketActivationRule>, Error> {
+        let rules = self.build()?;
+        let triggers = client.get_activation_triggers().await?;
+
+        if let Some(rule) = rules.iter().find(|rule| {
+            !triggers
+                .iter()
+                .any(|trigger| trigger.key == rule.trigger_key)
+        }) {
+            return Err(Error::InvalidActivationRule(format!(
+                "the trigger key {:?} for {} isn't supported",
+                rule.trigger_key, rule.symbol
+            )));
+        }
+
+        Ok(rules)
+    }
+
+    /// Add a rule, chained to the previous rule by the logic operator.
+    fn push(
+        mut self,
+        logic_operator: Option<LogicOp>,
+        symbol: String,
+        predicate: Predicate,
+        price: String,
+    ) -> Self {
+        if logic_operator.is_some() && self.rules.is_empty() {
+            return self.invalid("the first rule has to be added with `rule`");
+        }
+
+        self.rules.push(MarketActivationRule {
+            rule_type: ActivationRuleType::Price,
+            symbol,
+            predicate,
+            trigger_key: ActivationTriggerKey::STT,
+            price: Some(price),
+            logic_operator,
+        });
+        self
+    }
+
+    /// Record the first mistake made while chaining, returned when building.
+    fn invalid(mut self, reason: &str) -> Self {
+        self.error.get_or_insert_with(|| reason.to_string());
+        self
+    }
+}
@@ -0,0 +1,494 @@
+//! Build bracket [`OrderRequest`]'s, an entry order which sends a take profit
+//! and a stop loss order once filled, where either exit cancels the other.
+//!
+//! The exit prices can be absolute prices, or offsets from the entry price
+//! in ticks or percent (see [`BracketPrice`]). Offsets move the take profit
+//! in the profitable direction and the stop loss in the losing direction
+//! of the entry's [`TradeAction`].
+//!
+//! ```rust
+//! use tradestation::{
+//!     accounting::OrderType,
+//!     execution::{BracketOrderBuilder, BracketPrice, Duration, OrderTimeInForce, TradeAction},
+//!     orders::OrderRelationship,
+//! };
+//!
+//! // Buy 10 MSFT at $420.00, taking profit 2% higher and stopping out at $410.00
+//! let order_req = BracketOrderBuilder::new()
+//!     .account_id("11111111")
+//!     .symbol("MSFT")
+//!     .trade_action(TradeAction::Buy)
+//!     .quantity("10")
+//!     .order_type(OrderType::Limit)
+//!     .limit_price("420.00")
+//!     .take_profit(BracketPrice::Percent(2.0))
+//!     .stop_loss(BracketPrice::Price("410.00".into()))
+//!     .time_in_force(OrderTimeInForce {
+//!         duration: Duration::DAY,
+//!         expiration: None,
+//!     })
+//!     .build()
+//!     .unwrap();
+//!
+//! let bracket = &order_req.osos.as_ref().unwrap()[0];
+//! assert!(matches!(bracket.r#type, OrderRelationship::BRK));
+//! assert_eq!(bracket.orders[0].limit_price.as_deref(), Some("428.4"));
+//! assert_eq!(bracket.orders[1].stop_price.as_deref(), Some("410.00"));
+//! assert_eq!(bracket.orders[1].trade_action, Some(TradeAction::Sell));
+//!
+//! // A long position's stop loss has to be below the entry
+//! assert!(BracketOrderBuilder::new()
+//!     .account_id("11111111")
+//!     .symbol("MSFT")
+//!     .trade_action(TradeAction::Buy)
+//!     .quantity("10")
+//!     .order_type(OrderType::Limit)
+//!     .limit_price("420.00")
+//!     .stop_loss(BracketPrice::Price("430.00".into()))
+//!     .time_in_force(OrderTimeInForce {
+//!         duration: Duration::DAY,
+//!         expiration: None,
+//!     })
+//!     .build()
+//!     .is_err());
+//! ```
+
+use crate::{
+    accounting::orders::{OrderRelationship, OrderType, TrailingStop},
+    execution::{
+        orders::{AdvancedOrderOptionsBuilder, OrderTimeInForce, Oso, TradeAction},
+        request::{OrderRequest, OrderRequestBuilder},
+    },
+    market_data::{PriceFormat, RoundingMode},
+    utils::{format_number, parse_number},
+    Error,
+};
+
+#[derive(Clone, Debug)]
+/// The price of a bracket's take profit or stop loss order.
+pub enum BracketPrice {
+    /// An absolute price, like `"420.50"`.
+    Price(String),
+
+    /// An amount of ticks away from the entry price.
+    ///
+    /// NOTE: Requires the symbol's [`PriceFormat`] for it's tick size.
+    Ticks(u32),
+
+    /// A percent away from the entry price, where `2.0` is 2%.
+    Percent(f64),
+}
+
+#[derive(Clone, Debug, Default)]
+/// A builder pattern for bracket [`OrderRequest`]'s.
+///
+/// The take profit is a `Limit` order and the stop loss is a `StopMarket`
+/// order (or a trailing stop), both sent as a [`OrderRelationship::BRK`]
+/// [`Oso`] of the entry order, closing the same quantity of the same symbol.
+///
+/// NOTE: The entry price is the limit price of `Limit` and `StopLimit` orders,
+/// and the stop price of `StopMarket` orders. Set a `reference_price` to use
+/// offsets with `Market` orders.
+pub struct BracketOrderBuilder {
+    account_id: Option<String>,
+    symbol: Option<String>,
+    trade_action: Option<TradeAction>,
+    quantity: Option<String>,
+    order_type: Option<OrderType>,
+    limit_price: Option<String>,
+    stop_price: Option<String>,
+    reference_price: Option<String>,
+    take_profit: Option<BracketPrice>,
+    stop_loss: Option<BracketPrice>,
+    trailing_stop: Option<TrailingStop>,
+    time_in_force: Option<OrderTimeInForce>,
+    exit_time_in_force: Option<OrderTimeInForce>,
+    price_format: Option<PriceFormat>,
+    route: Option<String>,
+}
+impl BracketOrderBuilder {
+    /// Initialize a new builder for a bracket [`OrderRequest`].
+    pub fn new() -> Self {
+        BracketOrderBuilder::default()
+    }
+
+    /// Set the Account ID the [`OrderRequest`] belongs to.
+    ///
+    /// NOTE: Required to be set to build an [`OrderRequest`].
+    pub fn account_id(mut self, id: impl Into<String>) -> Self {
+        self.account_id = Some(id.into());
+        self
+    }
+
+    /// Set the Symbol the bracket is for.
+    ///
+    /// NOTE: Required to be set to build an [`OrderRequest`].
+    pub fn symbol(mut self, symbol: impl Into<String>) -> Self {
+        self.symbol = Some(symbol.into());
+        self
+    }
+
+    /// Set the Trade Action of the entry order, the exit orders use the opposite action.
+    ///
+    /// NOTE: Required to be set, and has to be an opening trade action.
+    pub fn trade_action(mut self, action: TradeAction) -> Self {
+        self.trade_action = Some(action);
+        self
+    }
+
+    /// Set the Quantity of shares or contracts, for the entry and both exits.
+    ///
+    /// NOTE: Required to be set to build an [`OrderRequest`].
+    pub fn quantity(mut self, quantity: impl Into<String>) -> Self {
+        self.quantity = Some(quantity.into());
+        self
+    }
+
+    /// Set the Order Type of the entry order.
+    ///
+    /// NOTE: Required to be set to build an [`OrderRequest`].
+    pub fn order_type(mut self, order_type: OrderType) -> Self {
+        self.order_type = Some(order_type);
+        self
+    }
+
+    /// Set the Limit Price of the entry order.
+    pub fn limit_price(mut self, price: impl Into<String>) -> Self {
+        self.limit_price = Some(price.into());
+        self
+    }
+
+    /// Set the Stop Price of the entry order.
+    pub fn stop_price(mut self, price: impl Into<String>) -> Self {
+        self.stop_price = Some(price.into());
+        self
+    }
+
+    /// Set the price offsets are from for `Market` entry orders,
+    /// for example the last price of the symbol.
+    pub fn reference_price(mut self, price: impl Into<String>) -> Self {
+        self.reference_price = Some(price.into());
+        self
+    }
+
+    /// Set the price of the take profit order.
+    pub fn take_profit(mut self, price: BracketPrice) -> Self {
+        self.take_profit = Some(price);
+        self
+    }
+
+    /// Set the price of the stop loss order.
+    ///
+    /// NOTE: Mutually exclusive with `trailing_stop`.
+    pub fn stop_loss(mut self, price: BracketPrice) -> Self {
+        self.stop_loss = Some(price);
+        self
+    }
+
+    /// Use a trailing stop, by amount or percent, as the stop loss order.
+    ///
+    /// NOTE: Mutually exclusive with `stop_loss`.
+    pub fn trailing_stop(mut self, trailing_stop: TrailingStop) -> Self {
+        self.trailing_stop = Some(trailing_stop);
+        self
+    }
+
+    /// Set the Time In Force (duration or expiration timestamp) of the entry order.
+    ///
+    /// NOTE: Required to be set to build an [`OrderRequest`].
+    pub fn time_in_force(mut self, time_in_force: OrderTimeInForce) -> Self {
+        self.time_in_force = Some(time_in_force);
+        self
+    }
+
+    /// Set the Time In Force of the exit orders.
+    ///
+    /// NOTE: Defaults to the Time In Force of the entry order.
+    pub fn exit_time_in_force(mut self, time_in_force: OrderTimeInForce) -> Self {
+        self.exit_time_in_force = Some(time_in_force);
+        self
+    }
+
+    /// Set the [`PriceFormat`] of the symbol, from it's [`crate::market_data::SymbolDetails`],
+    /// to round offset prices onto a valid tick, and validate every price when building.
+    ///
+    /// NOTE: Required for [`BracketPrice::Ticks`] offsets.
+    pub fn price_format(mut self, price_format: PriceFormat) -> Self {
+        self.price_format = Some(price_format);
+        self
+    }
+
+    /// Set the Execution Route for every order in the bracket.
+    pub fn route(mut self, route: impl Into<String>) -> Self {
+        self.route = Some(route.into());
+        self
+    }
+
+    /// Finish building the bracket into an [`OrderRequest`].
+    ///
+    /// NOTE: `account_id`, `symbol`, `trade_action`, `quantity`, `order_type`,
+    /// and `time_in_force` are all required, along with a take profit or stop loss.
+    ///
+    /// # Errors
+    ///
+    /// - [`Error::InvalidBracketOrder`] if there's no exits, both a stop loss and
+    ///   trailing stop, a closing entry trade action, an offset without an entry
+    ///   price, or the exit prices are on the wrong side of the entry price.
+    /// - [`Error::InvalidPriceFormat`] if a tick offset is used without a [`PriceFormat`].
+    /// - [`Error::PriceNotOnTick`] if a [`PriceFormat`] is set, and a price isn't on a valid tick.
+    pub fn build(self) -> Result<OrderRequest, Error> {
+        let account_id = self.account_id.ok_or(Error::AccountIdNotSet)?;
+        let symbol = self.symbol.ok_or(Error::SymbolNotSet)?;
+        let trade_action = self.trade_action.ok_or(Error::TradeActionNotSet)?;
+        let quantity = self.quantity.ok_or(Error::QuantityNotSet)?;
+        let order_type = self.order_type.ok_or(Error::OrderTypeNotSet)?;
+        let time_in_force = self.time_in_force.ok_or(Error::TimeInForceNotSet)?;
+        let exit_time_in_force = self
+            .exit_time_in_force
+            .unwrap_or_else(|| time_in_force.clone());
+
+        if self.take_profit.is_none() && self.stop_loss.is_none() && self.trailing_stop.is_none() {
+            return Err(Error::InvalidBracketOrder(
+                "a take profit or stop loss is required".into(),
+            ));
+        }
+        if self.stop_loss.is_some() && self.trailing_stop.is_some() {
+            return Err(Error::InvalidBracketOrder(
+                "a stop loss and trailing stop can't both be set".into(),
+            ));
+        }
+
+        let exit_action = exit_trade_action(&trade_action)?;
+        let long = is_long(&trade_action);
+        let entry_price = match order_type {
+            OrderType::Limit | OrderType::StopLimit => self.limit_price.as_ref(),
+            OrderType::StopMarket => self.stop_price.as_ref(),
+            OrderType::Market => None,
+        }
+        .or(self.reference_price.as_ref())
+        .map(|price| parse_number(price))
+        .transpose()?;
+
+        let exit_price = |price: &BracketPrice, profit: bool| -> Result<String, Error> {
+            let offset = match price {
+                BracketPrice::Price(price) => return Ok(price.clone()),
+                BracketPrice::Ticks(ticks) => (*ticks as f64, true),
+                BracketPrice::Percent(percent) => (*percent, false),
+            };
+            let entry = entry_price.ok_or_else(|| {
+                Error::InvalidBracketOrder(
+                    "offsets need an entry price, or a reference price for market orders".into(),
+                )
+            })?;
+            let offset = match (offset, &self.price_format) {
+                ((ticks, true), Some(price_format)) => ticks * price_format.tick_size(entry)?,
+                ((_, true), None) => {
+                    return Err(Error::InvalidPriceFormat(
+                        "a price format is required for tick offsets".into(),
+                    ))
+                }
+                ((percent, false), _) => entry * percent / 100.0,
+            };
+            let price = if profit == long {
+                entry + offset
+            } else {
+                entry - offset
+            };
+
+            Ok(match &self.price_format {
+                Some(price_format) => {
+                    format_number(price_format.round_price(price, RoundingMode::Nearest)?)
+                }
+                None => format_number(price),
+            })
+        };
+        let take_profit = self
+            .take_profit
+            .as_ref()
+            .map(|price| exit_price(price, true))
+            .transpose()?;
+        let stop_loss = self
+            .stop_loss
+            .as_ref()
+            .map(|price| exit_price(price, false))
+            .transpose()?;
+
+        // Make sure the exits are on the correct side of the entry, and each other
+        let take_profit_value = take_profit.as_deref().map(parse_number).transpose()?;
+        let stop_loss_value = stop_loss.as_deref().map(parse_number).transpose()?;
+        let above = |high: Option<f64>, low: Option<f64>| match (high, low) {
+            (Some(high), Some(low)) => high > low,
+            _ => true,
+        };
+        let (target_ok, stop_ok, range_ok) = if long {
+            (
+                above(take_profit_value, entry_price),
+                above(entry_price, stop_loss_value),
+                above(take_profit_value, stop_loss_value),
+            )
+        } else {
+            (
+                above(entry_price, take_profit_value),
+                above(stop_loss_value, entry_price),
+                above(stop_loss_value, take_profit_value),
+            )
+        };
+        let side = if long { "long" } else { "short" };
+        if !target_ok || !range_ok {
+            return Err(Error::InvalidBracketOrder(format!(
+                "the take profit of a {side} position must be {} the entry and stop loss",
+                if long { "above" } else { "below" }
+            )));
+        }
+        if !stop_ok {
+            return Err(Error::InvalidBracketOrder(format!(
+                "the stop loss of a {side} position must be {} the entry",
+                if long { "below" } else { "above" }
+            )));
+        }
+
+        let exit = |order_type: OrderType| {
+            let mut builder = OrderRequestBuilder::new()
+                .account_id(account_id.clone())
+                .symbol(symbol.clone())
+                .trade_action(exit_action.clone())
+                .quantity(quantity.clone())
+                .order_type(order_type)
+                .time_in_force(exit_time_in_force.clone());
+            if let Some(route) = &self.route {
+                builder = builder.route(route.clone());
+            }
+            if let Some(price_format) = &self.price_format {
+                builder = builder.price_format(price_format.clone());
+            }
+
+            builder
+        };
+
+        let mut exits = Vec::new();
+        if let Some(price) = take_profit {
+            exits.push(exit(OrderType::Limit).limit_price(price).build()?);
+        }
+        if let Some(price) = stop_loss {
+            exits.push(exit(OrderType::StopMarket).stop_price(price).build()?);
+        }
+        if let Some(trailing_stop) = self.trailing_stop {
+            exits.push(
+                exit(OrderType::StopMarket)
+                    .advanced_options(
+                        AdvancedOrderOptionsBuilder::new()
+                            .trailing_stop(trailing_stop)
+                            .build()?,
+                    )
+                    .build()?,
+            );
+        }
+
+        // A single exit is just sent by the entry, there's nothing for it to cancel
+        let relationship = if exits.len() > 1 {
+            OrderRelationship::BRK
+        } else {
+            OrderRelationship::NORMAL
+        };
+
+        let mut builder = OrderRequestBuilder::new()
+            .account_id(account_id)
+            .symbol(symbol)
+            .trade_action(trade_action)
+            .quantity(quantity)
+            .order_type(order_type)
+            .time_in_force(time_in_force)
+            .osos(vec![Oso {
+                orders: exits,
+                r#type: relationship,
+            }]);
+        if let Some(price) = self.limit_price {
+            builder = builder.limit_price(price);
+        }
+        if let Some(price) = self.stop_price {
+            builder = builder.stop_price(price);
+        }
+        if let Some(route) = self.route {
+            builder = builder.route(route);
+        }
+        if let Some(price_format) = self.price_format {
+            builder = builder.price_format(price_format);
+        }
+
+        let order_req = builder.build()?;
+        order_req.validate_bracket()?;
+
+        Ok(order_req)
+    }
+}
+
+impl OrderRequest {
+    /// Validate the orders sent by this order (it's [`Oso`]'s) close the
+    /// position it opens, with the same account, symbol, and quantity,
+    /// and the opposite trade action.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::InvalidBracketOrder`] if this isn't a single symbol
+    /// opening order, or an exit order doesn't match it.
+    pub fn validate_bracket(&self) -> Result<(), Error> {
+        let invalid = |reason: String| Err(Error::InvalidBracketOrder(reason));
+
+        let (Some(symbol), Some(quantity)) = (&self.symbol, &self.quantity) else {
+            return invalid("the entry order needs a symbol and quantity, not legs".into());
+        };
+        let trade_action = self.trade_action.as_ref().ok_or(Error::TradeActionNotSet)?;
+        let exit_action = exit_trade_action(trade_action)?;
+        let quantity_value = parse_number(quantity)?;
+
+        for order in self.osos.iter().flatten().flat_map(|oso| &oso.orders) {
+            if order.account_id != self.account_id {
+                return invalid(format!(
+                    "exit account {} doesn't match entry account {}",
+                    order.account_id, self.account_id
+                ));
+            }
+            if order.symbol.as_ref() != Some(symbol) {
+                return invalid(format!(
+                    "exit symbol {:?} doesn't match entry symbol {symbol}",
+                    order.symbol
+                ));
+            }
+            match order.quantity.as_deref().map(parse_number).transpose()? {
+                Some(exit_quantity) if exit_quantity == quantity_value => {}
+                exit_quantity => {
+                    return invalid(format!(
+                        "exit quantity {exit_quantity:?} doesn't match entry quantity {quantity}"
+                    ))
+                }
+            }
+            if order.trade_action.as_ref() != Some(&exit_action) {
+                return invalid(format!(
+                    "exit trade action {:?} doesn't close a {trade_action:?} entry, expected {exit_action:?}",
+                    order.trade_action
+                ));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Whether an opening trade action opens a long position.
+fn is_long(trade_action: &TradeAction) -> bool {
+    matches!(trade_action, TradeAction::Buy | TradeAction::BuyToOpen)
+}
+
+/// The trade action closing the position an opening trade action opens.
+pub(crate) fn exit_trade_action(trade_action: &TradeAction) -> Result<TradeAction, Error> {
+    match trade_action {
+        TradeAction::Buy => Ok(TradeAction::Sell),
+        TradeAction::Sell => Ok(TradeAction::Buy),
+        TradeAction::SellShort => Ok(TradeAction::BuyToCover),
+        TradeAction::BuyToOpen => Ok(TradeAction::SellToClose),
+        TradeAction::SellToOpen => Ok(TradeAction::BuyToClose),
+        closing => Err(Error::InvalidBracketOrder(format!(
+            "{closing:?} closes a position, the entry has to open one"
+        ))),
+    }
+}
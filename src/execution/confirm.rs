@@ -1,14 +1,16 @@
 use crate::{
     accounting::orders::{AssetType, Order, TrailingStop},
     execution::{
-        orders::{OrderRequestLeg, OrderTimeInForce, PegValue},
+        orders::{BPWarningStatus, OrderRequestLeg, OrderTimeInForce, PegValue},
         request::{OrderRequest, OrderRequestGroup},
+        ticket::OrderTicket,
     },
     market_data::OptionSpreadType,
     responses::{
         execution::{ConfirmOrderResp, ConfirmOrderRespRaw},
         ApiResponse,
     },
+    utils::parse_number,
     Client, Error,
 };
 use serde::{Deserialize, Serialize};
@@ -293,6 +295,9 @@ impl Order {
     /// a bracket order as one transaction, instead you must update each order within
     /// a bracket.
     ///
+    /// NOTE: The group is validated with [`OrderRequestGroup::validate`] before it's
+    /// sent, returning [`Error::InvalidOrderGroup`] if it breaks the rules of it's type.
+    ///
     /// # Example
     /// ---
     /// Confirm a trade involving a bracket group of orders with one order
@@ -384,6 +389,7 @@ impl Order {
         client: &Client,
         order_req_group: &OrderRequestGroup,
     ) -> Result<Vec<OrderConfirmation>, Error> {
+        order_req_group.validate()?;
         let endpoint = String::from("orderexecution/ordergroupconfirm");
 
         match client
@@ -603,3 +609,198 @@ impl OrderRequestGroup {
         Order::confirm_group(client, &self).await
     }
 }
+
+impl OrderRequest {
+    /// Confirm the [`OrderRequest`], returning a [`ConfirmedOrder`] which
+    /// can be reviewed, then placed exactly once with the `OrderConfirmID`
+    /// TradeStation returned.
+    ///
+    /// # Example
+    /// ---
+    ///
+    /// ```rust,no_run
+    /// # use tradestation::{Client, Error};
+    /// # use tradestation::orders::{
+    /// #     BPWarningStatus, Duration, OrderRequestBuilder, OrderTimeInForce, OrderType,
+    /// #     TradeAction,
+    /// # };
+    /// # async fn example(client: &Client) -> Result<(), Error> {
+    /// let order_req = OrderRequestBuilder::new()
+    ///     .account_id("YOUR_EQUITIES_ACCOUNT_ID")
+    ///     .symbol("MSFT")
+    ///     .trade_action(TradeAction::Buy)
+    ///     .quantity("10")
+    ///     .order_type(OrderType::Limit)
+    ///     .limit_price("420.00")
+    ///     .time_in_force(OrderTimeInForce {
+    ///         duration: Duration::DAY,
+    ///         expiration: None,
+    ///     })
+    ///     .build()?;
+    ///
+    /// let confirmed = order_req.confirm_to_place(client).await?;
+    /// println!(
+    ///     "Estimated cost: ${:.2}, commission: ${:.2}",
+    ///     confirmed.estimated_cost()?,
+    ///     confirmed.estimated_commission()?
+    /// );
+    ///
+    /// // Once the user accepts the confirmation, and any buying power warnings
+    /// let tickets = confirmed
+    ///     .buying_power_warning(BPWarningStatus::Confirmed)
+    ///     .place(client)
+    ///     .await?;
+    /// println!("Placed orders: {tickets:?}");
+    /// # Ok(()) }
+    /// ```
+    pub async fn confirm_to_place(self, client: &Client) -> Result<ConfirmedOrder, Error> {
+        let confirmations = Order::confirm(client, &self).await?;
+
+        ConfirmedOrder::new(ConfirmedRequest::Order(Box::new(self)), confirmations)
+    }
+}
+
+impl OrderRequestGroup {
+    /// Confirm the [`OrderRequestGroup`], returning a [`ConfirmedOrder`] which
+    /// can be reviewed, then placed exactly once with the `OrderConfirmID`'s
+    /// TradeStation returned.
+    ///
+    /// NOTE: See [`OrderRequest::confirm_to_place`] for an example.
+    pub async fn confirm_to_place(self, client: &Client) -> Result<ConfirmedOrder, Error> {
+        let confirmations = Order::confirm_group(client, &self).await?;
+
+        ConfirmedOrder::new(ConfirmedRequest::Group(self), confirmations)
+    }
+}
+
+#[derive(Debug)]
+/// An [`OrderRequest`], or [`OrderRequestGroup`], confirmed by TradeStation
+/// and ready to be placed once it's estimates have been reviewed.
+///
+/// Placing consumes the [`ConfirmedOrder`], and sends the `OrderConfirmID`'s
+/// from the confirmations, so TradeStation rejects any duplicate placements.
+pub struct ConfirmedOrder {
+    request: ConfirmedRequest,
+    confirmations: Vec<OrderConfirmation>,
+}
+impl ConfirmedOrder {
+    /// Tie the confirmations to the request, setting their `OrderConfirmID`'s on the orders.
+    fn new(
+        mut request: ConfirmedRequest,
+        confirmations: Vec<OrderConfirmation>,
+    ) -> Result<Self, Error> {
+        if confirmations.is_empty() {
+            return Err(Error::UnknownTradeStationAPIError(
+                "TradeStation didn't return any confirmations for the order.".into(),
+            ));
+        }
+        // Every order needs it's own `OrderConfirmID`, or it'd be placed without one
+        let order_count = request.order_requests().count();
+        if confirmations.len() != order_count {
+            return Err(Error::UnknownTradeStationAPIError(format!(
+                "TradeStation returned {} confirmations for {order_count} orders.",
+                confirmations.len()
+            )));
+        }
+
+        for (order_req, confirmation) in request.order_requests_mut().zip(&confirmations) {
+            order_req.order_confirm_id = Some(confirmation.order_confirm_id.clone());
+        }
+
+        Ok(Self {
+            request,
+            confirmations,
+        })
+    }
+
+    /// The confirmations of every order, in the order they'll be placed.
+    pub fn confirmations(&self) -> &[OrderConfirmation] {
+        &self.confirmations
+    }
+
+    /// The `OrderConfirmID`'s the orders will be placed with.
+    pub fn order_confirm_ids(&self) -> Vec<&str> {
+        self.confirmations
+            .iter()
+            .map(|confirmation| confirmation.order_confirm_id.as_str())
+            .collect()
+    }
+
+    /// The total estimated cost of every order.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::InvalidNumber`] if an estimated cost can't be parsed.
+    pub fn estimated_cost(&self) -> Result<f64, Error> {
+        self.confirmations
+            .iter()
+            .map(|confirmation| parse_number(&confirmation.estimated_cost))
+            .sum()
+    }
+
+    /// The total estimated commission of every order.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::InvalidNumber`] if an estimated commission can't be parsed.
+    pub fn estimated_commission(&self) -> Result<f64, Error> {
+        self.confirmations
+            .iter()
+            .map(|confirmation| parse_number(&confirmation.estimated_commission))
+            .sum()
+    }
+
+    /// The buying power warning status the orders will be placed with.
+    pub fn buying_power_warning_status(&self) -> Option<&BPWarningStatus> {
+        self.request
+            .order_requests()
+            .find_map(|order_req| order_req.buying_power_warning.as_ref())
+    }
+
+    /// Set the buying power warning status every order is placed with, like
+    /// [`BPWarningStatus::Confirmed`] once a buying power warning was accepted.
+    pub fn buying_power_warning(mut self, status: BPWarningStatus) -> Self {
+        for order_req in self.request.order_requests_mut() {
+            order_req.buying_power_warning = Some(status.clone());
+        }
+
+        self
+    }
+
+    /// Place the confirmed orders, with the `OrderConfirmID`'s from their confirmations.
+    ///
+    /// NOTE: This goes through [`Order::place`] or [`Order::place_group`], so any
+    /// interlocks, risk policy, or dry-run mode of the [`Client`] still apply.
+    pub async fn place(self, client: &Client) -> Result<Vec<OrderTicket>, Error> {
+        match &self.request {
+            ConfirmedRequest::Order(order_req) => Order::place(client, order_req).await,
+            ConfirmedRequest::Group(order_req_group) => {
+                Order::place_group(client, order_req_group).await
+            }
+        }
+    }
+}
+
+#[derive(Debug)]
+/// The request a [`ConfirmedOrder`] places.
+enum ConfirmedRequest {
+    Order(Box<OrderRequest>),
+    Group(OrderRequestGroup),
+}
+impl ConfirmedRequest {
+    /// The orders being placed.
+    fn order_requests(&self) -> impl Iterator<Item = &OrderRequest> {
+        match self {
+            Self::Order(order_req) => std::slice::from_ref(order_req.as_ref()).iter(),
+            Self::Group(order_req_group) => order_req_group.order_requests.iter(),
+        }
+    }
+
+    /// The orders being placed, mutably.
+    fn order_requests_mut(&mut self) -> impl Iterator<Item = &mut OrderRequest> {
+        match self {
+            Self::Order(order_req) => std::slice::from_mut(order_req.as_mut()).iter_mut(),
+            Self::Group(order_req_group) => order_req_group.order_requests.iter_mut(),
+        }
+    }
+}
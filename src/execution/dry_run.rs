@@ -0,0 +1,124 @@
+//! Dry-run mode, where placing orders confirms them instead of sending them.
+//!
+//! A [`crate::Client`] in dry-run mode runs [`crate::orders::Order::place`],
+//! [`crate::orders::Order::place_group`], and [`OrderRequestGroup::place`]
+//! through TradeStation's order confirm endpoints, so a strategy can run
+//! against the real API without sending any orders. Each confirmation is
+//! logged under the `tradestation::execution` tracing target with it's
+//! estimated cost and commission, and returned as an [`OrderTicket`] marked
+//! with [`OrderTicket::dry_run`].
+//!
+//! ```rust,no_run
+//! # use tradestation::{ClientBuilder, ClientEnvironment, Error, Token};
+//! # use tradestation::orders::{
+//! #     Duration, Order, OrderRequestBuilder, OrderTimeInForce, OrderType, TradeAction,
+//! # };
+//! # async fn example(token: Token) -> Result<(), Error> {
+//! let client = ClientBuilder::new()
+//!     .environment(ClientEnvironment::Live)
+//!     .with_token(token)
+//!     .dry_run()
+//!     .build()
+//!     .await?;
+//!
+//! let order_req = OrderRequestBuilder::new()
+//!     .account_id("YOUR_ACCOUNT_ID")
+//!     .symbol("MSFT")
+//!     .trade_action(TradeAction::Buy)
+//!     .quantity("10")
+//!     .order_type(OrderType::Limit)
+//!     .limit_price("420.00")
+//!     .time_in_force(OrderTimeInForce {
+//!         duration: Duration::DAY,
+//!         expiration: None,
+//!     })
+//!     .build()?;
+//!
+//! // Confirmed, but never sent to the market
+//! let tickets = Order::place(&client, &order_req).await?;
+//! assert!(tickets.iter().all(|ticket| ticket.dry_run));
+//! # Ok(()) }
+//! ```
+
+use crate::{
+    execution::{
+        confirm::OrderConfirmation,
+        request::{OrderRequest, OrderRequestGroup},
+        ticket::OrderTicket,
+    },
+    orders::Order,
+    Client, Error,
+};
+use std::sync::atomic::Ordering;
+use tracing::info;
+
+impl Client {
+    /// Switch dry-run mode on or off for the client, and every clone of it.
+    ///
+    /// NOTE: In dry-run mode placing orders confirms them instead, and never
+    /// sends them. Replacing and canceling orders is unaffected, so replacing orders
+    /// on [`crate::ClientEnvironment::Live`] still needs live trading enabled.
+    pub fn set_dry_run(&self, dry_run: bool) {
+        self.dry_run.store(dry_run, Ordering::SeqCst);
+
+        info!(
+            target: "tradestation::execution",
+            environment = %self.environment,
+            dry_run,
+            "dry-run mode changed"
+        );
+    }
+
+    /// Whether placing orders confirms them instead of sending them.
+    pub fn is_dry_run(&self) -> bool {
+        self.dry_run.load(Ordering::SeqCst)
+    }
+}
+
+/// Confirm an order instead of placing it, returning dry-run tickets.
+pub(crate) async fn place(
+    client: &Client,
+    order_request: &OrderRequest,
+) -> Result<Vec<OrderTicket>, Error> {
+    let confirmations = Order::confirm(client, order_request).await?;
+
+    Ok(into_tickets(client, confirmations))
+}
+
+/// Confirm an order group instead of placing it, returning dry-run tickets.
+pub(crate) async fn place_group(
+    client: &Client,
+    order_req_group: &OrderRequestGroup,
+) -> Result<Vec<OrderTicket>, Error> {
+    let confirmations = Order::confirm_group(client, order_req_group).await?;
+
+    Ok(into_tickets(client, confirmations))
+}
+
+/// Log each confirmation for review, and turn it into a dry-run ticket.
+fn into_tickets(client: &Client, confirmations: Vec<OrderConfirmation>) -> Vec<OrderTicket> {
+    confirmations
+        .into_iter()
+        .map(|confirmation| {
+            info!(
+                target: "tradestation::execution",
+                environment = %client.environment,
+                account = %confirmation.account_id,
+                order_confirm_id = %confirmation.order_confirm_id,
+                summary = %confirmation.summary_message,
+                estimated_price = %confirmation.estimated_price,
+                estimated_cost = %confirmation.estimated_cost,
+                estimated_commission = %confirmation.estimated_commission,
+                outcome = "dry_run",
+                "order confirmed instead of placed"
+            );
+
+            OrderTicket {
+                message: format!("Dry run: {}", confirmation.summary_message),
+                order_id: confirmation.order_confirm_id,
+                error: None,
+                dry_run: true,
+            }
+        })
+        .collect()
+}
@@ -0,0 +1,509 @@
+//! Emergency controls, canceling every open order and flattening every
+//! position of one or more [`crate::accounting::Account`]'s as fast as possible.
+//!
+//! Orders are canceled concurrently, and once the canceled orders are done,
+//! every position is closed concurrently with a market order. Positions in the
+//! symbol of an order which couldn't be canceled, or wasn't done canceling in
+//! time, aren't closed, so a working exit order can't fill alongside the closing
+//! order. Every cancellation and closing order is reported, whether it succeeded or failed.
+//!
+//! <div class="warning">WARNING: Closing orders bypass the kill switch, and the
+//! <code>RiskPolicy</code> of the client, so positions are flattened even while
+//! they block every other order. They're still refused for live accounts live
+//! trading wasn't enabled for, and in dry-run mode they're only confirmed, so
+//! nothing is flattened. Refused orders are recorded as failures in the report.</div>
+//!
+//! ```rust,no_run
+//! # use tradestation::{Client, Error};
+//! use tradestation::accounting::{AssetType, MultipleAccounts};
+//! use tradestation::execution::EmergencyFilter;
+//!
+//! # async fn example(client: &Client) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+//! let accounts = client.get_accounts().await?;
+//!
+//! // Get out of every stock option position
+//! let filter = EmergencyFilter::new().asset_type(AssetType::StockOption);
+//! let report = accounts.flatten_all(client, &filter).await?;
+//!
+//! for failure in report.failures() {
+//!     eprintln!("{failure}");
+//! }
+//! # Ok(()) }
+//! ```
+
+use crate::{
+    accounting::{orders::OrderType, Account, AssetType, Order, Position, PositionType},
+    execution::{
+        orders::{Duration, OrderTimeInForce, TradeAction},
+        request::OrderRequestBuilder,
+        ticket::OrderTicket,
+    },
+    utils::parse_number,
+    Client, Error,
+};
+use futures::future::join_all;
+use std::{
+    collections::{HashMap, HashSet},
+    fmt::Display,
+    time::Duration as StdDuration,
+};
+use tokio::time::{sleep, Instant};
+use tracing::{error, warn};
+
+/// How long to wait for canceled orders to be done, before skipping their symbols' positions.
+const CANCEL_TIMEOUT: StdDuration = StdDuration::from_secs(10);
+
+/// How often canceled orders are checked, while waiting for them to be done.
+const CANCEL_POLL_INTERVAL: StdDuration = StdDuration::from_millis(250);
+
+#[derive(Clone, Debug, Default)]
+/// Which orders and positions emergency controls apply to.
+///
+/// A new filter applies to every order and position.
+pub struct EmergencyFilter {
+    symbols: Option<HashSet<String>>,
+    asset_types: Option<HashSet<AssetType>>,
+}
+impl EmergencyFilter {
+    /// Create a new [`EmergencyFilter`] which applies to every order and position.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Apply to a symbol, once a symbol is set every other symbol is left alone.
+    pub fn symbol(mut self, symbol: impl Into<String>) -> Self {
+        self.symbols
+            .get_or_insert_with(HashSet::new)
+            .insert(symbol.into().to_uppercase());
+        self
+    }
+
+    /// Apply to an [`AssetType`], once an asset type is set every other asset type is left alone.
+    pub fn asset_type(mut self, asset_type: AssetType) -> Self {
+        self.asset_types
+            .get_or_insert_with(HashSet::new)
+            .insert(asset_type);
+        self
+    }
+
+    /// Whether the filter applies to a symbol of an [`AssetType`].
+    fn applies_to(&self, symbol: &str, asset_type: &AssetType) -> bool {
+        self.symbols
+            .as_ref()
+            .is_none_or(|symbols| symbols.contains(&symbol.to_uppercase()))
+            && self
+                .asset_types
+                .as_ref()
+                .is_none_or(|asset_types| asset_types.contains(asset_type))
+    }
+
+    /// Whether the filter applies to an [`Order`], which it does if it applies to any of it's legs.
+    fn applies_to_order(&self, order: &Order) -> bool {
+        order
+            .legs
+            .iter()
+            .any(|leg| self.applies_to(&leg.symbol, &leg.asset_type))
+    }
+}
+
+#[derive(Debug)]
+/// The outcome of canceling an open [`Order`].
+pub struct CancelOutcome {
+    /// The account the order belongs to.
+    pub account_id: String,
+
+    /// The id of the order canceled.
+    pub order_id: String,
+
+    /// The symbols of the order's legs.
+    pub symbols: Vec<String>,
+
+    /// The ticket of the cancellation, or why it failed.
+    pub result: Result<OrderTicket, Error>,
+}
+
+#[derive(Debug)]
+/// The outcome of closing a [`Position`] with a market order.
+pub struct CloseOutcome {
+    /// The account the position belongs to.
+    pub account_id: String,
+
+    /// The symbol of the position.
+    pub symbol: String,
+
+    /// The quantity of shares or contracts of the closing order.
+    pub quantity: String,
+
+    /// The trade action of the closing order.
+    pub trade_action: TradeAction,
+
+    /// The tickets of the closing order, or why it failed.
+    ///
+    /// NOTE: [`Error::PositionCloseSkipped`] if an order in the symbol
+    /// couldn't be canceled, or wasn't done canceling in time.
+    pub result: Result<Vec<OrderTicket>, Error>,
+}
+
+#[derive(Debug, Default)]
+/// A report of every order canceled, and position closed, by an emergency control.
+pub struct EmergencyReport {
+    /// Every open order canceled.
+    pub cancellations: Vec<CancelOutcome>,
+
+    /// Every position closed.
+    pub closures: Vec<CloseOutcome>,
+}
+impl EmergencyReport {
+    /// Whether every cancellation and closing order succeeded.
+    pub fn is_success(&self) -> bool {
+        self.failures().next().is_none()
+    }
+
+    /// Every cancellation and closing order that failed.
+    pub fn failures(&self) -> impl Iterator<Item = EmergencyFailure<'_>> {
+        let cancellations = self
+            .cancellations
+            .iter()
+            .filter_map(|outcome| match &outcome.result {
+                Err(error) => Some(EmergencyFailure::Cancel { outcome, error }),
+                Ok(_) => None,
+            });
+        let closures = self
+            .closures
+            .iter()
+            .filter_map(|outcome| match &outcome.result {
+                Err(error) => Some(EmergencyFailure::Close { outcome, error }),
+                Ok(_) => None,
+            });
+
+        cancellations.chain(closures)
+    }
+}
+
+#[derive(Debug)]
+/// A cancellation or closing order in an [`EmergencyReport`] that failed.
+pub enum EmergencyFailure<'a> {
+    /// An open order couldn't be canceled.
+    Cancel {
+        /// The failed cancellation.
+        outcome: &'a CancelOutcome,
+        /// Why it failed.
+        error: &'a Error,
+    },
+
+    /// A position couldn't be closed.
+    Close {
+        /// The failed closing order.
+        outcome: &'a CloseOutcome,
+        /// Why it failed.
+        error: &'a Error,
+    },
+}
+impl Display for EmergencyFailure<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Cancel { outcome, error } => write!(
+                f,
+                "Couldn't cancel order {} in {}: {error}",
+                outcome.order_id, outcome.account_id
+            ),
+            Self::Close { outcome, error } => write!(
+                f,
+                "Couldn't close {} {} in {}: {error}",
+                outcome.quantity, outcome.symbol, outcome.account_id
+            ),
+        }
+    }
+}
+
+impl Position {
+    /// The [`TradeAction`] which closes the position, based on
+    /// it's [`AssetType`] and whether it's long or short.
+    pub fn closing_trade_action(&self) -> TradeAction {
+        let is_long = matches!(self.long_short, PositionType::Long);
+        match self.asset_type {
+            AssetType::Equity | AssetType::Stock if is_long => TradeAction::Sell,
+            AssetType::Equity | AssetType::Stock => TradeAction::BuyToCover,
+            AssetType::Option
+            | AssetType::StockOption
+            | AssetType::IndexOption
+            | AssetType::CurrencyOption
+                if is_long =>
+            {
+                TradeAction::SellToClose
+            }
+            AssetType::Option
+            | AssetType::StockOption
+            | AssetType::IndexOption
+            | AssetType::CurrencyOption => TradeAction::BuyToClose,
+            _ if is_long => TradeAction::Sell,
+            _ => TradeAction::Buy,
+        }
+    }
+}
+
+impl Account {
+    /// Cancel every open [`Order`] in the account the filter applies to, concurrently.
+    ///
+    /// # Errors
+    ///
+    /// Only if the account's orders can't be fetched, failed
+    /// cancellations are reported in the [`EmergencyReport`].
+    pub async fn cancel_all_orders(
+        &self,
+        client: &Client,
+        filter: &EmergencyFilter,
+    ) -> Result<EmergencyReport, Error> {
+        cancel_all_in_accounts(client, vec![&self.account_id], filter).await
+    }
+
+    /// Cancel every open [`Order`] in the account the filter applies to, then close
+    /// every [`Position`] it applies to with a market order, both concurrently.
+    ///
+    /// NOTE: Orders are canceled first, and positions are only closed once the orders
+    /// in their symbol are done canceling, so working exit orders don't fill alongside
+    /// the closing orders. Positions with an order which couldn't be canceled, or
+    /// wasn't done canceling in time, are reported as [`Error::PositionCloseSkipped`].
+    ///
+    /// NOTE: Closing orders bypass the kill switch and risk policy, but
+    /// in dry-run mode they're only confirmed, see [`crate::execution::emergency`].
+    ///
+    /// # Errors
+    ///
+    /// Only if the account's orders or positions can't be fetched, failed
+    /// cancellations and closing orders are reported in the [`EmergencyReport`].
+    pub async fn flatten_all(
+        &self,
+        client: &Client,
+        filter: &EmergencyFilter,
+    ) -> Result<EmergencyReport, Error> {
+        flatten_accounts(client, vec![&self.account_id], filter).await
+    }
+}
+
+/// Cancel every open order in the accounts the filter applies to.
+pub(crate) async fn cancel_all_in_accounts(
+    client: &Client,
+    account_ids: Vec<&str>,
+    filter: &EmergencyFilter,
+) -> Result<EmergencyReport, Error> {
+    let orders = Account::get_orders_for_accounts(client, account_ids).await?;
+
+    Ok(EmergencyReport {
+        cancellations: cancel_orders(client, orders, filter).await,
+        closures: Vec::new(),
+    })
+}
+
+/// Cancel every open order, then close every position, in the accounts the filter applies to.
+pub(crate) async fn flatten_accounts(
+    client: &Client,
+    account_ids: Vec<&str>,
+    filter: &EmergencyFilter,
+) -> Result<EmergencyReport, Error> {
+    let orders = Account::get_orders_for_accounts(client, account_ids.clone()).await?;
+    let cancellations = cancel_orders(client, orders, filter).await;
+    let uncanceled = wait_for_cancellations(client, &cancellations).await;
+
+    let positions = Account::get_positions_by_accounts(client, account_ids).await?;
+    let closures = close_positions(client, positions, filter, &uncanceled).await;
+
+    Ok(EmergencyReport {
+        cancellations,
+        closures,
+    })
+}
+
+/// Cancel every open order the filter applies to, concurrently.
+async fn cancel_orders(
+    client: &Client,
+    orders: Vec<Order>,
+    filter: &EmergencyFilter,
+) -> Vec<CancelOutcome> {
+    let cancellations = orders
+        .into_iter()
+        .filter(|order| order.status.is_open() && filter.applies_to_order(order))
+        .map(|order| async move {
+            let result = OrderTicket::from_id(&order.order_id).cancel(client).await;
+            let symbols = order.legs.iter().map(|leg| leg.symbol.clone()).collect();
+            if let Err(e) = &result {
+                error!(
+                    target: "tradestation::execution",
+                    environment = %client.environment,
+                    account = %order.account_id,
+                    order_id = %order.order_id,
+                    error = %e,
+                    outcome = "failed",
+                    "emergency order cancellation failed"
+                );
+            }
+
+            CancelOutcome {
+                account_id: order.account_id,
+                order_id: order.order_id,
+                symbols,
+                result,
+            }
+        });
+
+    let outcomes = join_all(cancellations).await;
+    warn!(
+        target: "tradestation::execution",
+        environment = %client.environment,
+        order_count = outcomes.len(),
+        failed_count = outcomes.iter().filter(|outcome| outcome.result.is_err()).count(),
+        "emergency orders canceled"
+    );
+
+    outcomes
+}
+
+/// Wait for the canceled orders to be done, returning why each account's
+/// symbol with an order that couldn't be canceled in time can't be closed.
+async fn wait_for_cancellations(
+    client: &Client,
+    cancellations: &[CancelOutcome],
+) -> HashMap<(String, String), String> {
+    let mut uncanceled = HashMap::new();
+    let mut pending: HashMap<&str, Vec<&CancelOutcome>> = HashMap::new();
+    for outcome in cancellations {
+        match &outcome.result {
+            Ok(_) => pending
+                .entry(outcome.account_id.as_str())
+                .or_default()
+                .push(outcome),
+            Err(e) => skip_symbols(
+                &mut uncanceled,
+                outcome,
+                format!("order {} couldn't be canceled: {e}", outcome.order_id),
+            ),
+        }
+    }
+
+    let deadline = Instant::now() + CANCEL_TIMEOUT;
+    while !pending.is_empty() && Instant::now() < deadline {
+        for (account_id, outcomes) in pending.iter_mut() {
+            let order_ids = outcomes.iter().map(|outcome| outcome.order_id.as_str());
+            // Failing to check the orders is retried, until the timeout
+            let Ok(orders) = Order::find(client, order_ids.collect(), account_id.to_string()).await
+            else {
+                continue;
+            };
+
+            outcomes.retain(|outcome| {
+                !orders
+                    .iter()
+                    .any(|order| order.order_id == outcome.order_id && order.status.is_terminal())
+            });
+        }
+        pending.retain(|_, outcomes| !outcomes.is_empty());
+
+        if !pending.is_empty() {
+            sleep(CANCEL_POLL_INTERVAL).await;
+        }
+    }
+
+    for outcome in pending.into_values().flatten() {
+        warn!(
+            target: "tradestation::execution",
+            environment = %client.environment,
+            account = %outcome.account_id,
+            order_id = %outcome.order_id,
+            outcome = "timeout",
+            "emergency order cancellation wasn't done in time"
+        );
+        skip_symbols(
+            &mut uncanceled,
+            outcome,
+            format!(
+                "order {} wasn't done canceling after {CANCEL_TIMEOUT:?}",
+                outcome.order_id
+            ),
+        );
+    }
+
+    uncanceled
+}
+
+/// Skip closing the positions in the symbols of an order which couldn't be canceled.
+fn skip_symbols(
+    uncanceled: &mut HashMap<(String, String), String>,
+    outcome: &CancelOutcome,
+    reason: String,
+) {
+    for symbol in &outcome.symbols {
+        uncanceled
+            .entry((outcome.account_id.clone(), symbol.to_uppercase()))
+            .or_insert_with(|| reason.clone());
+    }
+}
+
+/// Close every position the filter applies to with a market order, concurrently,
+/// skipping the positions in a symbol with an order that couldn't be canceled.
+async fn close_positions(
+    client: &Client,
+    positions: Vec<Position>,
+    filter: &EmergencyFilter,
+    uncanceled: &HashMap<(String, String), String>,
+) -> Vec<CloseOutcome> {
+    let closures = positions
+        .into_iter()
+        .filter(|position| filter.applies_to(&position.symbol, &position.asset_type))
+        .map(|position| async move {
+            let trade_action = position.closing_trade_action();
+            let quantity = parse_number(&position.quantity)
+                .map(|quantity| quantity.abs().to_string())
+                .unwrap_or_else(|_| position.quantity.trim_start_matches('-').to_string());
+
+            let skipped =
+                uncanceled.get(&(position.account_id.clone(), position.symbol.to_uppercase()));
+            let result = match skipped {
+                Some(reason) => Err(Error::PositionCloseSkipped(reason.clone())),
+                None => match OrderRequestBuilder::new()
+                    .account_id(&position.account_id)
+                    .symbol(&position.symbol)
+                    .trade_action(trade_action.clone())
+                    .quantity(&quantity)
+                    .order_type(OrderType::Market)
+                    .time_in_force(OrderTimeInForce {
+                        duration: Duration::DAY,
+                        expiration: None,
+                    })
+                    .build()
+                {
+                    Ok(order_req) => Order::place_closing(client, &order_req).await,
+                    Err(e) => Err(e),
+                },
+            };
+            if let Err(e) = &result {
+                error!(
+                    target: "tradestation::execution",
+                    environment = %client.environment,
+                    account = %position.account_id,
+                    symbol = %position.symbol,
+                    error = %e,
+                    outcome = "failed",
+                    "emergency position close failed"
+                );
+            }
+
+            CloseOutcome {
+                account_id: position.account_id,
+                symbol: position.symbol,
+                quantity,
+                trade_action,
+                result,
+            }
+        });
+
+    let outcomes = join_all(closures).await;
+    warn!(
+        target: "tradestation::execution",
+        environment = %client.environment,
+        position_count = outcomes.len(),
+        failed_count = outcomes.iter().filter(|outcome| outcome.result.is_err()).count(),
+        "emergency positions flattened"
+    );
+
+    outcomes
+}
@@ -0,0 +1,415 @@
+//! Build [`OrderRequestGroup`]'s which are validated against TradeStation's
+//! rules for their group type before they're confirmed or placed.
+//!
+//! - [`OcoGroupBuilder`]: Order Cancels Order, where filling one order cancels the rest.
+//! - [`BracketGroupBuilder`]: Bracket, a take profit and stop loss closing the same position.
+//!
+//! ```rust
+//! use tradestation::{
+//!     accounting::OrderType,
+//!     execution::{
+//!         BracketGroupBuilder, Duration, OrderRequestBuilder, OrderTimeInForce, TradeAction,
+//!     },
+//! };
+//!
+//! let exit = |trade_action, order_type| {
+//!     OrderRequestBuilder::new()
+//!         .account_id("11111111")
+//!         .symbol("XLRE")
+//!         .trade_action(trade_action)
+//!         .quantity("1000")
+//!         .order_type(order_type)
+//!         .time_in_force(OrderTimeInForce {
+//!             duration: Duration::GTC,
+//!             expiration: None,
+//!         })
+//! };
+//!
+//! // Bracket a short position in XLRE
+//! let group = BracketGroupBuilder::new()
+//!     .take_profit(
+//!         exit(TradeAction::BuyToCover, OrderType::Limit)
+//!             .limit_price("35.75")
+//!             .build()
+//!             .unwrap(),
+//!     )
+//!     .stop_loss(
+//!         exit(TradeAction::BuyToCover, OrderType::StopMarket)
+//!             .stop_price("46.50")
+//!             .build()
+//!             .unwrap(),
+//!     )
+//!     .build()
+//!     .unwrap();
+//! assert_eq!(group.order_requests.len(), 2);
+//!
+//! // Both sides of a bracket have to close the position the same way
+//! assert!(BracketGroupBuilder::new()
+//!     .take_profit(
+//!         exit(TradeAction::BuyToCover, OrderType::Limit)
+//!             .limit_price("35.75")
+//!             .build()
+//!             .unwrap(),
+//!     )
+//!     .stop_loss(
+//!         exit(TradeAction::Sell, OrderType::StopMarket)
+//!             .stop_price("46.50")
+//!             .build()
+//!             .unwrap(),
+//!     )
+//!     .build()
+//!     .is_err());
+//! ```
+
+use crate::{
+    accounting::orders::{OrderRelationship, OrderType},
+    execution::{
+        bracket::exit_trade_action,
+        orders::TradeAction,
+        request::{OrderRequest, OrderRequestGroup},
+    },
+    utils::parse_number,
+    Error,
+};
+
+impl OrderRequestGroup {
+    /// Validate the orders follow the rules of the group type.
+    ///
+    /// Every group needs orders from a single account. On top of that:
+    /// - [`OrderRelationship::OCO`]: At least 2 `Limit`, `StopMarket`, or `StopLimit`
+    ///   orders, each with a symbol, trade action, and positive quantity, or with legs.
+    ///   Orders for the same symbol on the same side (buying or selling) must have the
+    ///   same trade action and quantity, and can't close both a long and a short position.
+    /// - [`OrderRelationship::BRK`]: At least 2 closing `Limit`, `StopMarket`, or
+    ///   `StopLimit` orders for the same symbol, quantity, and trade action, with at
+    ///   least 1 limit and 1 stop order. They can be preceded by the order opening
+    ///   the position they close.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::InvalidOrderGroup`] describing the first broken rule.
+    pub fn validate(&self) -> Result<(), Error> {
+        let invalid = |reason: String| {
+            Err(Error::InvalidOrderGroup(format!(
+                "{:?}: {reason}",
+                self.group_type
+            )))
+        };
+
+        let Some(first) = self.order_requests.first() else {
+            return invalid("there's no orders in the group".into());
+        };
+        if let Some(order) = self
+            .order_requests
+            .iter()
+            .find(|order| order.account_id != first.account_id)
+        {
+            return invalid(format!(
+                "every order must be for account {}, not {}",
+                first.account_id, order.account_id
+            ));
+        }
+
+        match self.group_type {
+            OrderRelationship::OCO => validate_oco(&self.order_requests).or_else(invalid),
+            OrderRelationship::BRK => validate_bracket(&self.order_requests).or_else(invalid),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Validate the orders of an OCO group, returning the broken rule.
+fn validate_oco(orders: &[OrderRequest]) -> Result<(), String> {
+    if orders.len() < 2 {
+        return Err(format!("needs at least 2 orders, got {}", orders.len()));
+    }
+    for (idx, order) in orders.iter().enumerate() {
+        let has_legs = order.legs.as_ref().is_some_and(|legs| !legs.is_empty());
+        if !has_legs
+            && (order.symbol.is_none() || order.trade_action.is_none() || order.quantity.is_none())
+        {
+            return Err(format!(
+                "order {idx} needs a symbol, trade action, and quantity, or legs"
+            ));
+        }
+        if let Some(quantity) = &order.quantity {
+            if !parse_number(quantity).is_ok_and(|quantity| quantity > 0.0) {
+                return Err(format!("order {idx} has an invalid quantity of {quantity}"));
+            }
+        }
+        // A market order would fill right away, canceling the rest of the group
+        if !matches!(
+            order.order_type,
+            OrderType::Limit | OrderType::StopMarket | OrderType::StopLimit
+        ) {
+            return Err(format!(
+                "order {idx} must be a Limit, StopMarket, or StopLimit order, not {:?}",
+                order.order_type
+            ));
+        }
+    }
+
+    // Orders for the same symbol are alternatives for the same trade
+    for (idx, order) in orders.iter().enumerate() {
+        let Some((symbol, trade_action, quantity)) = single_symbol(order) else {
+            continue;
+        };
+
+        for (other_idx, other) in orders.iter().enumerate().take(idx) {
+            let Some((other_symbol, other_action, other_quantity)) = single_symbol(other) else {
+                continue;
+            };
+            if !other_symbol.eq_ignore_ascii_case(symbol) {
+                continue;
+            }
+
+            if is_buying(other_action) == is_buying(trade_action) {
+                if other_action != trade_action || other_quantity != quantity {
+                    return Err(format!(
+                        "order {idx} must {other_action:?} {other_quantity} {symbol} like order {other_idx}"
+                    ));
+                }
+            } else if is_closing(other_action) && is_closing(trade_action) {
+                return Err(format!(
+                    "orders {other_idx} and {idx} can't close both a long and a short {symbol} position"
+                ));
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Validate the orders of a bracket group, returning the broken rule.
+fn validate_bracket(orders: &[OrderRequest]) -> Result<(), String> {
+    // The group may lead with the order opening the position being bracketed
+    let (entry, exits) = match orders {
+        [entry, exit, ..] if closes(entry, exit) => (Some(entry), &orders[1..]),
+        _ => (None, orders),
+    };
+    if exits.len() < 2 {
+        return Err(format!("needs at least 2 exit orders, got {}", exits.len()));
+    }
+
+    let Some((symbol, trade_action, quantity)) = single_symbol(&exits[0]) else {
+        return Err("order legs aren't allowed, every order needs a symbol".into());
+    };
+    if is_opening(trade_action) {
+        return Err(format!(
+            "exit orders must close a position, not open one with {trade_action:?}"
+        ));
+    }
+    if let Some(entry) = entry {
+        if single_symbol(entry)
+            .map(|(entry_symbol, _, entry_quantity)| (entry_symbol, entry_quantity))
+            != Some((symbol, quantity))
+        {
+            return Err(format!(
+                "the entry order must be for {quantity} {symbol} like the exit orders"
+            ));
+        }
+    }
+
+    for (idx, order) in exits.iter().enumerate() {
+        match single_symbol(order) {
+            Some((order_symbol, order_action, order_quantity))
+                if order_symbol == symbol
+                    && order_action == trade_action
+                    && order_quantity == quantity => {}
+            _ => {
+                return Err(format!(
+                "exit order {idx} must {trade_action:?} {quantity} {symbol} like every other exit"
+            ))
+            }
+        }
+        if matches!(order.order_type, OrderType::Market) {
+            return Err(format!(
+                "exit order {idx} must be a Limit, StopMarket, or StopLimit order"
+            ));
+        }
+    }
+
+    let has_limit = exits
+        .iter()
+        .any(|order| matches!(order.order_type, OrderType::Limit));
+    let has_stop = exits.iter().any(|order| {
+        matches!(
+            order.order_type,
+            OrderType::StopMarket | OrderType::StopLimit
+        )
+    });
+    if !has_limit || !has_stop {
+        return Err("needs at least 1 Limit and 1 stop exit order".into());
+    }
+
+    Ok(())
+}
+
+/// The symbol, trade action, and quantity of a single symbol order.
+fn single_symbol(order: &OrderRequest) -> Option<(&str, &TradeAction, f64)> {
+    let quantity = order.quantity.as_deref().map(parse_number)?.ok()?;
+
+    Some((
+        order.symbol.as_deref()?,
+        order.trade_action.as_ref()?,
+        quantity,
+    ))
+}
+
+/// Whether an exit order's trade action closes the position an entry order opens.
+fn closes(entry: &OrderRequest, exit: &OrderRequest) -> bool {
+    match (&entry.trade_action, &exit.trade_action) {
+        (Some(entry_action), Some(exit_action)) => {
+            exit_trade_action(entry_action).ok().as_ref() == Some(exit_action)
+        }
+        _ => false,
+    }
+}
+
+/// Whether a trade action only opens a position.
+///
+/// NOTE: `Buy` and `Sell` can open or close futures positions, so they're treated as closing.
+fn is_opening(trade_action: &TradeAction) -> bool {
+    matches!(
+        trade_action,
+        TradeAction::SellShort | TradeAction::BuyToOpen | TradeAction::SellToOpen
+    )
+}
+
+/// Whether a trade action buys, rather than sells.
+pub(crate) fn is_buying(trade_action: &TradeAction) -> bool {
+    matches!(
+        trade_action,
+        TradeAction::Buy
+            | TradeAction::BuyToCover
+            | TradeAction::BuyToOpen
+            | TradeAction::BuyToClose
+    )
+}
+
+/// Whether a trade action only closes a position.
+///
+/// NOTE: `Buy` and `Sell` can open or close futures positions, so they're treated as opening.
+fn is_closing(trade_action: &TradeAction) -> bool {
+    matches!(
+        trade_action,
+        TradeAction::BuyToCover | TradeAction::BuyToClose | TradeAction::SellToClose
+    )
+}
+
+#[derive(Clone, Debug, Default)]
+/// A builder pattern for Order Cancels Order [`OrderRequestGroup`]'s.
+pub struct OcoGroupBuilder {
+    order_requests: Vec<OrderRequest>,
+}
+impl OcoGroupBuilder {
+    /// Initialize a new builder for an OCO [`OrderRequestGroup`].
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add an [`OrderRequest`] to the group.
+    pub fn order_request(mut self, order_req: OrderRequest) -> Self {
+        self.order_requests.push(order_req);
+        self
+    }
+
+    /// Add multiple [`OrderRequest`]'s to the group.
+    pub fn order_requests(mut self, order_reqs: Vec<OrderRequest>) -> Self {
+        self.order_requests.extend(order_reqs);
+        self
+    }
+
+    /// Finish building the OCO [`OrderRequestGroup`].
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::InvalidOrderGroup`] if the orders break the OCO rules,
+    /// see [`OrderRequestGroup::validate`].
+    pub fn build(self) -> Result<OrderRequestGroup, Error> {
+        let group = OrderRequestGroup {
+            order_requests: self.order_requests,
+            group_type: OrderRelationship::OCO,
+        };
+        group.validate()?;
+
+        Ok(group)
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+/// A builder pattern for Bracket [`OrderRequestGroup`]'s.
+///
+/// NOTE: To bracket an entry order which hasn't been placed yet, the
+/// [`crate::execution::BracketOrderBuilder`] sends the bracket once it fills.
+pub struct BracketGroupBuilder {
+    entry: Option<OrderRequest>,
+    take_profits: Vec<OrderRequest>,
+    stop_losses: Vec<OrderRequest>,
+}
+impl BracketGroupBuilder {
+    /// Initialize a new builder for a bracket [`OrderRequestGroup`].
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the order opening the position, placed along with the bracket.
+    pub fn entry(mut self, order_req: OrderRequest) -> Self {
+        self.entry = Some(order_req);
+        self
+    }
+
+    /// Add a `Limit` take profit [`OrderRequest`] to the bracket.
+    pub fn take_profit(mut self, order_req: OrderRequest) -> Self {
+        self.take_profits.push(order_req);
+        self
+    }
+
+    /// Add a `StopMarket` or `StopLimit` stop loss [`OrderRequest`] to the bracket.
+    pub fn stop_loss(mut self, order_req: OrderRequest) -> Self {
+        self.stop_losses.push(order_req);
+        self
+    }
+
+    /// Finish building the bracket [`OrderRequestGroup`].
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::InvalidOrderGroup`] if the orders break the bracket rules,
+    /// see [`OrderRequestGroup::validate`].
+    pub fn build(self) -> Result<OrderRequestGroup, Error> {
+        if let (Some(entry), Some(exit)) = (
+            &self.entry,
+            self.take_profits.first().or(self.stop_losses.first()),
+        ) {
+            if !closes(entry, exit) {
+                return Err(Error::InvalidOrderGroup(format!(
+                    "BRK: a {:?} exit order doesn't close a {:?} entry order",
+                    exit.trade_action, entry.trade_action
+                )));
+            }
+        }
+        if self
+            .take_profits
+            .iter()
+            .any(|order| !matches!(order.order_type, OrderType::Limit))
+        {
+            return Err(Error::InvalidOrderGroup(
+                "BRK: take profit orders must be Limit orders".into(),
+            ));
+        }
+
+        let group = OrderRequestGroup {
+            order_requests: self
+                .entry
+                .into_iter()
+                .chain(self.take_profits)
+                .chain(self.stop_losses)
+                .collect(),
+            group_type: OrderRelationship::BRK,
+        };
+        group.validate()?;
+
+        Ok(group)
+    }
+}
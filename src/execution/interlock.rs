@@ -0,0 +1,203 @@
+//! Safety interlocks in front of every order submission and replacement.
+//!
+//! - A [`crate::Client`] for [`crate::ClientEnvironment::Live`] refuses to place or
+//!   replace orders unless it was built with
+//!   [`crate::client::ClientBuilderStep::enable_live_trading`], optionally
+//!   restricted to certain accounts with
+//!   [`crate::client::ClientBuilderStep::live_trading_accounts`]. Orders placed
+//!   by clients in [`crate::execution::dry_run`] mode are exempt, since they're only
+//!   confirmed, but replacing orders still sends them, so it isn't.
+//! - A kill switch, shared by a [`crate::Client`] and every clone of it,
+//!   instantly blocks every new order submission and replacement in any
+//!   environment, while still allowing orders to be canceled, and positions
+//!   to be closed by [`crate::execution::emergency`] controls.
+//!
+//! ```rust,no_run
+//! # use tradestation::{ClientBuilder, ClientEnvironment, Error, Token};
+//! # async fn example(token: Token) -> Result<(), Error> {
+//! let client = ClientBuilder::new()
+//!     .environment(ClientEnvironment::Live)
+//!     .with_token(token)
+//!     .enable_live_trading()
+//!     .live_trading_accounts(["YOUR_ACCOUNT_ID"])
+//!     .build()
+//!     .await?;
+//!
+//! // Something looks wrong, stop sending orders
+//! client.engage_kill_switch();
+//! assert!(client.kill_switch_engaged());
+//! # Ok(()) }
+//! ```
+
+use crate::{execution::OrderRequest, Client, ClientEnvironment, Error};
+use std::{
+    collections::HashSet,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+};
+use tracing::warn;
+
+#[derive(Clone, Debug, Default)]
+/// The live trading acknowledgement a [`Client`] is built with.
+pub(crate) struct LiveTrading {
+    /// Whether sending orders to the live environment was acknowledged.
+    pub(crate) enabled: bool,
+
+    /// The only accounts live orders can be sent for, if restricted.
+    pub(crate) accounts: Option<HashSet<String>>,
+}
+
+#[derive(Clone, Debug, Default)]
+/// The interlocks checked before sending orders, shared by every clone of a [`Client`].
+pub(crate) struct TradingInterlock {
+    live_trading: Arc<LiveTrading>,
+    kill_switch: Arc<AtomicBool>,
+}
+impl TradingInterlock {
+    /// Create the interlocks for a new [`Client`].
+    pub(crate) fn new(live_trading: LiveTrading) -> Self {
+        Self {
+            live_trading: Arc::new(live_trading),
+            kill_switch: Arc::default(),
+        }
+    }
+
+    /// Whether live orders are restricted to certain accounts.
+    pub(crate) fn restricts_accounts(&self, environment: &ClientEnvironment) -> bool {
+        matches!(environment, ClientEnvironment::Live) && self.live_trading.accounts.is_some()
+    }
+}
+
+impl Client {
+    /// Engage the kill switch, blocking every new order submission and
+    /// replacement through the client, and every clone of it, until released.
+    ///
+    /// NOTE: Orders can still be canceled while the kill switch is engaged.
+    pub fn engage_kill_switch(&self) {
+        self.interlock.kill_switch.store(true, Ordering::SeqCst);
+
+        warn!(
+            target: "tradestation::execution",
+            environment = %self.environment,
+            "kill switch engaged"
+        );
+    }
+
+    /// Release the kill switch, allowing orders to be sent again.
+    pub fn release_kill_switch(&self) {
+        self.interlock.kill_switch.store(false, Ordering::SeqCst);
+
+        warn!(
+            target: "tradestation::execution",
+            environment = %self.environment,
+            "kill switch released"
+        );
+    }
+
+    /// Whether the kill switch is engaged, blocking every new order submission and replacement.
+    pub fn kill_switch_engaged(&self) -> bool {
+        self.interlock.kill_switch.load(Ordering::SeqCst)
+    }
+
+    /// Whether the client can send orders to [`ClientEnvironment::Live`].
+    ///
+    /// NOTE: Always `false` for the other environments.
+    pub fn live_trading_enabled(&self) -> bool {
+        matches!(self.environment, ClientEnvironment::Live) && self.interlock.live_trading.enabled
+    }
+}
+
+/// Check the client's interlocks allow sending orders for the accounts.
+///
+/// NOTE: Pass no accounts when they aren't known, which is
+/// only allowed when live orders aren't restricted to accounts.
+pub(crate) fn ensure_can_send<'a>(
+    client: &Client,
+    account_ids: impl IntoIterator<Item = &'a str>,
+) -> Result<(), Error> {
+    if client.kill_switch_engaged() {
+        return blocked(client, None, Error::KillSwitchEngaged);
+    }
+
+    ensure_live_trading(client, account_ids)
+}
+
+/// Check live trading was enabled for the accounts, if the client is for [`ClientEnvironment::Live`].
+fn ensure_live_trading<'a>(
+    client: &Client,
+    account_ids: impl IntoIterator<Item = &'a str>,
+) -> Result<(), Error> {
+    if !matches!(client.environment, ClientEnvironment::Live) {
+        return Ok(());
+    }
+
+    let live_trading = &client.interlock.live_trading;
+    if !live_trading.enabled {
+        return blocked(client, None, Error::LiveTradingNotEnabled);
+    }
+    if let Some(allowed) = &live_trading.accounts {
+        for account_id in account_ids {
+            if !allowed.contains(account_id) {
+                return blocked(
+                    client,
+                    Some(account_id),
+                    Error::LiveTradingAccountNotAllowed(account_id.into()),
+                );
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Check the client's interlocks allow placing orders, and the orders they send.
+///
+/// NOTE: Only the kill switch applies in dry-run mode, since placing orders only
+/// confirms them, so they never reach live accounts. Replacements aren't exempt.
+pub(crate) fn ensure_can_send_orders(
+    client: &Client,
+    order_reqs: &[&OrderRequest],
+) -> Result<(), Error> {
+    if client.kill_switch_engaged() {
+        return blocked(client, None, Error::KillSwitchEngaged);
+    }
+
+    ensure_can_send_closing_orders(client, order_reqs)
+}
+
+/// Check the client's interlocks allow placing orders which close positions.
+///
+/// NOTE: The kill switch doesn't apply, so positions can be flattened while it's engaged.
+pub(crate) fn ensure_can_send_closing_orders(
+    client: &Client,
+    order_reqs: &[&OrderRequest],
+) -> Result<(), Error> {
+    if client.is_dry_run() {
+        return Ok(());
+    }
+
+    let mut account_ids = Vec::new();
+    let mut pending = order_reqs.to_vec();
+    while let Some(order_req) = pending.pop() {
+        account_ids.push(order_req.account_id.as_str());
+        pending.extend(order_req.osos.iter().flatten().flat_map(|oso| &oso.orders));
+    }
+
+    ensure_live_trading(client, account_ids)
+}
+
+/// Log and return an order blocked by an interlock.
+fn blocked(client: &Client, account_id: Option<&str>, error: Error) -> Result<(), Error> {
+    warn!(
+        target: "tradestation::execution",
+        environment = %client.environment,
+        account = ?account_id,
+        error = %error,
+        outcome = "blocked",
+        "order blocked by trading interlock"
+    );
+
+    Err(error)
+}
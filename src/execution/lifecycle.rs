@@ -0,0 +1,243 @@
+//! Follow an [`OrderTicket`] through it's lifecycle on the order stream,
+//! from being acknowledged, to being filled, rejected, canceled, expired, or replaced.
+//!
+//! ```rust,no_run
+//! # use tradestation::{Client, Error};
+//! # use tradestation::orders::{Order, OrderRequest};
+//! use futures::StreamExt;
+//! use std::time::Duration;
+//! use tradestation::execution::OrderEvent;
+//!
+//! # async fn example(client: &Client, order_req: &OrderRequest) -> Result<(), Error> {
+//! let tickets = Order::place(client, order_req).await?;
+//!
+//! // Wait up to a minute for the order to finish
+//! match tickets[0]
+//!     .wait_for_terminal(client, &order_req.account_id, Duration::from_secs(60))
+//!     .await?
+//! {
+//!     OrderEvent::Filled(order) => println!("Filled @ {:?}", order.filled_price),
+//!     OrderEvent::Rejected { reason, .. } => println!("Rejected: {reason}"),
+//!     event => println!("Finished with {event:?}"),
+//! }
+//!
+//! // Or, react to every step of the order's lifecycle
+//! let events = tickets[0].lifecycle_events(client, &order_req.account_id);
+//! tokio::pin!(events);
+//! while let Some(event) = events.next().await {
+//!     if let OrderEvent::PartiallyFilled { filled_quantity, .. } = event? {
+//!         println!("Filled {filled_quantity} so far");
+//!     }
+//! }
+//! # Ok(()) }
+//! ```
+
+use crate::{
+    accounting::{status::OrderStatusCategory, Order},
+    execution::ticket::OrderTicket,
+    responses::account::StreamOrdersResp,
+    utils::parse_number,
+    Client, Error,
+};
+use async_stream::try_stream;
+use futures::{pin_mut, Stream, StreamExt};
+use std::time::Duration;
+
+#[derive(Clone, Debug)]
+/// A step in the lifecycle of an [`Order`].
+pub enum OrderEvent {
+    /// The order was received, and is open.
+    Acknowledged(Box<Order>),
+
+    /// Part of the order was filled, and the rest is still open.
+    PartiallyFilled {
+        /// The cumulative quantity filled so far.
+        filled_quantity: f64,
+        /// The order as of the partial fill.
+        order: Box<Order>,
+    },
+
+    /// The order was filled.
+    Filled(Box<Order>),
+
+    /// The order was rejected.
+    Rejected {
+        /// Why the order was rejected.
+        reason: String,
+        /// The rejected order.
+        order: Box<Order>,
+    },
+
+    /// The order was canceled, possibly after being partially filled.
+    Cancelled(Box<Order>),
+
+    /// The order expired.
+    Expired(Box<Order>),
+
+    /// The order was replaced by another order.
+    Replaced(Box<Order>),
+}
+impl OrderEvent {
+    /// The [`Order`] as of this event.
+    pub fn order(&self) -> &Order {
+        match self {
+            Self::Acknowledged(order)
+            | Self::Filled(order)
+            | Self::Cancelled(order)
+            | Self::Expired(order)
+            | Self::Replaced(order) => order,
+            Self::PartiallyFilled { order, .. } | Self::Rejected { order, .. } => order,
+        }
+    }
+
+    /// Whether the order is done, and won't change anymore.
+    pub fn is_terminal(&self) -> bool {
+        matches!(
+            self,
+            Self::Filled(_)
+                | Self::Rejected { .. }
+                | Self::Cancelled(_)
+                | Self::Expired(_)
+                | Self::Replaced(_)
+        )
+    }
+}
+
+impl OrderTicket {
+    /// Stream the lifecycle of the ticket's [`Order`] as [`OrderEvent`]'s,
+    /// ending after the order is filled, rejected, canceled, expired, or replaced.
+    ///
+    /// <div class="warning">WARNING: There's a max of 10 concurrent streams allowed.</div>
+    ///
+    /// NOTE: You must pin the stream before polling it.
+    ///
+    /// NOTE: Repeated updates are skipped, so each event is for a change in the order.
+    ///
+    /// NOTE: A dry-run ticket's order was only confirmed, so the stream
+    /// only yields [`Error::DryRunTicket`], without connecting.
+    pub fn lifecycle_events<'a>(
+        &'a self,
+        client: &'a Client,
+        account_id: &'a str,
+    ) -> impl Stream<Item = Result<OrderEvent, Error>> + 'a {
+        try_stream! {
+            if self.dry_run {
+                Err(Error::DryRunTicket(self.order_id.clone()))?;
+            }
+
+            let orders_stream = Order::stream_by_ids(client, vec![&self.order_id], account_id);
+            pin_mut!(orders_stream);
+
+            let mut acknowledged = false;
+            let mut filled_quantity = 0.0;
+            while let Some(stream_resp) = orders_stream.next().await {
+                let order = match stream_resp? {
+                    StreamOrdersResp::Order(order) if order.order_id == self.order_id => order,
+                    StreamOrdersResp::Error(err) => {
+                        Err(Error::StreamIssue(format!(
+                            "{}: {}",
+                            err.error,
+                            err.message.unwrap_or_default()
+                        )))?;
+                        continue;
+                    }
+                    _ => continue,
+                };
+
+                let Some(event) = next_event(order, &mut acknowledged, &mut filled_quantity)?
+                else {
+                    continue;
+                };
+                let is_terminal = event.is_terminal();
+
+                yield event;
+                if is_terminal {
+                    break;
+                }
+            }
+        }
+    }
+
+    /// Wait for the ticket's [`Order`] to be filled, rejected, canceled, expired,
+    /// or replaced, returning the [`OrderEvent`] it finished with.
+    ///
+    /// # Errors
+    ///
+    /// - [`Error::OrderWaitTimeout`] if the order isn't done within the timeout.
+    /// - [`Error::StreamIssue`] if the order stream ends, or reports an
+    ///   error, before the order is done.
+    /// - [`Error::DryRunTicket`] right away for a dry-run ticket, since
+    ///   it's order was only confirmed, so it's never done.
+    pub async fn wait_for_terminal(
+        &self,
+        client: &Client,
+        account_id: &str,
+        timeout: Duration,
+    ) -> Result<OrderEvent, Error> {
+        if self.dry_run {
+            return Err(Error::DryRunTicket(self.order_id.clone()));
+        }
+
+        let wait = async {
+            let events = self.lifecycle_events(client, account_id);
+            pin_mut!(events);
+
+            while let Some(event) = events.next().await {
+                let event = event?;
+                if event.is_terminal() {
+                    return Ok(event);
+                }
+            }
+
+            Err(Error::StreamIssue(format!(
+                "the order stream for {} ended before the order was done",
+                self.order_id
+            )))
+        };
+
+        tokio::time::timeout(timeout, wait).await.map_err(|_| {
+            Error::OrderWaitTimeout(format!("{} wasn't done after {timeout:?}", self.order_id))
+        })?
+    }
+}
+
+/// Turn an update of the order into the next [`OrderEvent`], if it changed.
+fn next_event(
+    order: Box<Order>,
+    acknowledged: &mut bool,
+    filled_quantity: &mut f64,
+) -> Result<Option<OrderEvent>, Error> {
+    let event = match order.status.category() {
+        OrderStatusCategory::Filled => OrderEvent::Filled(order),
+        OrderStatusCategory::Rejected => OrderEvent::Rejected {
+            reason: order.status_description.clone(),
+            order,
+        },
+        OrderStatusCategory::Cancelled => OrderEvent::Cancelled(order),
+        OrderStatusCategory::Expired => OrderEvent::Expired(order),
+        OrderStatusCategory::Replaced => OrderEvent::Replaced(order),
+        OrderStatusCategory::PartiallyFilled => {
+            let quantity = match order.legs.first() {
+                Some(leg) => parse_number(&leg.exec_quantity)?,
+                None => 0.0,
+            };
+            if quantity <= *filled_quantity {
+                return Ok(None);
+            }
+
+            *acknowledged = true;
+            *filled_quantity = quantity;
+            OrderEvent::PartiallyFilled {
+                filled_quantity: quantity,
+                order,
+            }
+        }
+        _ if *acknowledged => return Ok(None),
+        _ => {
+            *acknowledged = true;
+            OrderEvent::Acknowledged(order)
+        }
+    };
+
+    Ok(Some(event))
+}
@@ -235,14 +235,34 @@
 //! ```
 //!
 
+/// Builders for the market and time activation rules of conditional [`crate::accounting::orders::Order`]'s.
+pub mod activation;
+/// Builders for bracket [`crate::accounting::orders::Order`] requests, with take profit and stop loss exits.
+pub mod bracket;
 /// Functionality and primitives around [`crate::accounting::orders::Order`] confirmations (pre execution).
 pub mod confirm;
+/// Dry-run mode, confirming [`crate::accounting::orders::Order`]'s instead of placing them.
+pub mod dry_run;
+/// Emergency controls canceling every open [`crate::accounting::orders::Order`], and flattening every position.
+pub mod emergency;
+/// Builders for OCO and bracket [`crate::accounting::orders::Order`] groups, validated by group type.
+pub mod group;
+/// Live trading and kill switch interlocks in front of every [`crate::accounting::orders::Order`] sent.
+pub mod interlock;
+/// Awaiting the outcome of placed [`crate::accounting::orders::Order`]'s, or streaming their lifecycle events.
+pub mod lifecycle;
 /// Functionality and primitives around [`crate::accounting::orders::Order`] specifically at the execution level.
 pub mod orders;
 /// Functionality and abstractions around [`crate::accounting::orders::Order`] requests.
 pub mod request;
+/// Pre-trade risk checks for [`crate::accounting::orders::Order`]'s placed or replaced through a [`crate::Client`].
+pub mod risk;
 /// Functionality and primitives around execution routes.
 pub mod route;
+/// Builders for multi leg option spread [`crate::accounting::orders::Order`] requests.
+pub mod spread;
+/// Idempotent submission of [`crate::accounting::orders::Order`]'s, reconciling submissions without a definitive outcome.
+pub mod submission;
 /// Functionality and primitives around [`crate::accounting::orders::Order`] tickets (post execution).
 pub mod ticket;
 /// Functionality and primitives around [`crate::accounting::orders::Order`] execution triggers.
@@ -251,11 +271,21 @@ pub mod trigger;
 pub mod update;
 
 // Expose these directly from the [`crate::execution`] level
+pub use activation::MarketActivationRuleBuilder;
+pub use bracket::{BracketOrderBuilder, BracketPrice};
+pub use confirm::{ConfirmedOrder, OrderConfirmation};
+pub use emergency::{
+    CancelOutcome, CloseOutcome, EmergencyFailure, EmergencyFilter, EmergencyReport,
+};
+pub use group::{BracketGroupBuilder, OcoGroupBuilder};
+pub use lifecycle::OrderEvent;
 pub use orders::{
-    AdvancedOrderOptions, BPWarningStatus, Duration, OrderRequestLeg, OrderTimeInForce, Oso,
-    PegValue, TradeAction,
+    AdvancedOrderOptions, AdvancedOrderOptionsBuilder, BPWarningStatus, Duration, OrderRequestLeg,
+    OrderTimeInForce, Oso, PegValue, TradeAction,
 };
 pub use request::{OrderRequest, OrderRequestBuilder, OrderRequestGroup, OrderRequestGroupBuilder};
+pub use risk::{AssetTypeLimits, RiskPolicy, RiskViolation};
 pub use route::Route;
+pub use spread::{OptionSpreadOrderBuilder, PositionEffect};
 pub use ticket::OrderTicket;
 pub use trigger::{ActivationTrigger, ActivationTriggerKey};
@@ -1,13 +1,15 @@
 use crate::{
     accounting::{
-        orders::{MarketActivationRule, TimeActivationRule, TrailingStop},
+        orders::{MarketActivationRule, OrderType, TimeActivationRule, TrailingStop},
         OptionType,
     },
+    execution::{dry_run, interlock, risk, submission},
     orders::{Order, OrderRelationship, OrderRequest, OrderRequestGroup, OrderTicket, OrderUpdate},
     responses::{
         execution::{ModifyOrderResp, ModifyOrderRespRaw, OrderResp, OrderRespRaw},
         ApiResponse,
     },
+    utils::parse_number,
     Client, Error,
 };
 use serde::{Deserialize, Serialize};
@@ -48,6 +50,48 @@ impl Order {
         client: &Client,
         order_request: &OrderRequest,
     ) -> Result<Vec<OrderTicket>, Error> {
+        // An earlier submission found placed is returned without checking
+        // the order again, since placing it more than once isn't intended
+        if let Some(tickets) = submission::reconcile_pending(client, order_request).await? {
+            return Ok(tickets);
+        }
+
+        interlock::ensure_can_send_orders(client, &[order_request])?;
+        risk::enforce_orders(client, &[order_request]).await?;
+
+        Self::submit(client, order_request).await
+    }
+
+    /// Place an [`OrderRequest`] which closes a position, bypassing the kill switch
+    /// and [`crate::execution::RiskPolicy`], so positions can always be flattened.
+    ///
+    /// NOTE: Live trading must still be enabled, and in dry-run mode it's only confirmed.
+    pub(crate) async fn place_closing(
+        client: &Client,
+        order_request: &OrderRequest,
+    ) -> Result<Vec<OrderTicket>, Error> {
+        if let Some(tickets) = submission::reconcile_pending(client, order_request).await? {
+            return Ok(tickets);
+        }
+
+        interlock::ensure_can_send_closing_orders(client, &[order_request])?;
+
+        Self::submit(client, order_request).await
+    }
+
+    /// Send an [`OrderRequest`] which passed the client's interlocks and risk policy.
+    async fn submit(
+        client: &Client,
+        order_request: &OrderRequest,
+    ) -> Result<Vec<OrderTicket>, Error> {
+        if client.is_dry_run() {
+            return dry_run::place(client, order_request).await;
+        }
+
+        let submission = submission::begin(client, order_request)?;
+        let order_request = &submission.order_request;
+        let correlation_id = &submission.correlation_id;
+
         let endpoint = String::from("orderexecution/orders");
 
         debug!(
@@ -58,42 +102,54 @@ impl Order {
             trade_action = ?order_request.trade_action,
             quantity = ?order_request.quantity,
             order_type = ?order_request.order_type,
+            correlation_id = %correlation_id,
             "submitting order"
         );
 
-        let response = client
-            .post(&endpoint, &order_request)
-            .await
-            .inspect_err(|order_request_err| {
-                error!(
-                    target: "tradestation::execution",
-                    environment = %client.environment,
-                    account = %order_request.account_id,
-                    symbol = ?order_request.symbol,
-                    trade_action = ?order_request.trade_action,
-                    quantity = ?order_request.quantity,
-                    order_type = ?order_request.order_type,
-                    error = %order_request_err,
-                    outcome = "unknown",
-                    "order submission failed"
-                );
-            })?
-            .json::<ApiResponse<OrderRespRaw>>()
-            .await
-            .inspect_err(|order_request_err| {
-                warn!(
-                    target: "tradestation::execution",
-                    environment = %client.environment,
-                    account = %order_request.account_id,
-                    symbol = ?order_request.symbol,
-                    trade_action = ?order_request.trade_action,
-                    quantity = ?order_request.quantity,
-                    order_type = ?order_request.order_type,
-                    outcome = "unknown",
-                    error = %order_request_err,
-                    "failed to decode order submission response"
-                );
-            })?;
+        let response: Result<ApiResponse<OrderRespRaw>, Error> = async {
+            Ok(client
+                .post(&endpoint, &order_request)
+                .await
+                .inspect_err(|order_request_err| {
+                    error!(
+                        target: "tradestation::execution",
+                        environment = %client.environment,
+                        account = %order_request.account_id,
+                        symbol = ?order_request.symbol,
+                        trade_action = ?order_request.trade_action,
+                        quantity = ?order_request.quantity,
+                        order_type = ?order_request.order_type,
+                        correlation_id = %correlation_id,
+                        error = %order_request_err,
+                        outcome = "unknown",
+                        "order submission failed"
+                    );
+                })?
+                .json::<ApiResponse<OrderRespRaw>>()
+                .await
+                .inspect_err(|order_request_err| {
+                    warn!(
+                        target: "tradestation::execution",
+                        environment = %client.environment,
+                        account = %order_request.account_id,
+                        symbol = ?order_request.symbol,
+                        trade_action = ?order_request.trade_action,
+                        quantity = ?order_request.quantity,
+                        order_type = ?order_request.order_type,
+                        correlation_id = %correlation_id,
+                        outcome = "unknown",
+                        error = %order_request_err,
+                        "failed to decode order submission response"
+                    );
+                })?)
+        }
+        .await;
+
+        // Without a definitive answer, find out if the order was placed before returning
+        let response = match response {
+            Ok(response) => response,
+            Err(error) => return submission.reconcile(client, error).await,
+        };
 
         match response {
             ApiResponse::Success(resp_raw) => {
@@ -193,6 +249,13 @@ impl Order {
     /// a bracket order as one transaction, instead you must update each order within
     /// a bracket.
     ///
+    /// NOTE: The group is validated with [`OrderRequestGroup::validate`] before it's
+    /// sent, returning [`Error::InvalidOrderGroup`] if it breaks the rules of it's type.
+    ///
+    /// <div class="warning">WARNING: Unlike <code>Order::place</code>, groups aren't tagged with a
+    /// correlation id, or reconciled when their submission fails without a definitive outcome,
+    /// so check the account's orders before retrying a group which failed.</div>
+    ///
     /// # Example
     /// ---
     /// Place a trade involving a bracket group of orders with one order
@@ -270,6 +333,14 @@ impl Order {
         client: &Client,
         order_req_group: &OrderRequestGroup,
     ) -> Result<Vec<OrderTicket>, Error> {
+        order_req_group.validate()?;
+        let order_reqs: Vec<&OrderRequest> = order_req_group.order_requests.iter().collect();
+        interlock::ensure_can_send_orders(client, &order_reqs)?;
+        risk::enforce_orders(client, &order_reqs).await?;
+        if client.is_dry_run() {
+            return dry_run::place_group(client, order_req_group).await;
+        }
+
         let endpoint = String::from("orderexecution/ordergroups");
         let order_request_count = order_req_group.order_requests.len();
 
@@ -401,6 +472,9 @@ impl Order {
         client: &Client,
         order_update: OrderUpdate,
     ) -> Result<OrderTicket, Error> {
+        interlock::ensure_can_send(client, [self.account_id.as_str()])?;
+        risk::enforce_replace(client, &self, &order_update).await?;
+
         let endpoint = format!("orderexecution/orders/{}", self.order_id);
 
         debug!(
@@ -593,16 +667,20 @@ impl Order {
     }
 }
 
-#[derive(Clone, Debug, Deserialize, Serialize)]
-#[serde(rename_all = "PascalCase")]
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[serde(rename_all = "PascalCase", default)]
 /// Advanced options for configuring [`Order`]'s.
+///
+/// NOTE: Unset options are left out of the request, see
+/// [`AdvancedOrderOptionsBuilder`] to set only the options you need.
 pub struct AdvancedOrderOptions {
     /// This option allows you to place orders that will
     /// only add liquidity on the route you selected. To
     /// place an Add Liquidity order, the user must also
     /// select Book Only order type.
     ///
-    /// NOTE: Only calid for Equities.
+    /// NOTE: Only valid for Equities.
+    #[serde(skip_serializing_if = "is_false")]
     pub add_liquidity: bool,
 
     /// Use this advanced order feature when you do not
@@ -610,6 +688,7 @@ pub struct AdvancedOrderOptions {
     /// in its entirety or not at all.
     ///
     /// NOTE: Valid for Equities and Options.
+    #[serde(skip_serializing_if = "is_false")]
     pub all_or_none: bool,
 
     /// This option restricts the destination you choose
@@ -620,6 +699,7 @@ pub struct AdvancedOrderOptions {
     /// order to another market center.
     ///
     /// NOTE: Only valid for Equities.
+    #[serde(skip_serializing_if = "is_false")]
     pub book_only: bool,
 
     /// You can use this option to reflect a Bid/Ask
@@ -629,10 +709,12 @@ pub struct AdvancedOrderOptions {
     /// NOTE: Only valid for `Limit` and `StopLimit` orders.
     ///
     /// NOTE: Only valid for Equities.
-    pub discretionary_price: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub discretionary_price: Option<String>,
 
     /// Allows you to specify when an order will be placed
     /// based on the price action of one or more symbols.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
     pub market_activation_rules: Vec<MarketActivationRule>,
 
     /// When you send a non-display order, it will not be
@@ -640,13 +722,15 @@ pub struct AdvancedOrderOptions {
     /// ECN books.
     ///
     /// NOTE: Only valid for Equities.
+    #[serde(skip_serializing_if = "is_false")]
     pub non_display: bool,
 
     /// This order type is useful to achieve a fair price in
     /// a fast or volatile market.
     ///
     /// NOTE: Only valid for Equities.
-    pub peg_value: PegValue,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub peg_value: Option<PegValue>,
 
     /// Hides the true number of shares or contracts intended
     /// to be bought or sold.
@@ -656,13 +740,177 @@ pub struct AdvancedOrderOptions {
     /// NOTE: Only valid for Equities and Futures.
     ///
     /// <div class="warning">NOTE: Not valid for all exchanges.</div>
-    pub show_only_quantity: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub show_only_quantity: Option<String>,
 
     /// Allows you to specify a time that an order will be placed.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
     pub time_activation_rules: Vec<TimeActivationRule>,
 
-    /// Trailing Stop offeset, amount or percent.
-    pub trailing_stop: TrailingStop,
+    /// Trailing Stop offset, amount or percent.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub trailing_stop: Option<TrailingStop>,
+}
+
+impl AdvancedOrderOptions {
+    /// Validate the options can be used together, and with an order's type and quantity.
+    ///
+    /// - `add_liquidity` needs `book_only`.
+    /// - `peg_value` is only valid for `Limit` orders.
+    /// - `discretionary_price` and `show_only_quantity` are only valid for `Limit` and `StopLimit` orders.
+    /// - `show_only_quantity` has to be less than the order's quantity.
+    /// - `trailing_stop` is only valid for `StopMarket` orders, with either an amount or percent.
+    ///
+    /// NOTE: This is called when building an [`OrderRequest`] with advanced options.
+    ///
+    /// # Errors
+    ///
+    /// - [`Error::InvalidAdvancedOptions`] describing the first broken rule.
+    /// - [`Error::InvalidNumber`] if a price, quantity, or trailing stop offset can't be parsed.
+    pub fn validate(&self, order_type: &OrderType, quantity: Option<&str>) -> Result<(), Error> {
+        let invalid = |reason: &str| Err(Error::InvalidAdvancedOptions(reason.into()));
+        let is_limit = matches!(order_type, OrderType::Limit | OrderType::StopLimit);
+
+        if self.add_liquidity && !self.book_only {
+            return invalid("add_liquidity requires book_only");
+        }
+        if self.peg_value.is_some() && !matches!(order_type, OrderType::Limit) {
+            return invalid("peg_value is only valid for Limit orders");
+        }
+        if let Some(price) = &self.discretionary_price {
+            if !is_limit {
+                return invalid("discretionary_price is only valid for Limit and StopLimit orders");
+            }
+            parse_number(price)?;
+        }
+        if let Some(show_only_quantity) = &self.show_only_quantity {
+            if !is_limit {
+                return invalid("show_only_quantity is only valid for Limit and StopLimit orders");
+            }
+            let show_only_quantity = parse_number(show_only_quantity)?;
+            if show_only_quantity <= 0.0 {
+                return invalid("show_only_quantity has to be positive");
+            }
+            if let Some(quantity) = quantity.map(parse_number).transpose()? {
+                if show_only_quantity >= quantity {
+                    return invalid("show_only_quantity has to be less than the order quantity");
+                }
+            }
+        }
+        if let Some(trailing_stop) = &self.trailing_stop {
+            if !matches!(order_type, OrderType::StopMarket) {
+                return invalid("trailing_stop is only valid for StopMarket orders");
+            }
+            match (&trailing_stop.amount, &trailing_stop.percent) {
+                (Some(offset), None) | (None, Some(offset)) => {
+                    if parse_number(offset)? <= 0.0 {
+                        return invalid("trailing_stop offset has to be positive");
+                    }
+                }
+                _ => return invalid("trailing_stop needs either an amount or a percent"),
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+/// A builder pattern for [`AdvancedOrderOptions`], where every option is optional.
+pub struct AdvancedOrderOptionsBuilder {
+    options: AdvancedOrderOptions,
+}
+impl AdvancedOrderOptionsBuilder {
+    /// Initialize a new builder for [`AdvancedOrderOptions`], without any options set.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Only add liquidity on the selected route.
+    ///
+    /// NOTE: Requires `book_only`.
+    pub fn add_liquidity(mut self) -> Self {
+        self.options.add_liquidity = true;
+        self
+    }
+
+    /// Fill the order in its entirety or not at all.
+    pub fn all_or_none(mut self) -> Self {
+        self.options.all_or_none = true;
+        self
+    }
+
+    /// Restrict the route from re-routing the order to another destination.
+    pub fn book_only(mut self) -> Self {
+        self.options.book_only = true;
+        self
+    }
+
+    /// Set the Discretionary Price increment to reflect the Bid/Ask at.
+    pub fn discretionary_price(mut self, price: impl Into<String>) -> Self {
+        self.options.discretionary_price = Some(price.into());
+        self
+    }
+
+    /// Set the rules activating the order on the price action of symbols,
+    /// see [`crate::execution::MarketActivationRuleBuilder`].
+    pub fn market_activation_rules(mut self, rules: Vec<MarketActivationRule>) -> Self {
+        self.options.market_activation_rules = rules;
+        self
+    }
+
+    /// Don't show the order in Market Depth or ECN books.
+    pub fn non_display(mut self) -> Self {
+        self.options.non_display = true;
+        self
+    }
+
+    /// Set the Peg Value the order's price follows.
+    pub fn peg_value(mut self, peg_value: PegValue) -> Self {
+        self.options.peg_value = Some(peg_value);
+        self
+    }
+
+    /// Set the Quantity of shares or contracts to show, hiding the rest.
+    pub fn show_only_quantity(mut self, quantity: impl Into<String>) -> Self {
+        self.options.show_only_quantity = Some(quantity.into());
+        self
+    }
+
+    /// Add a rule activating the order at a time.
+    pub fn time_activation_rule(mut self, rule: TimeActivationRule) -> Self {
+        self.options.time_activation_rules.push(rule);
+        self
+    }
+
+    /// Set the Trailing Stop offset, amount or percent.
+    pub fn trailing_stop(mut self, trailing_stop: TrailingStop) -> Self {
+        self.options.trailing_stop = Some(trailing_stop);
+        self
+    }
+
+    /// Finish building the [`AdvancedOrderOptions`].
+    ///
+    /// NOTE: The options are validated against the order's type and
+    /// quantity when building the [`OrderRequest`] they're set on.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::InvalidAdvancedOptions`] if `add_liquidity` is set without `book_only`.
+    pub fn build(self) -> Result<AdvancedOrderOptions, Error> {
+        if self.options.add_liquidity && !self.options.book_only {
+            return Err(Error::InvalidAdvancedOptions(
+                "add_liquidity requires book_only".into(),
+            ));
+        }
+
+        Ok(self.options)
+    }
+}
+
+/// Whether a flag is unset, to leave it out of requests.
+fn is_false(flag: &bool) -> bool {
+    !flag
 }
 
 #[derive(Clone, Debug, Deserialize, Serialize)]
@@ -681,7 +929,7 @@ pub enum PegValue {
 
 // TODO: There is a similar enum in `crate::account`
 // it should instead just use this enum.
-#[derive(Clone, Debug, Deserialize, Serialize)]
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
 /// The different trade actions that can be sent or
 /// received, and conveys the intent of the [`Order`].
 pub enum TradeAction {
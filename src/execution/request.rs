@@ -7,6 +7,7 @@ use crate::{
         },
         ticket::OrderTicket,
     },
+    market_data::{option_symbol::validate_leg_symbol, PriceFormat},
     Client, Error,
 };
 use serde::{Deserialize, Serialize};
@@ -36,6 +37,7 @@ pub struct OrderRequest {
     /// Order Sends Orders
     pub osos: Option<Vec<Oso>>,
 
+    #[serde(rename = "OrderConfirmID")]
     /// A unique identifier regarding an order used
     /// to prevent duplicates. Must be unique per API
     /// key, per order, per user.
@@ -79,6 +81,49 @@ pub struct OrderRequest {
     /// NOTE: Only required if not provided within order legs.
     pub trade_action: Option<TradeAction>,
 }
+impl OrderRequest {
+    /// Validate the limit and stop prices are on a valid tick of the symbol,
+    /// using the [`PriceFormat`] from the client's [`crate::market_data::SymbolDetailsCache`].
+    ///
+    /// NOTE: Multi leg orders (without a `symbol`) aren't validated, as their
+    /// prices are net prices of every leg.
+    ///
+    /// # Example
+    /// ---
+    ///
+    /// ```ignore
+    /// let order_req = OrderRequestBuilder::new()
+    ///     .account_id("11111111")
+    ///     .symbol("@ES")
+    ///     .trade_action(TradeAction::Buy)
+    ///     .quantity("1")
+    ///     .order_type(OrderType::Limit)
+    ///     .limit_price("5000.10")
+    ///     .time_in_force(OrderTimeInForce {
+    ///         duration: Duration::GTC,
+    ///         expiration: None,
+    ///     })
+    ///     .build()?;
+    ///
+    /// // `@ES` trades in ticks of 0.25, so this is an `Error::PriceNotOnTick`
+    /// assert!(order_req.validate_prices(&client).await.is_err());
+    /// ```
+    pub async fn validate_prices(&self, client: &Client) -> Result<(), Error> {
+        let Some(symbol) = &self.symbol else {
+            return Ok(());
+        };
+        if self.limit_price.is_none() && self.stop_price.is_none() {
+            return Ok(());
+        }
+
+        let price_format = client.get_price_format(symbol).await?;
+        for price in [&self.limit_price, &self.stop_price].into_iter().flatten() {
+            price_format.validate_price(price)?;
+        }
+
+        Ok(())
+    }
+}
 
 #[derive(Debug, Default)]
 /// A builder pattern for [`OrderRequest`].
@@ -91,12 +136,14 @@ pub struct OrderRequestBuilder {
     osos: Option<Vec<Oso>>,
     order_confirm_id: Option<String>,
     order_type: Option<OrderType>,
+    price_format: Option<PriceFormat>,
     quantity: Option<String>,
     route: Option<String>,
     stop_price: Option<String>,
     symbol: Option<String>,
     time_in_force: Option<OrderTimeInForce>,
     trade_action: Option<TradeAction>,
+    validate_option_symbols: Option<bool>,
 }
 impl OrderRequestBuilder {
     /// Initialize a new builder for [`OrderRequest`].
@@ -183,12 +230,29 @@ impl OrderRequestBuilder {
         self
     }
 
+    /// Set the [`PriceFormat`] of the symbol, from it's [`crate::market_data::SymbolDetails`],
+    /// to validate the limit and stop prices are on a valid tick when building.
+    pub fn price_format(mut self, price_format: PriceFormat) -> Self {
+        self.price_format = Some(price_format);
+        self
+    }
+
     /// Set the Legs of the [`OrderRequest`].
     pub fn legs(mut self, legs: Vec<OrderRequestLeg>) -> Self {
         self.legs = Some(legs);
         self
     }
 
+    /// Set if the option leg symbols should be validated as
+    /// [`crate::market_data::OptionSymbol`]'s when building.
+    ///
+    /// NOTE: Defaults to `false`, since not every symbol TradeStation
+    /// accepts can be parsed as an [`crate::market_data::OptionSymbol`].
+    pub fn validate_option_symbols(mut self, validate: bool) -> Self {
+        self.validate_option_symbols = Some(validate);
+        self
+    }
+
     /// Set the Buying Power Warning Status for the [`OrderRequest`].
     pub fn buying_power_warning(mut self, status: BPWarningStatus) -> Self {
         self.buying_power_warning = Some(status);
@@ -204,9 +268,36 @@ impl OrderRequestBuilder {
     /// Finish building the [`OrderRequest`].
     ///
     /// NOTE: `account_id`, `order_type`, and `time_in_force` are all required.
+    ///
+    /// NOTE: When `validate_option_symbols` is set, option leg symbols are validated, returning
+    /// [`Error::InvalidOptionSymbol`] if any can't be parsed as an [`crate::market_data::OptionSymbol`].
+    ///
+    /// NOTE: When a [`PriceFormat`] is set, the limit and stop prices are validated,
+    /// returning [`Error::PriceNotOnTick`] if either isn't on a valid tick.
+    ///
+    /// NOTE: Advanced options are validated against the order type and quantity,
+    /// returning [`Error::InvalidAdvancedOptions`] if they can't be used together.
     pub fn build(self) -> Result<OrderRequest, Error> {
+        if self.validate_option_symbols.unwrap_or(false) {
+            for leg in self.legs.iter().flatten() {
+                validate_leg_symbol(&leg.symbol)?;
+            }
+        }
+        if let Some(price_format) = &self.price_format {
+            for price in [&self.limit_price, &self.stop_price].into_iter().flatten() {
+                price_format.validate_price(price)?;
+            }
+        }
+
+        let account_id = self.account_id.ok_or(Error::AccountIdNotSet)?;
+        let time_in_force = self.time_in_force.ok_or(Error::TimeInForceNotSet)?;
+        let order_type = self.order_type.ok_or(Error::OrderTypeNotSet)?;
+        if let Some(options) = &self.advanced_options {
+            options.validate(&order_type, self.quantity.as_deref())?;
+        }
+
         Ok(OrderRequest {
-            account_id: self.account_id.ok_or(Error::AccountIdNotSet)?,
+            account_id,
             advanced_options: self.advanced_options,
             buying_power_warning: self.buying_power_warning,
             legs: self.legs,
@@ -214,9 +305,9 @@ impl OrderRequestBuilder {
             order_confirm_id: self.order_confirm_id,
             route: self.route,
             trade_action: self.trade_action,
-            time_in_force: self.time_in_force.ok_or(Error::TimeInForceNotSet)?,
+            time_in_force,
             symbol: self.symbol,
-            order_type: self.order_type.ok_or(Error::OrderTypeNotSet)?,
+            order_type,
             quantity: self.quantity,
             stop_price: self.stop_price,
             limit_price: self.limit_price,
@@ -0,0 +1,703 @@
+//! Pre-trade risk checks, enforced by the library before orders are placed or replaced.
+//!
+//! Attach a [`RiskPolicy`] to a [`crate::Client`] with [`crate::Client::set_risk_policy`], and
+//! every [`crate::orders::Order::place`], [`crate::orders::Order::place_group`],
+//! [`crate::orders::Order::replace`], and [`crate::orders::OrderTicket::replace`]
+//! is checked against it first. A violation returns [`crate::Error::RiskViolation`]
+//! without sending anything to TradeStation, and is logged under the
+//! `tradestation::execution` tracing target.
+//!
+//! ```rust,no_run
+//! # use tradestation::{Client, Error};
+//! use tradestation::{
+//!     accounting::AssetType,
+//!     execution::{AssetTypeLimits, RiskPolicy},
+//! };
+//!
+//! # async fn example(client: &Client) -> Result<(), Error> {
+//! client.set_risk_policy(
+//!     RiskPolicy::new()
+//!         .max_order_notional(50_000.0)
+//!         .max_quantity(1_000.0)
+//!         .max_quantity_for_symbol("@ES", 2.0)
+//!         .max_open_orders(10)
+//!         .deny_symbol("GME")
+//!         .price_collar_percent(5.0)
+//!         .asset_type_limits(
+//!             AssetType::StockOption,
+//!             AssetTypeLimits {
+//!                 max_quantity: Some(20.0),
+//!                 max_order_notional: Some(10_000.0),
+//!             },
+//!         ),
+//! );
+//! # Ok(()) }
+//! ```
+
+use crate::{
+    accounting::{AssetType, Order},
+    execution::{request::OrderRequest, update::OrderUpdate},
+    market_data::SymbolDetails,
+    utils::parse_number,
+    Client, Error,
+};
+use std::{
+    collections::{HashMap, HashSet},
+    fmt::Display,
+    sync::{Arc, RwLock},
+};
+use tracing::warn;
+
+#[derive(Clone, Debug, Default, PartialEq)]
+/// Limits for every order of an [`AssetType`].
+pub struct AssetTypeLimits {
+    /// The max quantity of shares or contracts per order.
+    pub max_quantity: Option<f64>,
+
+    /// The max notional value (quantity x price x point value) per order.
+    pub max_order_notional: Option<f64>,
+}
+
+#[derive(Clone, Debug, Default)]
+/// A set of pre-trade risk rules orders have to pass before being sent.
+///
+/// Every rule is optional, and a new policy doesn't have any.
+///
+/// NOTE: Notional and price collar checks are only applied to single symbol
+/// orders, since the prices of multi leg orders are net prices of every leg.
+///
+/// NOTE: Orders a notional or price collar check can't be done for, since the
+/// symbol's last price isn't available, are blocked with [`RiskViolation::PriceUnavailable`].
+pub struct RiskPolicy {
+    max_order_notional: Option<f64>,
+    max_quantity: Option<f64>,
+    symbol_max_quantities: HashMap<String, f64>,
+    max_open_orders: Option<usize>,
+    allowed_symbols: Option<HashSet<String>>,
+    denied_symbols: HashSet<String>,
+    price_collar_percent: Option<f64>,
+    asset_type_limits: HashMap<AssetType, AssetTypeLimits>,
+}
+impl RiskPolicy {
+    /// Create a new [`RiskPolicy`] without any rules.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the max notional value (quantity x price x point value) of an order.
+    ///
+    /// NOTE: Market orders use the last price of the symbol.
+    pub fn max_order_notional(mut self, max_notional: f64) -> Self {
+        self.max_order_notional = Some(max_notional);
+        self
+    }
+
+    /// Set the max quantity of shares or contracts of an order, for any symbol.
+    pub fn max_quantity(mut self, max_quantity: f64) -> Self {
+        self.max_quantity = Some(max_quantity);
+        self
+    }
+
+    /// Set the max quantity of shares or contracts of an order for a symbol,
+    /// overriding `max_quantity`.
+    pub fn max_quantity_for_symbol(mut self, symbol: impl Into<String>, max_quantity: f64) -> Self {
+        self.symbol_max_quantities
+            .insert(symbol.into().to_uppercase(), max_quantity);
+        self
+    }
+
+    /// Set the max amount of open orders per account, including the orders being placed.
+    pub fn max_open_orders(mut self, max_open_orders: usize) -> Self {
+        self.max_open_orders = Some(max_open_orders);
+        self
+    }
+
+    /// Allow trading a symbol, once a symbol is allowed every other symbol is denied.
+    pub fn allow_symbol(mut self, symbol: impl Into<String>) -> Self {
+        self.allowed_symbols
+            .get_or_insert_with(HashSet::new)
+            .insert(symbol.into().to_uppercase());
+        self
+    }
+
+    /// Deny trading a symbol.
+    pub fn deny_symbol(mut self, symbol: impl Into<String>) -> Self {
+        self.denied_symbols.insert(symbol.into().to_uppercase());
+        self
+    }
+
+    /// Set the max percent a limit or stop price can be away from
+    /// the symbol's last price, where `5.0` is 5%.
+    pub fn price_collar_percent(mut self, max_deviation_percent: f64) -> Self {
+        self.price_collar_percent = Some(max_deviation_percent);
+        self
+    }
+
+    /// Set the limits of orders for an [`AssetType`].
+    pub fn asset_type_limits(mut self, asset_type: AssetType, limits: AssetTypeLimits) -> Self {
+        self.asset_type_limits.insert(asset_type, limits);
+        self
+    }
+
+    /// Check an [`OrderRequest`], and the orders it sends, against the policy.
+    ///
+    /// NOTE: This is done automatically by [`crate::orders::Order::place`]
+    /// when the policy is attached to the [`Client`].
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::RiskViolation`] for the first broken rule, or an error
+    /// fetching the symbol details, quotes, or open orders the rules need.
+    pub async fn check_order(
+        &self,
+        client: &Client,
+        order_req: &OrderRequest,
+    ) -> Result<(), Error> {
+        self.check_orders(client, &[order_req]).await
+    }
+
+    /// Check orders being placed together against the policy.
+    async fn check_orders(
+        &self,
+        client: &Client,
+        order_reqs: &[&OrderRequest],
+    ) -> Result<(), Error> {
+        let mut exposures = Vec::new();
+        let mut new_orders: HashMap<&str, usize> = HashMap::new();
+        for order_req in order_reqs {
+            collect_exposures(order_req, &mut exposures, &mut new_orders)?;
+        }
+
+        self.check_exposures(client, &exposures).await?;
+        for (account_id, new_orders) in new_orders {
+            self.check_open_orders(client, account_id, new_orders)
+                .await?;
+        }
+
+        Ok(())
+    }
+
+    /// Check replacing an open [`Order`] with an [`OrderUpdate`] against the policy.
+    ///
+    /// NOTE: This is done automatically by [`crate::orders::Order::replace`]
+    /// when the policy is attached to the [`Client`].
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::RiskViolation`] for the first broken rule, or an error
+    /// fetching the symbol details or quotes the rules need.
+    pub async fn check_replace(
+        &self,
+        client: &Client,
+        order: &Order,
+        order_update: &OrderUpdate,
+    ) -> Result<(), Error> {
+        let single = order.legs.len() == 1;
+        let price = [
+            &order_update.limit_price,
+            &order_update.stop_price,
+            &order.limit_price,
+            &order.stop_price,
+        ]
+        .into_iter()
+        .flatten()
+        .next()
+        .map(|price| parse_number(price))
+        .transpose()?;
+
+        let exposures = order
+            .legs
+            .iter()
+            .map(|leg| {
+                let quantity = match (&order_update.quantity, single) {
+                    (Some(quantity), true) => quantity,
+                    _ => &leg.quantity_ordered,
+                };
+
+                Ok(Exposure {
+                    account_id: &order.account_id,
+                    symbol: leg.symbol.to_uppercase(),
+                    quantity: parse_number(quantity)?,
+                    price: if single { price } else { None },
+                    single,
+                })
+            })
+            .collect::<Result<Vec<_>, Error>>()?;
+
+        self.check_exposures(client, &exposures).await
+    }
+
+    /// Check the symbols, quantities, and prices of orders against the policy.
+    async fn check_exposures(
+        &self,
+        client: &Client,
+        exposures: &[Exposure<'_>],
+    ) -> Result<(), Error> {
+        // Check the rules which don't need any requests first
+        for exposure in exposures {
+            let symbol = &exposure.symbol;
+            if self.denied_symbols.contains(symbol) {
+                return exposure.violation(client, RiskViolation::SymbolDenied(symbol.clone()));
+            }
+            if let Some(allowed) = &self.allowed_symbols {
+                if !allowed.contains(symbol) {
+                    return exposure
+                        .violation(client, RiskViolation::SymbolNotAllowed(symbol.clone()));
+                }
+            }
+            if let Some(max) = self
+                .symbol_max_quantities
+                .get(symbol)
+                .or(self.max_quantity.as_ref())
+            {
+                if exposure.quantity > *max {
+                    return exposure.violation(
+                        client,
+                        RiskViolation::QuantityExceeded {
+                            symbol: symbol.clone(),
+                            quantity: exposure.quantity,
+                            max: *max,
+                        },
+                    );
+                }
+            }
+        }
+
+        let needs_details = self.max_order_notional.is_some() || !self.asset_type_limits.is_empty();
+        let needs_quotes = self.price_collar_percent.is_some() || needs_details;
+        if !needs_details && !needs_quotes {
+            return Ok(());
+        }
+
+        let details: HashMap<String, SymbolDetails> = if needs_details {
+            let symbols: Vec<&str> = exposures.iter().map(|e| e.symbol.as_str()).collect();
+            // Key by the requested symbol, since the details are in the same order
+            let details = client.get_cached_symbol_details(&symbols).await?;
+            symbols
+                .into_iter()
+                .map(str::to_string)
+                .zip(details)
+                .collect()
+        } else {
+            HashMap::new()
+        };
+
+        // Only single symbol orders have a price to collar or value
+        let priced: Vec<&Exposure> = exposures.iter().filter(|e| e.single).collect();
+        let quote_symbols: Vec<&str> = priced
+            .iter()
+            .filter(|e| self.price_collar_percent.is_some() || (needs_details && e.price.is_none()))
+            .map(|e| e.symbol.as_str())
+            .collect();
+        let last_prices: HashMap<String, f64> = if quote_symbols.is_empty() {
+            HashMap::new()
+        } else {
+            client
+                .get_quotes(quote_symbols)
+                .await?
+                .into_iter()
+                .filter_map(|quote| {
+                    let last = parse_number(&quote.last).ok()?;
+                    Some((quote.symbol.to_uppercase(), last))
+                })
+                .collect()
+        };
+
+        for exposure in exposures {
+            let symbol = &exposure.symbol;
+            // A last price of zero can't be collared against, or value an order
+            let last_price = last_prices
+                .get(symbol)
+                .copied()
+                .filter(|last_price| *last_price != 0.0);
+
+            if let (Some(max_deviation), Some(price), true) =
+                (self.price_collar_percent, exposure.price, exposure.single)
+            {
+                let Some(last_price) = last_price else {
+                    return exposure
+                        .violation(client, RiskViolation::PriceUnavailable(symbol.clone()));
+                };
+
+                let deviation = ((price - last_price) / last_price).abs() * 100.0;
+                if deviation > max_deviation {
+                    return exposure.violation(
+                        client,
+                        RiskViolation::PriceOutsideCollar {
+                            symbol: symbol.clone(),
+                            price,
+                            last_price,
+                            max_deviation_percent: max_deviation,
+                        },
+                    );
+                }
+            }
+
+            if !needs_details {
+                continue;
+            }
+            let details = details
+                .get(symbol)
+                .ok_or_else(|| Error::SymbolNotFound(symbol.clone()))?;
+            let limits = self.asset_type_limits.get(&details.asset_type);
+
+            if let Some(max) = limits.and_then(|limits| limits.max_quantity) {
+                if exposure.quantity > max {
+                    return exposure.violation(
+                        client,
+                        RiskViolation::AssetTypeQuantityExceeded {
+                            asset_type: details.asset_type.clone(),
+                            symbol: symbol.clone(),
+                            quantity: exposure.quantity,
+                            max,
+                        },
+                    );
+                }
+            }
+
+            let max_asset_type_notional = limits.and_then(|limits| limits.max_order_notional);
+            if !exposure.single
+                || (self.max_order_notional.is_none() && max_asset_type_notional.is_none())
+            {
+                continue;
+            }
+            let Some(price) = exposure.price.or(last_price) else {
+                return exposure.violation(client, RiskViolation::PriceUnavailable(symbol.clone()));
+            };
+            let point_value = parse_number(&details.price_format.point_value)?;
+            let notional = (exposure.quantity * price * point_value).abs();
+
+            if let Some(max) = self.max_order_notional {
+                if notional > max {
+                    return exposure.violation(
+                        client,
+                        RiskViolation::NotionalExceeded {
+                            symbol: symbol.clone(),
+                            notional,
+                            max,
+                        },
+                    );
+                }
+            }
+            if let Some(max) = max_asset_type_notional {
+                if notional > max {
+                    return exposure.violation(
+                        client,
+                        RiskViolation::AssetTypeNotionalExceeded {
+                            asset_type: details.asset_type.clone(),
+                            symbol: symbol.clone(),
+                            notional,
+                            max,
+                        },
+                    );
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Check placing more orders won't go over the max amount of open orders in an account.
+    async fn check_open_orders(
+        &self,
+        client: &Client,
+        account_id: &str,
+        new_orders: usize,
+    ) -> Result<(), Error> {
+        let Some(max) = self.max_open_orders else {
+            return Ok(());
+        };
+
+        let open_orders = Order::get_all_by_account(client, account_id)
+            .await?
+            .iter()
+            .filter(|order| order.status.is_open())
+            .count();
+        if open_orders + new_orders > max {
+            let violation = RiskViolation::OpenOrdersExceeded {
+                account_id: account_id.to_string(),
+                open_orders,
+                new_orders,
+                max,
+            };
+            log_violation(client, account_id, None, &violation);
+
+            return Err(Error::RiskViolation(violation));
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+/// A broken [`RiskPolicy`] rule.
+pub enum RiskViolation {
+    /// The symbol is denied.
+    SymbolDenied(String),
+
+    /// The symbol isn't one of the allowed symbols.
+    SymbolNotAllowed(String),
+
+    /// The order's quantity is over the max quantity for it's symbol.
+    QuantityExceeded {
+        /// The symbol of the order.
+        symbol: String,
+        /// The quantity of the order.
+        quantity: f64,
+        /// The max quantity allowed.
+        max: f64,
+    },
+
+    /// The order's notional value is over the max notional value.
+    NotionalExceeded {
+        /// The symbol of the order.
+        symbol: String,
+        /// The notional value of the order.
+        notional: f64,
+        /// The max notional value allowed.
+        max: f64,
+    },
+
+    /// The order's quantity is over the max quantity for it's asset type.
+    AssetTypeQuantityExceeded {
+        /// The asset type of the symbol.
+        asset_type: AssetType,
+        /// The symbol of the order.
+        symbol: String,
+        /// The quantity of the order.
+        quantity: f64,
+        /// The max quantity allowed.
+        max: f64,
+    },
+
+    /// The order's notional value is over the max notional value for it's asset type.
+    AssetTypeNotionalExceeded {
+        /// The asset type of the symbol.
+        asset_type: AssetType,
+        /// The symbol of the order.
+        symbol: String,
+        /// The notional value of the order.
+        notional: f64,
+        /// The max notional value allowed.
+        max: f64,
+    },
+
+    /// The order's limit or stop price is too far from the symbol's last price.
+    PriceOutsideCollar {
+        /// The symbol of the order.
+        symbol: String,
+        /// The limit or stop price of the order.
+        price: f64,
+        /// The last price of the symbol.
+        last_price: f64,
+        /// The max percent the price can be away from the last price.
+        max_deviation_percent: f64,
+    },
+
+    /// The symbol's last price, needed to value or collar the order, isn't available.
+    PriceUnavailable(String),
+
+    /// Placing the orders would go over the max amount of open orders in the account.
+    OpenOrdersExceeded {
+        /// The account of the orders.
+        account_id: String,
+        /// The amount of orders already open in the account.
+        open_orders: usize,
+        /// The amount of orders being placed.
+        new_orders: usize,
+        /// The max amount of open orders allowed.
+        max: usize,
+    },
+}
+impl Display for RiskViolation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::SymbolDenied(symbol) => write!(f, "{symbol} is denied"),
+            Self::SymbolNotAllowed(symbol) => write!(f, "{symbol} isn't an allowed symbol"),
+            Self::QuantityExceeded {
+                symbol,
+                quantity,
+                max,
+            } => write!(f, "quantity {quantity} of {symbol} is over the max of {max}"),
+            Self::NotionalExceeded {
+                symbol,
+                notional,
+                max,
+            } => write!(f, "notional {notional:.2} of {symbol} is over the max of {max:.2}"),
+            Self::AssetTypeQuantityExceeded {
+                asset_type,
+                symbol,
+                quantity,
+                max,
+            } => write!(
+                f,
+                "quantity {quantity} of {symbol} is over the {asset_type:?} max of {max}"
+            ),
+            Self::AssetTypeNotionalExceeded {
+                asset_type,
+                symbol,
+                notional,
+                max,
+            } => write!(
+                f,
+                "notional {notional:.2} of {symbol} is over the {asset_type:?} max of {max:.2}"
+            ),
+            Self::PriceOutsideCollar {
+                symbol,
+                price,
+                last_price,
+                max_deviation_percent,
+            } => write!(
+                f,
+                "price {price} of {symbol} is more than {max_deviation_percent}% away from the last price {last_price}"
+            ),
+            Self::PriceUnavailable(symbol) => {
+                write!(f, "{symbol} has no last price to check the order against")
+            }
+            Self::OpenOrdersExceeded {
+                account_id,
+                open_orders,
+                new_orders,
+                max,
+            } => write!(
+                f,
+                "{new_orders} new orders with {open_orders} open orders in account {account_id} is over the max of {max}"
+            ),
+        }
+    }
+}
+
+impl Client {
+    /// Attach a [`RiskPolicy`] to the client, checking every order placed
+    /// or replaced through it, and every clone of it.
+    pub fn set_risk_policy(&self, policy: RiskPolicy) {
+        *self.write_risk_policy() = Some(Arc::new(policy));
+    }
+
+    /// Remove the [`RiskPolicy`] attached to the client.
+    pub fn clear_risk_policy(&self) {
+        *self.write_risk_policy() = None;
+    }
+
+    /// The [`RiskPolicy`] attached to the client, if there is one.
+    pub fn risk_policy(&self) -> Option<Arc<RiskPolicy>> {
+        self.risk_policy
+            .read()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .clone()
+    }
+
+    /// Write access to the attached risk policy, recovering from a poisoned lock.
+    fn write_risk_policy(&self) -> std::sync::RwLockWriteGuard<'_, Option<Arc<RiskPolicy>>> {
+        self.risk_policy
+            .write()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+/// The shared slot a [`Client`] and it's clones keep their [`RiskPolicy`] in.
+pub(crate) type SharedRiskPolicy = Arc<RwLock<Option<Arc<RiskPolicy>>>>;
+
+/// Check orders against the client's [`RiskPolicy`], if it has one.
+pub(crate) async fn enforce_orders(
+    client: &Client,
+    order_reqs: &[&OrderRequest],
+) -> Result<(), Error> {
+    match client.risk_policy() {
+        Some(policy) => policy.check_orders(client, order_reqs).await,
+        None => Ok(()),
+    }
+}
+
+/// Check replacing an order against the client's [`RiskPolicy`], if it has one.
+pub(crate) async fn enforce_replace(
+    client: &Client,
+    order: &Order,
+    order_update: &OrderUpdate,
+) -> Result<(), Error> {
+    match client.risk_policy() {
+        Some(policy) => policy.check_replace(client, order, order_update).await,
+        None => Ok(()),
+    }
+}
+
+/// The symbol and size of a single order, or a single leg of an order.
+struct Exposure<'a> {
+    account_id: &'a str,
+    symbol: String,
+    quantity: f64,
+    price: Option<f64>,
+    /// Whether this is a single symbol order, with a price for just this symbol.
+    single: bool,
+}
+impl Exposure<'_> {
+    /// Log and return a violation of the policy by this order.
+    fn violation(&self, client: &Client, violation: RiskViolation) -> Result<(), Error> {
+        log_violation(client, self.account_id, Some(&self.symbol), &violation);
+
+        Err(Error::RiskViolation(violation))
+    }
+}
+
+/// Collect the exposures of an order, and the orders it sends, and count the new orders per account.
+fn collect_exposures<'a>(
+    order_req: &'a OrderRequest,
+    exposures: &mut Vec<Exposure<'a>>,
+    new_orders: &mut HashMap<&'a str, usize>,
+) -> Result<(), Error> {
+    *new_orders.entry(&order_req.account_id).or_default() += 1;
+
+    match (&order_req.symbol, &order_req.quantity) {
+        (Some(symbol), Some(quantity)) => {
+            let price = order_req
+                .limit_price
+                .as_ref()
+                .or(order_req.stop_price.as_ref())
+                .map(|price| parse_number(price))
+                .transpose()?;
+
+            exposures.push(Exposure {
+                account_id: &order_req.account_id,
+                symbol: symbol.to_uppercase(),
+                quantity: parse_number(quantity)?,
+                price,
+                single: true,
+            });
+        }
+        _ => {
+            for leg in order_req.legs.iter().flatten() {
+                exposures.push(Exposure {
+                    account_id: &order_req.account_id,
+                    symbol: leg.symbol.to_uppercase(),
+                    quantity: parse_number(&leg.quantity)?,
+                    price: None,
+                    single: false,
+                });
+            }
+        }
+    }
+
+    for order_req in order_req.osos.iter().flatten().flat_map(|oso| &oso.orders) {
+        collect_exposures(order_req, exposures, new_orders)?;
+    }
+
+    Ok(())
+}
+
+/// Log a violation under the execution tracing target.
+fn log_violation(
+    client: &Client,
+    account_id: &str,
+    symbol: Option<&str>,
+    violation: &RiskViolation,
+) {
+    warn!(
+        target: "tradestation::execution",
+        environment = %client.environment,
+        account = %account_id,
+        symbol = ?symbol,
+        violation = %violation,
+        outcome = "blocked",
+        "order blocked by risk policy"
+    );
+}
@@ -0,0 +1,566 @@
+//! Build multi leg option spread [`OrderRequest`]'s from option symbols,
+//! [`crate::market_data::OptionChain`] rows, or [`crate::market_data::OptionSpreadStrikes`] results.
+//!
+//! Every leg has a ratio, where a positive ratio buys and a negative ratio sells
+//! the leg when buying the spread (like [`crate::market_data::options::OptionSpreadLeg`]'s ratio). Selling the
+//! spread flips every leg, and the [`PositionEffect`] picks between the `*ToOpen`
+//! and `*ToClose` trade actions.
+//!
+//! ```rust
+//! use tradestation::{
+//!     accounting::OrderType,
+//!     execution::{Duration, OptionSpreadOrderBuilder, OrderTimeInForce, TradeAction},
+//!     market_data::{OptionSymbol, OptionTradeAction},
+//! };
+//!
+//! // Sell 2 SPY Dec 20th 2024 $580/$590 call spreads for a $3.10 credit
+//! let order_req = OptionSpreadOrderBuilder::vertical(
+//!     OptionSymbol::parse("SPY 241220C580").unwrap(),
+//!     OptionSymbol::parse("SPY 241220C590").unwrap(),
+//! )
+//! .account_id("11111111")
+//! .quantity(2)
+//! .trade_action(OptionTradeAction::Sell)
+//! .order_type(OrderType::Limit)
+//! .limit_price("3.10")
+//! .time_in_force(OrderTimeInForce {
+//!     duration: Duration::DAY,
+//!     expiration: None,
+//! })
+//! .build()
+//! .unwrap();
+//!
+//! let legs = order_req.legs.unwrap();
+//! assert_eq!(legs[0].symbol, "SPY 241220C580");
+//! assert!(matches!(legs[0].trade_action, TradeAction::SellToOpen));
+//! assert_eq!(legs[0].quantity, "2");
+//! assert!(matches!(legs[1].trade_action, TradeAction::BuyToOpen));
+//!
+//! // A vertical needs 2 different strikes
+//! assert!(OptionSpreadOrderBuilder::vertical(
+//!     OptionSymbol::parse("SPY 241220C580").unwrap(),
+//!     OptionSymbol::parse("SPY 241220C580").unwrap(),
+//! )
+//! .account_id("11111111")
+//! .build()
+//! .is_err());
+//! ```
+
+use crate::{
+    accounting::{orders::OrderType, OptionType},
+    execution::{
+        orders::{OrderTimeInForce, TradeAction},
+        request::{OrderRequest, OrderRequestBuilder},
+    },
+    market_data::{
+        options::OptionSpreadLeg, OptionChain, OptionSpreadType, OptionSymbol,
+        OptionSymbolExpiration, OptionTradeAction,
+    },
+    utils::parse_number,
+    Error,
+};
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+/// Whether an order opens a new position, or closes an existing one.
+pub enum PositionEffect {
+    #[default]
+    /// Open a new position (`BuyToOpen` / `SellToOpen`).
+    Open,
+
+    /// Close an existing position (`BuyToClose` / `SellToClose`).
+    Close,
+}
+
+#[derive(Clone, Debug)]
+/// A builder pattern for option spread [`OrderRequest`]'s.
+///
+/// NOTE: The legs are validated against the [`OptionSpreadType`] when
+/// building, returning [`Error::MismatchedOptionLegs`] if they don't
+/// make up that spread.
+pub struct OptionSpreadOrderBuilder {
+    spread_type: OptionSpreadType,
+    legs: Vec<(OptionSymbol, i32)>,
+    quantity: u32,
+    trade_action: OptionTradeAction,
+    position_effect: PositionEffect,
+    account_id: Option<String>,
+    order_type: Option<OrderType>,
+    limit_price: Option<String>,
+    stop_price: Option<String>,
+    time_in_force: Option<OrderTimeInForce>,
+    route: Option<String>,
+}
+impl OptionSpreadOrderBuilder {
+    /// Initialize a new builder for a spread type, without any legs.
+    pub fn new(spread_type: OptionSpreadType) -> Self {
+        OptionSpreadOrderBuilder {
+            spread_type,
+            legs: Vec::new(),
+            quantity: 1,
+            trade_action: OptionTradeAction::Buy,
+            position_effect: PositionEffect::default(),
+            account_id: None,
+            order_type: None,
+            limit_price: None,
+            stop_price: None,
+            time_in_force: None,
+            route: None,
+        }
+    }
+
+    /// A vertical spread, buying one strike and selling another
+    /// strike of the same option type and expiration.
+    pub fn vertical(long: OptionSymbol, short: OptionSymbol) -> Self {
+        Self::new(OptionSpreadType::Vertical)
+            .leg(long, 1)
+            .leg(short, -1)
+    }
+
+    /// A straddle, buying a call and a put at the same strike and expiration.
+    pub fn straddle(call: OptionSymbol, put: OptionSymbol) -> Self {
+        Self::new(OptionSpreadType::Straddle)
+            .leg(call, 1)
+            .leg(put, 1)
+    }
+
+    /// A strangle, buying a call and a put at different strikes, but the same expiration.
+    pub fn strangle(call: OptionSymbol, put: OptionSymbol) -> Self {
+        Self::new(OptionSpreadType::Strangle)
+            .leg(call, 1)
+            .leg(put, 1)
+    }
+
+    /// A butterfly, buying the lower and upper strikes and selling 2 of the
+    /// middle strike, all of the same option type and expiration.
+    pub fn butterfly(lower: OptionSymbol, middle: OptionSymbol, upper: OptionSymbol) -> Self {
+        Self::new(OptionSpreadType::Butterfly)
+            .leg(lower, 1)
+            .leg(middle, -2)
+            .leg(upper, 1)
+    }
+
+    /// A condor, buying the outer strikes and selling the inner strikes,
+    /// all of the same option type and expiration.
+    pub fn condor(
+        lower: OptionSymbol,
+        lower_middle: OptionSymbol,
+        upper_middle: OptionSymbol,
+        upper: OptionSymbol,
+    ) -> Self {
+        Self::new(OptionSpreadType::Condor)
+            .leg(lower, 1)
+            .leg(lower_middle, -1)
+            .leg(upper_middle, -1)
+            .leg(upper, 1)
+    }
+
+    /// An iron condor, buying the outer put and call and selling the
+    /// inner put and call, all of the same expiration.
+    ///
+    /// NOTE: Buying the spread collects a credit, which TradeStation
+    /// represents as a negative limit price, e.g. `"-1.16"`.
+    pub fn iron_condor(
+        long_put: OptionSymbol,
+        short_put: OptionSymbol,
+        short_call: OptionSymbol,
+        long_call: OptionSymbol,
+    ) -> Self {
+        Self::new(OptionSpreadType::IronCondor)
+            .leg(long_put, 1)
+            .leg(short_put, -1)
+            .leg(short_call, -1)
+            .leg(long_call, 1)
+    }
+
+    /// A calendar spread, selling the near expiration and buying the far
+    /// expiration, at the same strike and option type.
+    pub fn calendar(near: OptionSymbol, far: OptionSymbol) -> Self {
+        Self::new(OptionSpreadType::Calendar)
+            .leg(near, -1)
+            .leg(far, 1)
+    }
+
+    /// A diagonal spread, selling the near expiration and buying the far
+    /// expiration, at different strikes of the same option type.
+    pub fn diagonal(near: OptionSymbol, far: OptionSymbol) -> Self {
+        Self::new(OptionSpreadType::Diagonal)
+            .leg(near, -1)
+            .leg(far, 1)
+    }
+
+    /// Initialize a builder from an [`OptionChain`] row, using it's legs and
+    /// ratios, for the spread type the chain was streamed with.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::InvalidOptionSymbol`] if a leg isn't an option symbol.
+    pub fn from_option_chain(
+        chain: &OptionChain,
+        spread_type: OptionSpreadType,
+    ) -> Result<Self, Error> {
+        Self::from_spread_legs(&chain.legs, spread_type)
+    }
+
+    /// Initialize a builder from [`OptionSpreadLeg`]'s and their ratios.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::InvalidOptionSymbol`] if a leg isn't an option symbol.
+    pub fn from_spread_legs(
+        legs: &[OptionSpreadLeg],
+        spread_type: OptionSpreadType,
+    ) -> Result<Self, Error> {
+        legs.iter()
+            .try_fold(Self::new(spread_type), |builder, leg| {
+                Ok(builder.leg(OptionSymbol::try_from(leg)?, leg.ratio))
+            })
+    }
+
+    /// Initialize a builder from a single spread's strikes, from the
+    /// [`crate::market_data::OptionSpreadStrikes::strikes`] of a spread type.
+    ///
+    /// The strikes are expected in ascending order, and are used as:
+    /// - Vertical: buy the first strike, sell the second.
+    /// - Straddle: a call and a put at the strike.
+    /// - Strangle: a put at the first strike, and a call at the second.
+    /// - Butterfly and Condor: buy the outer strikes, sell the inner strikes.
+    /// - IronCondor: puts at the lower 2 strikes, calls at the upper 2 strikes.
+    /// - Calendar: sell the first expiration, buy the second at the same strike.
+    /// - Diagonal: sell the first strike and expiration, buy the second.
+    ///
+    /// NOTE: Calendar and Diagonal spreads need 2 expirations, every other spread uses the first.
+    ///
+    /// # Errors
+    ///
+    /// - [`Error::InvalidNumber`] if a strike can't be parsed.
+    /// - [`Error::ExpirationNotSet`] if an expiration is missing.
+    /// - [`Error::MismatchedOptionLegs`] if there's the wrong amount of strikes,
+    ///   or the spread type isn't supported.
+    pub fn from_strikes(
+        spread_type: OptionSpreadType,
+        root: &str,
+        expirations: &[OptionSymbolExpiration],
+        option_type: OptionType,
+        strikes: &[String],
+    ) -> Result<Self, Error> {
+        let strikes = strikes
+            .iter()
+            .map(|strike| parse_number(strike))
+            .collect::<Result<Vec<f64>, Error>>()?;
+        let near = *expirations.first().ok_or(Error::ExpirationNotSet)?;
+
+        let symbol = |expiration, option_type, strike| OptionSymbol {
+            root: root.to_uppercase(),
+            expiration,
+            option_type,
+            strike,
+        };
+        let leg = |strike| symbol(near, option_type, strike);
+
+        Ok(match (&spread_type, strikes.as_slice()) {
+            (OptionSpreadType::Vertical, &[long, short]) => Self::vertical(leg(long), leg(short)),
+            (OptionSpreadType::Straddle, &[strike]) => Self::straddle(
+                symbol(near, OptionType::Call, strike),
+                symbol(near, OptionType::Put, strike),
+            ),
+            (OptionSpreadType::Strangle, &[put, call]) => Self::strangle(
+                symbol(near, OptionType::Call, call),
+                symbol(near, OptionType::Put, put),
+            ),
+            (OptionSpreadType::Butterfly, &[lower, middle, upper]) => {
+                Self::butterfly(leg(lower), leg(middle), leg(upper))
+            }
+            (OptionSpreadType::Condor, &[a, b, c, d]) => {
+                Self::condor(leg(a), leg(b), leg(c), leg(d))
+            }
+            (OptionSpreadType::IronCondor, &[a, b, c, d]) => Self::iron_condor(
+                symbol(near, OptionType::Put, a),
+                symbol(near, OptionType::Put, b),
+                symbol(near, OptionType::Call, c),
+                symbol(near, OptionType::Call, d),
+            ),
+            (OptionSpreadType::Calendar, &[strike]) => {
+                let far = *expirations.get(1).ok_or(Error::ExpirationNotSet)?;
+                Self::calendar(leg(strike), symbol(far, option_type, strike))
+            }
+            (OptionSpreadType::Diagonal, &[near_strike, far_strike]) => {
+                let far = *expirations.get(1).ok_or(Error::ExpirationNotSet)?;
+                Self::diagonal(leg(near_strike), symbol(far, option_type, far_strike))
+            }
+            (spread_type, strikes) => {
+                return Err(Error::MismatchedOptionLegs(format!(
+                    "can't build a {spread_type:?} from {} strikes",
+                    strikes.len()
+                )))
+            }
+        })
+    }
+
+    /// Add a leg to the spread.
+    ///
+    /// NOTE: Use a positive ratio to buy the leg, and a negative ratio
+    /// to sell the leg, when buying the spread.
+    pub fn leg(mut self, symbol: OptionSymbol, ratio: i32) -> Self {
+        self.legs.push((symbol, ratio));
+        self
+    }
+
+    /// Set the amount of spreads to trade, every leg's quantity is it's ratio times this.
+    ///
+    /// NOTE: Defaults to `1`.
+    pub fn quantity(mut self, quantity: u32) -> Self {
+        self.quantity = quantity;
+        self
+    }
+
+    /// Set whether to buy or sell the spread.
+    ///
+    /// NOTE: Defaults to `OptionTradeAction::Buy`, selling flips every leg.
+    pub fn trade_action(mut self, trade_action: OptionTradeAction) -> Self {
+        self.trade_action = trade_action;
+        self
+    }
+
+    /// Set whether the order opens or closes the spread.
+    ///
+    /// NOTE: Defaults to `PositionEffect::Open`.
+    pub fn position_effect(mut self, position_effect: PositionEffect) -> Self {
+        self.position_effect = position_effect;
+        self
+    }
+
+    /// Set the Account ID the [`OrderRequest`] belongs to.
+    ///
+    /// NOTE: Required to be set to build an [`OrderRequest`].
+    pub fn account_id(mut self, id: impl Into<String>) -> Self {
+        self.account_id = Some(id.into());
+        self
+    }
+
+    /// Set the Order Type for the [`OrderRequest`].
+    ///
+    /// NOTE: Required to be set to build an [`OrderRequest`].
+    pub fn order_type(mut self, order_type: OrderType) -> Self {
+        self.order_type = Some(order_type);
+        self
+    }
+
+    /// Set the net Limit Price of the spread.
+    pub fn limit_price(mut self, price: impl Into<String>) -> Self {
+        self.limit_price = Some(price.into());
+        self
+    }
+
+    /// Set the net Stop Price of the spread.
+    pub fn stop_price(mut self, price: impl Into<String>) -> Self {
+        self.stop_price = Some(price.into());
+        self
+    }
+
+    /// Set the Time In Force (duration or expiration timestamp) for the [`OrderRequest`].
+    ///
+    /// NOTE: Required to be set to build an [`OrderRequest`].
+    pub fn time_in_force(mut self, time_in_force: OrderTimeInForce) -> Self {
+        self.time_in_force = Some(time_in_force);
+        self
+    }
+
+    /// Set the Route for the [`OrderRequest`].
+    pub fn route(mut self, route: impl Into<String>) -> Self {
+        self.route = Some(route.into());
+        self
+    }
+
+    /// Finish building the spread into an [`OrderRequest`].
+    ///
+    /// NOTE: `account_id`, `order_type`, and `time_in_force` are all required.
+    ///
+    /// # Errors
+    ///
+    /// - [`Error::OptionLegsNotSet`] if there's no legs.
+    /// - [`Error::QuantityNotSet`] if the quantity, or a leg's ratio, is zero.
+    /// - [`Error::MismatchedOptionLegs`] if the legs don't make up the spread type.
+    /// - [`Error::QuantityTooLarge`] if the quantity times a leg's ratio doesn't fit in a `u32`.
+    pub fn build(self) -> Result<OrderRequest, Error> {
+        validate_spread(&self.spread_type, &self.legs)?;
+        if self.quantity == 0 {
+            return Err(Error::QuantityNotSet);
+        }
+
+        let sell = matches!(self.trade_action, OptionTradeAction::Sell);
+        let legs = self
+            .legs
+            .iter()
+            .map(|(symbol, ratio)| {
+                let buy = (*ratio > 0) != sell;
+                let trade_action = match (buy, self.position_effect) {
+                    (true, PositionEffect::Open) => TradeAction::BuyToOpen,
+                    (false, PositionEffect::Open) => TradeAction::SellToOpen,
+                    (true, PositionEffect::Close) => TradeAction::BuyToClose,
+                    (false, PositionEffect::Close) => TradeAction::SellToClose,
+                };
+
+                let quantity =
+                    ratio
+                        .unsigned_abs()
+                        .checked_mul(self.quantity)
+                        .ok_or_else(|| {
+                            Error::QuantityTooLarge(format!(
+                                "{} x {} of {symbol}",
+                                self.quantity,
+                                ratio.unsigned_abs()
+                            ))
+                        })?;
+
+                Ok(symbol.order_leg(quantity, trade_action))
+            })
+            .collect::<Result<Vec<_>, Error>>()?;
+
+        let mut builder = OrderRequestBuilder::new().legs(legs);
+        if let Some(account_id) = self.account_id {
+            builder = builder.account_id(account_id);
+        }
+        if let Some(order_type) = self.order_type {
+            builder = builder.order_type(order_type);
+        }
+        if let Some(price) = self.limit_price {
+            builder = builder.limit_price(price);
+        }
+        if let Some(price) = self.stop_price {
+            builder = builder.stop_price(price);
+        }
+        if let Some(time_in_force) = self.time_in_force {
+            builder = builder.time_in_force(time_in_force);
+        }
+        if let Some(route) = self.route {
+            builder = builder.route(route);
+        }
+
+        builder.build()
+    }
+}
+
+/// Validate the legs make up the spread type.
+fn validate_spread(
+    spread_type: &OptionSpreadType,
+    legs: &[(OptionSymbol, i32)],
+) -> Result<(), Error> {
+    let mismatched = |reason: &str| {
+        Err(Error::MismatchedOptionLegs(format!(
+            "{spread_type:?}: {reason}"
+        )))
+    };
+
+    let Some((first, _)) = legs.first() else {
+        return Err(Error::OptionLegsNotSet);
+    };
+    if legs.iter().any(|(_, ratio)| *ratio == 0) {
+        return Err(Error::QuantityNotSet);
+    }
+    if legs.iter().any(|(symbol, _)| symbol.root != first.root) {
+        return mismatched("every leg must have the same underlying");
+    }
+
+    let expected_legs = match spread_type {
+        OptionSpreadType::Single => 1,
+        OptionSpreadType::Vertical
+        | OptionSpreadType::Straddle
+        | OptionSpreadType::Strangle
+        | OptionSpreadType::Calendar
+        | OptionSpreadType::Diagonal => 2,
+        OptionSpreadType::Butterfly => 3,
+        OptionSpreadType::Condor | OptionSpreadType::IronCondor => 4,
+        // Only the underlying is checked for the other spread types
+        _ => return Ok(()),
+    };
+    if legs.len() != expected_legs {
+        return mismatched(&format!(
+            "expected {expected_legs} legs, got {}",
+            legs.len()
+        ));
+    }
+
+    // Sort the legs by expiration then strike, so they can be compared by position
+    let mut sorted: Vec<&(OptionSymbol, i32)> = legs.iter().collect();
+    sorted.sort_by(|(a, _), (b, _)| {
+        a.expiration
+            .cmp(&b.expiration)
+            .then(a.strike.total_cmp(&b.strike))
+    });
+    let ratios: Vec<i32> = sorted.iter().map(|(_, ratio)| *ratio).collect();
+    let same_expiration = sorted.iter().all(|(s, _)| s.expiration == first.expiration);
+    let same_option_type = sorted
+        .iter()
+        .all(|(s, _)| s.option_type == first.option_type);
+    let distinct_strikes = sorted
+        .windows(2)
+        .all(|pair| pair[0].0.strike != pair[1].0.strike);
+    let opposite = |a: i32, b: i32| a == -b;
+
+    match spread_type {
+        OptionSpreadType::Vertical => {
+            if !same_expiration || !same_option_type || !distinct_strikes {
+                return mismatched("legs must be the same option type and expiration at 2 strikes");
+            }
+            if !opposite(ratios[0], ratios[1]) {
+                return mismatched("one leg must be bought and the other sold");
+            }
+        }
+        OptionSpreadType::Straddle | OptionSpreadType::Strangle => {
+            if !same_expiration || same_option_type || ratios[0] != ratios[1] {
+                return mismatched(
+                    "legs must be a call and a put of the same expiration and ratio",
+                );
+            }
+            let same_strike = sorted[0].0.strike == sorted[1].0.strike;
+            if matches!(spread_type, OptionSpreadType::Straddle) != same_strike {
+                return mismatched("straddles must share a strike, and strangles must not");
+            }
+        }
+        OptionSpreadType::Butterfly => {
+            if !same_expiration || !same_option_type || !distinct_strikes {
+                return mismatched("legs must be the same option type and expiration at 3 strikes");
+            }
+            if ratios[1] != -2 * ratios[0] || ratios[2] != ratios[0] {
+                return mismatched("the middle strike must be traded 2:1 against the wings");
+            }
+        }
+        OptionSpreadType::Condor | OptionSpreadType::IronCondor => {
+            if !same_expiration || !distinct_strikes {
+                return mismatched("legs must be the same expiration at 4 strikes");
+            }
+            let is_iron = matches!(spread_type, OptionSpreadType::IronCondor);
+            let puts_then_calls = sorted[..2]
+                .iter()
+                .all(|(s, _)| s.option_type == OptionType::Put)
+                && sorted[2..]
+                    .iter()
+                    .all(|(s, _)| s.option_type == OptionType::Call);
+            if (is_iron && !puts_then_calls) || (!is_iron && !same_option_type) {
+                return mismatched(if is_iron {
+                    "the lower 2 strikes must be puts and the upper 2 calls"
+                } else {
+                    "legs must be the same option type"
+                });
+            }
+            if ratios[1] != -ratios[0] || ratios[2] != ratios[1] || ratios[3] != ratios[0] {
+                return mismatched("the outer strikes must be traded against the inner strikes");
+            }
+        }
+        OptionSpreadType::Calendar | OptionSpreadType::Diagonal => {
+            if same_expiration || !same_option_type {
+                return mismatched("legs must be the same option type at 2 expirations");
+            }
+            let same_strike = sorted[0].0.strike == sorted[1].0.strike;
+            if matches!(spread_type, OptionSpreadType::Calendar) != same_strike {
+                return mismatched("calendars must share a strike, and diagonals must not");
+            }
+            if !opposite(ratios[0], ratios[1]) {
+                return mismatched("one leg must be bought and the other sold");
+            }
+        }
+        _ => {}
+    }
+
+    Ok(())
+}
@@ -0,0 +1,354 @@
+//! Idempotent order submission, tagging each [`OrderRequest`] placed with
+//! [`crate::orders::Order::place`] with a client generated correlation id.
+//!
+//! The correlation id is sent as the order's `OrderConfirmID`, which TradeStation
+//! uses to reject duplicates, unless the order already has one.
+//!
+//! When a submission fails without a definitive answer from TradeStation, like
+//! a timeout, the account's orders are searched for an order with the same
+//! symbols, quantities, sides, order type, and prices opened since the submission started:
+//! - If it's found, the order was placed, and it's ticket is returned.
+//! - If it isn't, [`crate::Error::OrderNotPlaced`] is returned, and retrying the
+//!   same order reuses the correlation id, so TradeStation rejects it if the
+//!   first submission shows up late.
+//! - If the orders can't be searched, [`crate::Error::OrderOutcomeUnknown`] is returned,
+//!   and retrying the same order searches again before sending it.
+//!
+//! Retries search for the earlier submission before the interlocks and risk policy
+//! are checked, so a retry of an order which was placed returns it's tickets, even
+//! when the checks would block sending it again.
+//!
+//! Only submissions which failed this way are remembered by the [`crate::Client`], and
+//! every clone of it, so identical orders placed concurrently are each sent as intended.
+//!
+//! NOTE: Order groups placed with [`crate::orders::Order::place_group`] aren't tagged
+//! or reconciled, so check the account's orders before retrying a group which failed.
+
+use crate::{
+    accounting::{orders::OrderAction, Order},
+    execution::{group::is_buying, request::OrderRequest, ticket::OrderTicket},
+    utils::{now_epoch_seconds, parse_number, parse_timestamp},
+    Client, Error,
+};
+use std::{
+    collections::HashMap,
+    process,
+    sync::{
+        atomic::{AtomicU32, Ordering},
+        Arc, Mutex, MutexGuard,
+    },
+    time::{SystemTime, UNIX_EPOCH},
+};
+use tracing::{info, warn};
+
+/// How many seconds before a submission started an order can be opened,
+/// and still match it, to allow for clock differences with TradeStation.
+const CLOCK_SKEW_SECONDS: i64 = 60;
+
+/// The max length of an `OrderConfirmID`.
+const CORRELATION_ID_LENGTH: usize = 22;
+
+/// The submissions which failed without a definitive outcome, shared by every
+/// clone of a [`Client`], keyed by the [`OrderRequest`] they submitted.
+///
+/// NOTE: Submissions still on their way aren't kept, so they're never
+/// reconciled against identical orders being placed at the same time.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct UnresolvedSubmissions {
+    inner: Arc<Mutex<HashMap<String, PendingSubmission>>>,
+}
+impl UnresolvedSubmissions {
+    /// Lock the submissions, recovering from a poisoned lock.
+    fn lock(&self) -> MutexGuard<'_, HashMap<String, PendingSubmission>> {
+        self.inner
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+#[derive(Clone, Debug)]
+/// A submission which failed, without a definitive outcome.
+struct PendingSubmission {
+    correlation_id: String,
+    submitted_at: i64,
+}
+
+/// An [`OrderRequest`] being submitted, tagged with it's correlation id.
+pub(crate) struct Submission {
+    /// The order being submitted, with the correlation id as it's `OrderConfirmID`.
+    pub(crate) order_request: OrderRequest,
+    pub(crate) correlation_id: String,
+    key: String,
+    submitted_at: i64,
+}
+
+/// The key submissions of an order are remembered by.
+fn submission_key(order_req: &OrderRequest) -> Result<String, Error> {
+    serde_json::to_string(order_req).map_err(|e| {
+        Error::UnknownTradeStationAPIError(format!("couldn't serialize the order request: {e}"))
+    })
+}
+
+/// Reconcile an earlier submission of the order which failed without a
+/// definitive outcome, returning it's tickets if it was placed after all.
+///
+/// NOTE: This is done before the interlocks and risk policy are checked, so
+/// they can't hide the outcome of the earlier submission by blocking the retry.
+pub(crate) async fn reconcile_pending(
+    client: &Client,
+    order_req: &OrderRequest,
+) -> Result<Option<Vec<OrderTicket>>, Error> {
+    let key = submission_key(order_req)?;
+    let Some(pending) = client.submissions.lock().get(&key).cloned() else {
+        return Ok(None);
+    };
+
+    match find_placed(client, order_req, pending.submitted_at).await {
+        Ok(tickets) if !tickets.is_empty() => {
+            client.submissions.lock().remove(&key);
+            log_reconciled(client, order_req, &pending.correlation_id, &tickets);
+
+            Ok(Some(tickets))
+        }
+        Ok(_) => Ok(None),
+        Err(error) => Err(Error::OrderOutcomeUnknown(format!(
+            "an earlier submission `{}` couldn't be reconciled: {error}",
+            pending.correlation_id
+        ))),
+    }
+}
+
+/// Start submitting an order, reusing the correlation id of an earlier submission
+/// of it which failed without a definitive outcome, and wasn't found placed.
+pub(crate) fn begin(client: &Client, order_req: &OrderRequest) -> Result<Submission, Error> {
+    let key = submission_key(order_req)?;
+
+    // Take the failed submission, so identical orders placed
+    // concurrently aren't reconciled against it's retry
+    let pending = client.submissions.lock().remove(&key);
+    let (correlation_id, submitted_at) = match pending {
+        // Reuse the correlation id, so TradeStation rejects this
+        // submission if the earlier one is still on it's way
+        Some(pending) => (pending.correlation_id, pending.submitted_at),
+        None => (
+            order_req
+                .order_confirm_id
+                .clone()
+                .unwrap_or_else(generate_correlation_id),
+            now_epoch_seconds(),
+        ),
+    };
+
+    let mut order_request = order_req.clone();
+    order_request.order_confirm_id = Some(correlation_id.clone());
+
+    Ok(Submission {
+        order_request,
+        correlation_id,
+        key,
+        submitted_at,
+    })
+}
+
+impl Submission {
+    /// Remember the submission failed without a definitive outcome,
+    /// so retrying the same order reconciles it before sending it.
+    fn remember(self, client: &Client) {
+        client.submissions.lock().insert(
+            self.key,
+            PendingSubmission {
+                correlation_id: self.correlation_id,
+                submitted_at: self.submitted_at,
+            },
+        );
+    }
+
+    /// Find out whether the order was placed, after the submission failed without a definitive outcome.
+    pub(crate) async fn reconcile(
+        self,
+        client: &Client,
+        error: Error,
+    ) -> Result<Vec<OrderTicket>, Error> {
+        match find_placed(client, &self.order_request, self.submitted_at).await {
+            Ok(tickets) if !tickets.is_empty() => {
+                log_reconciled(client, &self.order_request, &self.correlation_id, &tickets);
+
+                Ok(tickets)
+            }
+            Ok(_) => {
+                warn!(
+                    target: "tradestation::execution",
+                    environment = %client.environment,
+                    account = %self.order_request.account_id,
+                    symbol = ?self.order_request.symbol,
+                    correlation_id = %self.correlation_id,
+                    error = %error,
+                    outcome = "not_placed",
+                    "order submission failed, and the order wasn't placed"
+                );
+
+                let not_placed = Error::OrderNotPlaced(format!(
+                    "`{}` wasn't found after the submission failed: {error}",
+                    self.correlation_id
+                ));
+                self.remember(client);
+
+                Err(not_placed)
+            }
+            Err(reconcile_error) => {
+                warn!(
+                    target: "tradestation::execution",
+                    environment = %client.environment,
+                    account = %self.order_request.account_id,
+                    symbol = ?self.order_request.symbol,
+                    correlation_id = %self.correlation_id,
+                    error = %reconcile_error,
+                    outcome = "unknown",
+                    "couldn't reconcile failed order submission"
+                );
+
+                let outcome_unknown = Error::OrderOutcomeUnknown(format!(
+                    "`{}` couldn't be reconciled after the submission failed: {error}",
+                    self.correlation_id
+                ));
+                self.remember(client);
+
+                Err(outcome_unknown)
+            }
+        }
+    }
+}
+
+/// Find the orders in the account matching the order's symbols, quantities, sides,
+/// order type, and prices, which were opened since the submission started.
+async fn find_placed(
+    client: &Client,
+    order_req: &OrderRequest,
+    submitted_at: i64,
+) -> Result<Vec<OrderTicket>, Error> {
+    let wanted = match (&order_req.symbol, &order_req.quantity) {
+        (Some(symbol), Some(quantity)) => vec![(
+            symbol.as_str(),
+            parse_number(quantity)?,
+            order_req.trade_action.as_ref().map(is_buying),
+        )],
+        _ => order_req
+            .legs
+            .iter()
+            .flatten()
+            .map(|leg| {
+                Ok((
+                    leg.symbol.as_str(),
+                    parse_number(&leg.quantity)?,
+                    Some(is_buying(&leg.trade_action)),
+                ))
+            })
+            .collect::<Result<Vec<_>, Error>>()?,
+    };
+    let limit_price = order_req
+        .limit_price
+        .as_deref()
+        .map(parse_number)
+        .transpose()?;
+    let stop_price = order_req
+        .stop_price
+        .as_deref()
+        .map(parse_number)
+        .transpose()?;
+
+    let tickets = Order::get_all_by_account(client, &order_req.account_id)
+        .await?
+        .into_iter()
+        .filter(|order| {
+            parse_timestamp(&order.opened_date_time)
+                .is_ok_and(|opened_at| opened_at >= submitted_at - CLOCK_SKEW_SECONDS)
+        })
+        .filter(|order| {
+            order.order_type == order_req.order_type
+                && price_matches(order.limit_price.as_deref(), limit_price)
+                && price_matches(order.stop_price.as_deref(), stop_price)
+        })
+        .filter(|order| {
+            order.legs.len() == wanted.len()
+                && wanted.iter().all(|(symbol, quantity, buying)| {
+                    order.legs.iter().any(|leg| {
+                        leg.symbol.eq_ignore_ascii_case(symbol)
+                            && parse_number(&leg.quantity_ordered).ok() == Some(*quantity)
+                            && buying.is_none_or(|buying| {
+                                buying
+                                    == matches!(
+                                        leg.buy_or_sell,
+                                        OrderAction::Buy | OrderAction::BuyToCover
+                                    )
+                            })
+                    })
+                })
+        })
+        .map(|order| OrderTicket {
+            message: format!(
+                "Reconciled order {} in {}",
+                order.order_id, order.account_id
+            ),
+            order_id: order.order_id,
+            error: None,
+            dry_run: false,
+        })
+        .collect();
+
+    Ok(tickets)
+}
+
+/// Whether an order's price matches the price it was submitted with, if it was submitted with one.
+fn price_matches(price: Option<&str>, wanted: Option<f64>) -> bool {
+    wanted.is_none_or(|wanted| price.and_then(|price| parse_number(price).ok()) == Some(wanted))
+}
+
+/// Log an order found placed, after it's submission failed without a definitive outcome.
+fn log_reconciled(
+    client: &Client,
+    order_req: &OrderRequest,
+    correlation_id: &str,
+    tickets: &[OrderTicket],
+) {
+    info!(
+        target: "tradestation::execution",
+        environment = %client.environment,
+        account = %order_req.account_id,
+        symbol = ?order_req.symbol,
+        correlation_id = %correlation_id,
+        order_count = tickets.len(),
+        outcome = "accepted",
+        "order submission reconciled as placed"
+    );
+}
+
+/// Generate a correlation id, unique per process, which fits in an `OrderConfirmID`.
+fn generate_correlation_id() -> String {
+    static COUNTER: AtomicU32 = AtomicU32::new(0);
+
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_nanos())
+        .unwrap_or_default();
+    let count = COUNTER.fetch_add(1, Ordering::Relaxed);
+
+    let mut id = base36(nanos, 13);
+    id.push_str(&base36(process::id().into(), 4));
+    id.push_str(&base36(count.into(), 5));
+    id.truncate(CORRELATION_ID_LENGTH);
+
+    id
+}
+
+/// Format the lowest `width` base 36 digits of a value, zero padded.
+fn base36(mut value: u128, width: usize) -> String {
+    const DIGITS: &[u8] = b"0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZ";
+
+    let mut digits = vec![b'0'; width];
+    for digit in digits.iter_mut().rev() {
+        *digit = DIGITS[(value % 36) as usize];
+        value /= 36;
+    }
+
+    String::from_utf8(digits).unwrap_or_default()
+}
@@ -1,4 +1,6 @@
 use crate::{
+    accounting::Account,
+    execution::interlock,
     orders::{Order, OrderUpdate},
     responses::{
         execution::{ModifyOrderResp, ModifyOrderRespRaw},
@@ -21,6 +23,13 @@ pub struct OrderTicket {
 
     /// The error for the [`crate::orders::Order`], if there was any errors.
     pub error: Option<String>,
+
+    #[serde(skip)]
+    /// Whether the [`crate::orders::Order`] was only confirmed by a [`Client`]
+    /// in dry-run mode, where the order id is the order confirm id.
+    ///
+    /// NOTE: See [`crate::execution::dry_run`].
+    pub dry_run: bool,
 }
 impl OrderTicket {
     /// Instantiate an [`OrderTicket`] using a provided [`crate::orders::Order`] id.
@@ -46,6 +55,7 @@ impl OrderTicket {
             message: "".into(),
             order_id: order_id.into(),
             error: None,
+            dry_run: false,
         }
     }
 
@@ -108,6 +118,17 @@ impl OrderTicket {
             .ok_or(Error::OrderNotFound(self.order_id.clone()))
     }
 
+    /// Find the [`crate::orders::Order`] this ticket is for, in any of the client's accounts.
+    async fn find_order_in_any_account(&self, client: &Client) -> Result<Order, Error> {
+        let account_ids: Vec<String> = Account::get_all(client)
+            .await?
+            .into_iter()
+            .map(|account| account.account_id)
+            .collect();
+
+        self.find_order(client, account_ids.join(",")).await
+    }
+
     /// Replace an [`crate::orders::Order`] with a new [`crate::orders::Order`].
     ///
     /// # Example
@@ -157,6 +178,15 @@ impl OrderTicket {
         client: &Client,
         order_update: OrderUpdate,
     ) -> Result<OrderTicket, Error> {
+        // Checks needing the order's account, or legs, are done replacing the order itself
+        if client.risk_policy().is_some()
+            || client.interlock.restricts_accounts(&client.environment)
+        {
+            let order = self.find_order_in_any_account(client).await?;
+            return order.replace(client, order_update).await;
+        }
+        interlock::ensure_can_send(client, [])?;
+
         let endpoint = format!("orderexecution/orders/{}", self.order_id);
 
         match client
@@ -108,7 +108,7 @@ impl Client {
     }
 }
 
-#[derive(Clone, Debug, Deserialize, Serialize)]
+#[derive(Clone, Copy, Debug, Deserialize, Serialize, PartialEq, Eq)]
 /// The different types of activation trigger keys.
 pub enum ActivationTriggerKey {
     /// Single Trade Tick, one trade tick must print
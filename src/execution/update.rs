@@ -1,4 +1,10 @@
-use crate::{accounting::orders::OrderType, execution::orders::AdvancedOrderOptions};
+use crate::{
+    accounting::orders::OrderType,
+    execution::orders::AdvancedOrderOptions,
+    market_data::{PriceFormat, RoundingMode},
+    utils::{format_number, parse_number},
+    Error,
+};
 use serde::{Deserialize, Serialize};
 
 #[derive(Clone, Debug, Deserialize, Serialize, Default)]
@@ -60,4 +66,38 @@ impl OrderUpdate {
 
         self
     }
+
+    /// Validate the limit and stop prices are on a valid tick of the symbol's [`PriceFormat`].
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::PriceNotOnTick`] if either price isn't on a valid tick.
+    pub fn validate_prices(&self, price_format: &PriceFormat) -> Result<(), Error> {
+        for price in [&self.limit_price, &self.stop_price].into_iter().flatten() {
+            price_format.validate_price(price)?;
+        }
+
+        Ok(())
+    }
+
+    /// Round the limit and stop prices onto a valid tick of the symbol's [`PriceFormat`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`Error`] if either price can't be parsed, or the price format is invalid.
+    pub fn round_prices(
+        mut self,
+        price_format: &PriceFormat,
+        mode: RoundingMode,
+    ) -> Result<Self, Error> {
+        for price in [&mut self.limit_price, &mut self.stop_price]
+            .into_iter()
+            .flatten()
+        {
+            let rounded = price_format.round_price(parse_number(price)?, mode)?;
+            *price = format_number(rounded);
+        }
+
+        Ok(self)
+    }
 }
@@ -127,18 +127,28 @@ pub use token::{Scope, Token};
 /// Functions, Structs, and primitives related to market data.
 pub mod execution;
 
+/// Functions, structs, and primitives related to sharing streams.
+pub mod stream;
+
+/// Shared parsing and time helpers.
+mod utils;
+
 /// Abstractions, functions, and primitives related to orders.
 pub mod orders {
     pub use crate::{
-        accounting::orders::{
-            ConditionalOrder, LogicOp, OptionType, Order, OrderAction, OrderLeg, OrderRelationship,
-            OrderStage, OrderStatus, OrderType,
+        accounting::{
+            orders::{
+                ConditionalOrder, LogicOp, OptionType, Order, OrderAction, OrderLeg,
+                OrderRelationship, OrderStage, OrderStatus, OrderType,
+            },
+            status::{OrderStateMachine, OrderStatusCategory},
         },
         execution::{
-            confirm::OrderConfirmation,
+            confirm::{ConfirmedOrder, OrderConfirmation},
+            lifecycle::OrderEvent,
             orders::{
-                AdvancedOrderOptions, BPWarningStatus, Duration, OrderRequestLeg, OrderTimeInForce,
-                Oso, PegValue, TradeAction,
+                AdvancedOrderOptions, AdvancedOrderOptionsBuilder, BPWarningStatus, Duration,
+                OrderRequestLeg, OrderTimeInForce, Oso, PegValue, TradeAction,
             },
             request::{
                 OrderRequest, OrderRequestBuilder, OrderRequestGroup, OrderRequestGroupBuilder,
@@ -0,0 +1,639 @@
+//! Prices European options with Black-Scholes (options on equities) or
+//! Black-76 (options on futures), computes all of their greeks, and solves
+//! for implied volatility from an option's bid, ask, or mid price.
+//!
+//! The greeks use the same units TradeStation returns in an
+//! [`OptionChain`](crate::market_data::OptionChain):
+//! - `theta` is the change in value for one day passing.
+//! - `vega` is the change in value for a one percentage point increase in volatility.
+//! - `rho` is the change in value for a one percentage point increase in the risk-free rate.
+//!
+//! # Example
+//! ---
+//!
+//! Price a call option, then solve back for the volatility used to price it.
+//!
+//! ```rust
+//! use tradestation::{
+//!     market_data::analytics::EuropeanOption,
+//!     orders::OptionType,
+//! };
+//!
+//! // A 3 month at the money call on a $100 stock, with a 5% risk free rate
+//! let call = EuropeanOption::black_scholes(OptionType::Call, 100.0, 100.0, 0.25, 0.05);
+//!
+//! let price = call.price(0.20);
+//! assert!((price - 4.615).abs() < 0.001);
+//!
+//! let greeks = call.greeks(0.20);
+//! assert!((greeks.delta - 0.5695).abs() < 0.0001);
+//!
+//! let implied_volatility = call.implied_volatility(price).unwrap();
+//! assert!((implied_volatility - 0.20).abs() < 1e-6);
+//! ```
+
+use crate::{
+    accounting::OptionType,
+    market_data::{options::OptionType as ChainOptionType, OptionChain, OptionQuote},
+    utils::{now_epoch_seconds, parse_number, parse_timestamp, SECONDS_PER_DAY},
+    Error,
+};
+
+/// The amount of days in a year used for time to expiration.
+pub const DAYS_PER_YEAR: f64 = 365.0;
+
+/// The time of day (UTC) options are considered expired, when the
+/// expiration is only given as a date (4:00 PM Eastern Standard Time).
+const EXPIRATION_SECONDS_INTO_DAY: i64 = 21 * 3600;
+
+/// The lowest volatility the implied volatility solver searches.
+const MIN_VOLATILITY: f64 = 1e-6;
+
+/// The highest volatility the implied volatility solver searches.
+const MAX_VOLATILITY: f64 = 10.0;
+
+/// The max amount of iterations for the implied volatility solver.
+const MAX_IV_ITERATIONS: usize = 100;
+
+/// How close the solved price must be to the target price.
+const IV_PRICE_TOLERANCE: f64 = 1e-10;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+/// The model used to price a European option.
+pub enum PricingModel {
+    /// Black-Scholes(-Merton), for options on equities and indexes,
+    /// where the underlying price is the spot price.
+    BlackScholes,
+
+    /// Black-76, for options on futures, where the
+    /// underlying price is the futures price.
+    Black76,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+/// Which price of an option quote to use.
+pub enum QuoteSide {
+    /// The bid price.
+    Bid,
+
+    /// The ask price.
+    Ask,
+
+    /// The mid price between the bid and ask.
+    Mid,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+/// The greeks of an option.
+pub struct Greeks {
+    /// The change in value for a one point increase in the underlying price.
+    pub delta: f64,
+
+    /// The change in delta for a one point increase in the underlying price.
+    pub gamma: f64,
+
+    /// The change in value for one day passing.
+    pub theta: f64,
+
+    /// The change in value for a one percentage point increase in volatility.
+    pub vega: f64,
+
+    /// The change in value for a one percentage point increase in the risk-free rate.
+    pub rho: f64,
+}
+impl Greeks {
+    /// Parse the greeks TradeStation returned for an [`OptionChain`].
+    ///
+    /// NOTE: Returns `None` if the chain was streamed without `enable_greeks`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::InvalidNumber`] if a greek isn't a valid number.
+    pub fn from_chain(chain: &OptionChain) -> Result<Option<Greeks>, Error> {
+        Self::from_reported(
+            &chain.delta,
+            &chain.gamma,
+            &chain.theta,
+            &chain.vega,
+            &chain.rho,
+        )
+    }
+
+    /// Parse the greeks TradeStation returned for an [`OptionQuote`].
+    ///
+    /// NOTE: Returns `None` if the quote was streamed without `enable_greeks`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::InvalidNumber`] if a greek isn't a valid number.
+    pub fn from_quote(quote: &OptionQuote) -> Result<Option<Greeks>, Error> {
+        Self::from_reported(
+            &quote.delta,
+            &quote.gamma,
+            &quote.theta,
+            &quote.vega,
+            &quote.rho,
+        )
+    }
+
+    /// The largest absolute difference between any of the greeks.
+    pub fn max_difference(&self, other: &Greeks) -> f64 {
+        [
+            self.delta - other.delta,
+            self.gamma - other.gamma,
+            self.theta - other.theta,
+            self.vega - other.vega,
+            self.rho - other.rho,
+        ]
+        .into_iter()
+        .map(f64::abs)
+        .fold(0.0, f64::max)
+    }
+
+    /// Parse greeks reported as optional strings.
+    fn from_reported(
+        delta: &Option<String>,
+        gamma: &Option<String>,
+        theta: &Option<String>,
+        vega: &Option<String>,
+        rho: &Option<String>,
+    ) -> Result<Option<Greeks>, Error> {
+        match (delta, gamma, theta, vega, rho) {
+            (Some(delta), Some(gamma), Some(theta), Some(vega), Some(rho)) => Ok(Some(Greeks {
+                delta: parse_number(delta)?,
+                gamma: parse_number(gamma)?,
+                theta: parse_number(theta)?,
+                vega: parse_number(vega)?,
+                rho: parse_number(rho)?,
+            })),
+            _ => Ok(None),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+/// The analytics computed for an [`OptionChain`], alongside
+/// the values TradeStation reported for it.
+pub struct ChainComparison {
+    /// The implied volatility solved from the option's price.
+    pub implied_volatility: f64,
+
+    /// The greeks at the solved implied volatility.
+    pub greeks: Greeks,
+
+    /// The implied volatility TradeStation reported.
+    pub reported_implied_volatility: Option<f64>,
+
+    /// The greeks TradeStation reported.
+    pub reported_greeks: Option<Greeks>,
+}
+impl ChainComparison {
+    /// The absolute difference between the solved and reported implied volatility.
+    pub fn implied_volatility_difference(&self) -> Option<f64> {
+        self.reported_implied_volatility
+            .map(|reported| (self.implied_volatility - reported).abs())
+    }
+
+    /// The largest absolute difference between the computed and reported greeks.
+    pub fn greeks_difference(&self) -> Option<f64> {
+        self.reported_greeks
+            .map(|reported| self.greeks.max_difference(&reported))
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+/// A European option to price, and compute greeks or implied volatility for.
+pub struct EuropeanOption {
+    /// The model used to price the option.
+    pub model: PricingModel,
+
+    /// If the option is a call or a put.
+    pub option_type: OptionType,
+
+    /// The price of the underlying, the spot price for
+    /// [`PricingModel::BlackScholes`] or the futures price for
+    /// [`PricingModel::Black76`].
+    pub underlying_price: f64,
+
+    /// The strike price of the option.
+    pub strike: f64,
+
+    /// The time until the option expires in years.
+    pub time_to_expiration: f64,
+
+    /// The continuously compounded risk-free rate as a decimal (`0.05` for 5%).
+    pub risk_free_rate: f64,
+
+    /// The continuous dividend yield as a decimal (`0.01` for 1%).
+    ///
+    /// NOTE: Only used by [`PricingModel::BlackScholes`].
+    pub dividend_yield: f64,
+}
+impl EuropeanOption {
+    /// Create an option on an equity or index priced with Black-Scholes.
+    pub fn black_scholes(
+        option_type: OptionType,
+        spot_price: f64,
+        strike: f64,
+        time_to_expiration: f64,
+        risk_free_rate: f64,
+    ) -> Self {
+        Self {
+            model: PricingModel::BlackScholes,
+            option_type,
+            underlying_price: spot_price,
+            strike,
+            time_to_expiration,
+            risk_free_rate,
+            dividend_yield: 0.0,
+        }
+    }
+
+    /// Create an option on a futures contract priced with Black-76.
+    pub fn black_76(
+        option_type: OptionType,
+        futures_price: f64,
+        strike: f64,
+        time_to_expiration: f64,
+        risk_free_rate: f64,
+    ) -> Self {
+        Self {
+            model: PricingModel::Black76,
+            option_type,
+            underlying_price: futures_price,
+            strike,
+            time_to_expiration,
+            risk_free_rate,
+            dividend_yield: 0.0,
+        }
+    }
+
+    /// Set the continuous dividend yield as a decimal (`0.01` for 1%).
+    pub fn dividend_yield(mut self, dividend_yield: f64) -> Self {
+        self.dividend_yield = dividend_yield;
+        self
+    }
+
+    /// Create the option described by a single option [`OptionChain`],
+    /// using the strike, type, and expiration of it's first leg.
+    ///
+    /// NOTE: The time to expiration is measured from now.
+    ///
+    /// # Errors
+    ///
+    /// - [`Error::OptionLegsNotSet`] if the chain has no legs.
+    /// - [`Error::InvalidOptionType`] if the leg isn't a call or a put.
+    /// - [`Error::InvalidNumber`] or [`Error::InvalidTimestamp`] if the leg can't be parsed.
+    pub fn from_chain(
+        chain: &OptionChain,
+        model: PricingModel,
+        underlying_price: f64,
+        risk_free_rate: f64,
+    ) -> Result<Self, Error> {
+        let leg = chain.legs.first().ok_or(Error::OptionLegsNotSet)?;
+        let option_type = match leg.option_type {
+            ChainOptionType::Call => OptionType::Call,
+            ChainOptionType::Put => OptionType::Put,
+            ChainOptionType::All => return Err(Error::InvalidOptionType("All".into())),
+        };
+
+        Ok(Self {
+            model,
+            option_type,
+            underlying_price,
+            strike: parse_number(&leg.strike_price)?,
+            time_to_expiration: years_until(&leg.expiration, now_epoch_seconds())?,
+            risk_free_rate,
+            dividend_yield: 0.0,
+        })
+    }
+
+    /// The cost of carry of the underlying for the model.
+    fn cost_of_carry(&self) -> f64 {
+        match self.model {
+            PricingModel::BlackScholes => self.risk_free_rate - self.dividend_yield,
+            PricingModel::Black76 => 0.0,
+        }
+    }
+
+    /// The theoretical price of the option for a given volatility.
+    ///
+    /// NOTE: Volatility is a decimal (`0.20` for 20%).
+    pub fn price(&self, volatility: f64) -> f64 {
+        let time = self.time_to_expiration.max(0.0);
+        let carry = self.cost_of_carry();
+        let discount = (-self.risk_free_rate * time).exp();
+        let underlying_discount = ((carry - self.risk_free_rate) * time).exp();
+
+        if time == 0.0 || volatility <= 0.0 {
+            // No uncertainty left, it's worth it's discounted intrinsic value
+            let forward = self.underlying_price * (carry * time).exp();
+            return discount * self.intrinsic(forward);
+        }
+
+        let (d1, d2) = self.d1_d2(volatility, time, carry);
+        let (spot, strike) = (self.underlying_price, self.strike);
+
+        match self.option_type {
+            OptionType::Call => {
+                spot * underlying_discount * normal_cdf(d1) - strike * discount * normal_cdf(d2)
+            }
+            OptionType::Put => {
+                strike * discount * normal_cdf(-d2) - spot * underlying_discount * normal_cdf(-d1)
+            }
+        }
+    }
+
+    /// The greeks of the option for a given volatility.
+    ///
+    /// NOTE: Volatility is a decimal (`0.20` for 20%).
+    pub fn greeks(&self, volatility: f64) -> Greeks {
+        let time = self.time_to_expiration.max(0.0);
+        let carry = self.cost_of_carry();
+        let rate = self.risk_free_rate;
+        let discount = (-rate * time).exp();
+        let underlying_discount = ((carry - rate) * time).exp();
+        let (spot, strike) = (self.underlying_price, self.strike);
+
+        if time == 0.0 || volatility <= 0.0 {
+            let forward = spot * (carry * time).exp();
+            let in_the_money = self.intrinsic(forward) > 0.0;
+            let delta = match (self.option_type, in_the_money) {
+                (_, false) => 0.0,
+                (OptionType::Call, true) => underlying_discount,
+                (OptionType::Put, true) => -underlying_discount,
+            };
+
+            return Greeks {
+                delta,
+                ..Greeks::default()
+            };
+        }
+
+        let (d1, d2) = self.d1_d2(volatility, time, carry);
+        let sqrt_time = time.sqrt();
+        let density = normal_pdf(d1);
+
+        let gamma = underlying_discount * density / (spot * volatility * sqrt_time);
+        let vega = spot * underlying_discount * density * sqrt_time;
+        let time_decay = -spot * underlying_discount * density * volatility / (2.0 * sqrt_time);
+
+        let (delta, theta, rho) = match self.option_type {
+            OptionType::Call => (
+                underlying_discount * normal_cdf(d1),
+                time_decay
+                    - (carry - rate) * spot * underlying_discount * normal_cdf(d1)
+                    - rate * strike * discount * normal_cdf(d2),
+                time * strike * discount * normal_cdf(d2),
+            ),
+            OptionType::Put => (
+                underlying_discount * (normal_cdf(d1) - 1.0),
+                time_decay
+                    + (carry - rate) * spot * underlying_discount * normal_cdf(-d1)
+                    + rate * strike * discount * normal_cdf(-d2),
+                -time * strike * discount * normal_cdf(-d2),
+            ),
+        };
+
+        // The futures price doesn't depend on the rate, so only discounting is affected
+        let rho = match self.model {
+            PricingModel::BlackScholes => rho,
+            PricingModel::Black76 => -time * self.price(volatility),
+        };
+
+        Greeks {
+            delta,
+            gamma,
+            theta: theta / DAYS_PER_YEAR,
+            vega: vega / 100.0,
+            rho: rho / 100.0,
+        }
+    }
+
+    /// Solve for the volatility which prices the option at `price`.
+    ///
+    /// NOTE: Returns volatility as a decimal (`0.20` for 20%).
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::ImpliedVolatilityNotFound`] if the price is outside of
+    /// the arbitrage bounds of the option, or the solver didn't converge.
+    pub fn implied_volatility(&self, price: f64) -> Result<f64, Error> {
+        let time = self.time_to_expiration.max(0.0);
+        if time == 0.0 {
+            return Err(Error::ImpliedVolatilityNotFound(
+                "the option is expired".into(),
+            ));
+        }
+
+        let lower_bound = self.price(0.0);
+        let upper_bound = self.price(MAX_VOLATILITY);
+        if !price.is_finite() || price < lower_bound - IV_PRICE_TOLERANCE || price > upper_bound {
+            return Err(Error::ImpliedVolatilityNotFound(format!(
+                "price {price} is outside of the bounds {lower_bound} to {upper_bound}"
+            )));
+        }
+
+        // Newton-Raphson, falling back to bisection when a step leaves the bracket
+        let (mut low, mut high) = (MIN_VOLATILITY, MAX_VOLATILITY);
+        let mut volatility = (2.0 * std::f64::consts::PI / time).sqrt()
+            * (price / self.underlying_price.max(f64::MIN_POSITIVE));
+        if !(low..=high).contains(&volatility) {
+            volatility = 0.5;
+        }
+
+        for _ in 0..MAX_IV_ITERATIONS {
+            let difference = self.price(volatility) - price;
+            if difference.abs() < IV_PRICE_TOLERANCE {
+                return Ok(volatility);
+            }
+
+            if difference > 0.0 {
+                high = volatility;
+            } else {
+                low = volatility;
+            }
+
+            let vega = self.greeks(volatility).vega * 100.0;
+            let newton = volatility - difference / vega;
+            volatility = if vega > f64::EPSILON && newton > low && newton < high {
+                newton
+            } else {
+                (low + high) / 2.0
+            };
+
+            if (high - low).abs() < f64::EPSILON {
+                return Ok(volatility);
+            }
+        }
+
+        Err(Error::ImpliedVolatilityNotFound(format!(
+            "didn't converge for price {price}"
+        )))
+    }
+
+    /// Solve for the implied volatility of an [`OptionChain`] using it's bid, ask, or mid.
+    ///
+    /// # Errors
+    ///
+    /// - [`Error::InvalidNumber`] if the price can't be parsed.
+    /// - [`Error::ImpliedVolatilityNotFound`] if no volatility matches the price.
+    pub fn implied_volatility_from_chain(
+        &self,
+        chain: &OptionChain,
+        side: QuoteSide,
+    ) -> Result<f64, Error> {
+        let price = quote_price(&chain.bid, &chain.ask, &chain.mid, side)?;
+        self.implied_volatility(price)
+    }
+
+    /// Solve for the implied volatility of an [`OptionQuote`] using it's bid, ask, or mid.
+    ///
+    /// # Errors
+    ///
+    /// - [`Error::InvalidNumber`] if the price can't be parsed.
+    /// - [`Error::ImpliedVolatilityNotFound`] if no volatility matches the price.
+    pub fn implied_volatility_from_quote(
+        &self,
+        quote: &OptionQuote,
+        side: QuoteSide,
+    ) -> Result<f64, Error> {
+        let price = quote_price(&quote.bid, &quote.ask, &quote.mid, side)?;
+        self.implied_volatility(price)
+    }
+
+    /// Solve for implied volatility and greeks from an [`OptionChain`]'s price,
+    /// alongside the implied volatility and greeks TradeStation reported, to
+    /// validate one against the other.
+    ///
+    /// NOTE: TradeStation only reports implied volatility and greeks
+    /// when the chain was streamed with `enable_greeks`.
+    ///
+    /// # Errors
+    ///
+    /// - [`Error::InvalidNumber`] if a price or reported value can't be parsed.
+    /// - [`Error::ImpliedVolatilityNotFound`] if no volatility matches the price.
+    pub fn compare_with_chain(
+        &self,
+        chain: &OptionChain,
+        side: QuoteSide,
+    ) -> Result<ChainComparison, Error> {
+        let implied_volatility = self.implied_volatility_from_chain(chain, side)?;
+        let reported_implied_volatility = chain
+            .implied_volatility
+            .as_deref()
+            .map(parse_number)
+            .transpose()?;
+
+        Ok(ChainComparison {
+            implied_volatility,
+            greeks: self.greeks(implied_volatility),
+            reported_implied_volatility,
+            reported_greeks: Greeks::from_chain(chain)?,
+        })
+    }
+
+    /// The value of the option if exercised against a given underlying price.
+    fn intrinsic(&self, underlying_price: f64) -> f64 {
+        match self.option_type {
+            OptionType::Call => (underlying_price - self.strike).max(0.0),
+            OptionType::Put => (self.strike - underlying_price).max(0.0),
+        }
+    }
+
+    /// The `d1` and `d2` terms of the generalized Black-Scholes formula.
+    fn d1_d2(&self, volatility: f64, time: f64, carry: f64) -> (f64, f64) {
+        let volatility_time = volatility * time.sqrt();
+        let d1 = ((self.underlying_price / self.strike).ln()
+            + (carry + volatility * volatility / 2.0) * time)
+            / volatility_time;
+
+        (d1, d1 - volatility_time)
+    }
+}
+
+/// The amount of years from `now` (unix epoch seconds) until an option expiration.
+///
+/// NOTE: Expirations given at midnight (like TradeStation's `2024-12-20T00:00:00Z`)
+/// are treated as expiring at the close of that day (4:00 PM Eastern Standard Time).
+///
+/// # Errors
+///
+/// Returns [`Error::InvalidTimestamp`] if the expiration can't be parsed.
+pub fn years_until(expiration: &str, now: i64) -> Result<f64, Error> {
+    let mut expires_at = parse_timestamp(expiration)?;
+    if expires_at.rem_euclid(SECONDS_PER_DAY) == 0 {
+        expires_at += EXPIRATION_SECONDS_INTO_DAY;
+    }
+
+    Ok(((expires_at - now) as f64 / (DAYS_PER_YEAR * SECONDS_PER_DAY as f64)).max(0.0))
+}
+
+/// Pick and parse the price of a quote.
+fn quote_price(bid: &str, ask: &str, mid: &str, side: QuoteSide) -> Result<f64, Error> {
+    match side {
+        QuoteSide::Bid => parse_number(bid),
+        QuoteSide::Ask => parse_number(ask),
+        QuoteSide::Mid => parse_number(mid)
+            .or_else(|_| Ok::<f64, Error>((parse_number(bid)? + parse_number(ask)?) / 2.0)),
+    }
+}
+
+/// The standard normal probability density function.
+pub fn normal_pdf(x: f64) -> f64 {
+    (-x * x / 2.0).exp() / (2.0 * std::f64::consts::PI).sqrt()
+}
+
+/// The standard normal cumulative distribution function.
+///
+/// NOTE: Uses the double precision algorithm from Hart (1968) as
+/// given by West (2005), accurate to about 1e-15.
+pub fn normal_cdf(x: f64) -> f64 {
+    let x_abs = x.abs();
+
+    let tail = if x_abs > 37.0 {
+        0.0
+    } else {
+        let exponential = (-x_abs * x_abs / 2.0).exp();
+
+        if x_abs < 7.071_067_811_865_47 {
+            let numerator = [
+                3.526_249_659_989_11e-2,
+                0.700_383_064_443_688,
+                6.373_962_203_531_65,
+                33.912_866_078_383,
+                112.079_291_497_871,
+                221.213_596_169_931,
+                220.206_867_912_376,
+            ]
+            .into_iter()
+            .fold(0.0, |acc, coefficient| acc * x_abs + coefficient);
+
+            let denominator = [
+                8.838_834_764_831_84e-2,
+                1.755_667_163_182_64,
+                16.064_177_579_207,
+                86.780_732_202_946_1,
+                296.564_248_779_674,
+                637.333_633_378_831,
+                793.826_512_519_948,
+                440.413_735_824_752,
+            ]
+            .into_iter()
+            .fold(0.0, |acc, coefficient| acc * x_abs + coefficient);
+
+            exponential * numerator / denominator
+        } else {
+            let continued_fraction =
+                x_abs + 1.0 / (x_abs + 2.0 / (x_abs + 3.0 / (x_abs + 4.0 / (x_abs + 0.65))));
+
+            exponential / continued_fraction / 2.506_628_274_631
+        }
+    };
+
+    if x > 0.0 {
+        1.0 - tail
+    } else {
+        tail
+    }
+}
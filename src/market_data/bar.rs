@@ -3,6 +3,7 @@ use crate::{
         market_data::{GetBarsResp, GetBarsRespRaw, StreamBarsResp},
         ApiResponse,
     },
+    stream::{SharedStream, SharedStreamConfig},
     Client, Error,
 };
 use futures::{Stream, StreamExt};
@@ -391,6 +392,45 @@ impl Client {
     ) -> Result<(), Error> {
         Bar::stream_into(self, query, callback).await
     }
+
+    /// Streams [`Bar`]'s for a given symbol through a [`SharedStream`],
+    /// so many subscribers can share a single stream connection.
+    ///
+    /// <div class="warning">WARNING: There's a max of 10 concurrent streams allowed.</div>
+    ///
+    /// NOTE: Clone the returned [`SharedStream`] to add subscribers, the
+    /// stream is closed once every subscriber is dropped.
+    ///
+    /// # Example
+    /// ---
+    ///
+    /// ```rust,no_run
+    /// # use tradestation::{Client, Error, market_data::{BarUnit, StreamBarsQueryBuilder}, stream::SharedStreamConfig};
+    /// # async fn example(client: &Client) -> Result<(), Error> {
+    /// let query = StreamBarsQueryBuilder::new()
+    ///     .symbol("CLX30")
+    ///     .unit(BarUnit::Minute)
+    ///     .interval(5)
+    ///     .build()?;
+    ///
+    /// let mut strategy_feed = client.share_bars_stream(&query, SharedStreamConfig::new());
+    /// let mut chart_feed = strategy_feed.clone();
+    /// # let _ = (strategy_feed.recv().await, chart_feed.recv().await);
+    /// # Ok(()) }
+    /// ```
+    pub fn share_bars_stream(
+        &self,
+        query: &StreamBarsQuery,
+        config: SharedStreamConfig,
+    ) -> SharedStream<StreamBarsResp> {
+        let endpoint = format!(
+            "marketdata/stream/barcharts/{}{}",
+            query.symbol,
+            query.as_query_string()
+        );
+
+        SharedStream::open(self, endpoint, config)
+    }
 }
 
 #[derive(Clone, Debug, Deserialize, Serialize)]
@@ -0,0 +1,122 @@
+use crate::{
+    responses::{market_data::GetCryptoSymbolNamesResp, ApiResponse},
+    Client, Error,
+};
+use serde::{Deserialize, Serialize};
+
+/// The currencies crypto pairs are quoted in, longest first so
+/// `USDC` is matched before `USD`.
+const QUOTE_CURRENCIES: [&str; 5] = ["USDC", "USDT", "USD", "BTC", "ETH"];
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+/// A cryptocurrency pair symbol, like `BTCUSD`.
+pub struct CryptoPair {
+    /// The full pair symbol, e.g. `BTCUSD`.
+    pub symbol: String,
+
+    /// The currency being priced, e.g. `BTC` in `BTCUSD`.
+    pub base: String,
+
+    /// The currency the price is in, e.g. `USD` in `BTCUSD`.
+    pub quote: String,
+}
+impl CryptoPair {
+    /// Parse a crypto pair symbol into it's base and quote currencies.
+    ///
+    /// # Example
+    /// ---
+    ///
+    /// ```rust
+    /// use tradestation::market_data::CryptoPair;
+    ///
+    /// let pair = CryptoPair::parse("USDCUSD").unwrap();
+    /// assert_eq!(pair.base, "USDC");
+    /// assert_eq!(pair.quote, "USD");
+    ///
+    /// assert!(CryptoPair::parse("BTC").is_err());
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::InvalidCryptoSymbol`] if the symbol doesn't end
+    /// in a known quote currency after a base currency.
+    pub fn parse(symbol: &str) -> Result<Self, Error> {
+        let symbol = symbol.trim().to_uppercase();
+
+        QUOTE_CURRENCIES
+            .iter()
+            .find_map(|quote| {
+                symbol
+                    .strip_suffix(quote)
+                    .filter(|base| !base.is_empty())
+                    .map(|base| CryptoPair {
+                        base: base.to_string(),
+                        quote: quote.to_string(),
+                        symbol: symbol.clone(),
+                    })
+            })
+            .ok_or(Error::InvalidCryptoSymbol(symbol))
+    }
+
+    /// Fetch the symbol names of every crypto pair TradeStation supports.
+    pub async fn fetch_symbol_names(client: &Client) -> Result<Vec<String>, Error> {
+        let endpoint = "marketdata/symbollists/cryptopairs/symbolnames";
+
+        match client
+            .get(endpoint)
+            .await?
+            .json::<ApiResponse<GetCryptoSymbolNamesResp>>()
+            .await?
+        {
+            ApiResponse::Success(resp) => Ok(resp.symbol_names),
+            ApiResponse::Error(resp) => Err(Error::from_api_error(resp)),
+        }
+    }
+}
+impl std::fmt::Display for CryptoPair {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.symbol)
+    }
+}
+
+impl Client {
+    /// Fetch the symbol names of every crypto pair TradeStation supports,
+    /// e.g. `["BTCUSD", "ETHUSD", "LTCUSD"]`.
+    ///
+    /// # Example
+    /// ---
+    ///
+    /// ```ignore
+    /// let symbols = client.get_crypto_symbol_names().await?;
+    /// println!("Crypto pairs: {symbols:?}");
+    /// ```
+    pub async fn get_crypto_symbol_names(&self) -> Result<Vec<String>, Error> {
+        CryptoPair::fetch_symbol_names(self).await
+    }
+
+    /// Fetch every crypto pair TradeStation supports, parsed into [`CryptoPair`]'s.
+    ///
+    /// NOTE: Symbols which can't be parsed into a [`CryptoPair`] are skipped.
+    ///
+    /// # Example
+    /// ---
+    ///
+    /// Find every pair quoted in US dollars.
+    ///
+    /// ```ignore
+    /// let usd_pairs: Vec<CryptoPair> = client
+    ///     .get_crypto_pairs()
+    ///     .await?
+    ///     .into_iter()
+    ///     .filter(|pair| pair.quote == "USD")
+    ///     .collect();
+    /// ```
+    pub async fn get_crypto_pairs(&self) -> Result<Vec<CryptoPair>, Error> {
+        Ok(self
+            .get_crypto_symbol_names()
+            .await?
+            .iter()
+            .filter_map(|symbol| CryptoPair::parse(symbol).ok())
+            .collect())
+    }
+}
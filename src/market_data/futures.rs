@@ -0,0 +1,680 @@
+//! Futures contract symbology: parse contract symbols like `"CLX24"` or
+//! `"SR3Z24"` into their root, month, and year, enumerate contract chains,
+//! resolve continuous `"@CL"` style symbols, and build roll schedules and back
+//! adjusted continuous bar series.
+//!
+//! # Example
+//! ---
+//!
+//! Parse a contract, then list the next 4 quarterly contracts of it's chain.
+//!
+//! ```rust
+//! use tradestation::market_data::futures::{FuturesChain, FuturesContract};
+//!
+//! let contract: FuturesContract = "SR3Z24".parse().unwrap();
+//! assert_eq!(contract.root, "SR3");
+//! assert_eq!((contract.year, contract.month), (2024, 12));
+//! assert_eq!(contract.month_code(), 'Z');
+//! assert_eq!(contract.continuous_symbol(), "@SR3");
+//!
+//! let chain = FuturesChain::quarterly("SR3");
+//! let contracts: Vec<String> = chain
+//!     .contracts_from(2024, 11, 4)
+//!     .iter()
+//!     .map(|contract| contract.to_string())
+//!     .collect();
+//! assert_eq!(contracts, vec!["SR3Z24", "SR3H25", "SR3M25", "SR3U25"]);
+//! ```
+
+use crate::{
+    market_data::{Bar, GetBarsQuery, Quote, SymbolDetails},
+    utils::{civil_from_days, format_number, parse_number, parse_timestamp, SECONDS_PER_DAY},
+    Client, Error,
+};
+use std::{cmp::Ordering, fmt, str::FromStr};
+use tracing::warn;
+
+/// The futures month codes, January (`F`) through December (`Z`).
+pub const MONTH_CODES: [char; 12] = ['F', 'G', 'H', 'J', 'K', 'M', 'N', 'Q', 'U', 'V', 'X', 'Z'];
+
+/// Get the futures month code of a month (`1` through `12`).
+pub fn month_code(month: u32) -> Option<char> {
+    MONTH_CODES.get(month.checked_sub(1)? as usize).copied()
+}
+
+/// Get the month (`1` through `12`) of a futures month code.
+pub fn month_from_code(code: char) -> Option<u32> {
+    MONTH_CODES
+        .iter()
+        .position(|month_code| *month_code == code.to_ascii_uppercase())
+        .map(|index| index as u32 + 1)
+}
+
+/// Get the root of a continuous futures symbol (`"CL"` for `"@CL"`).
+pub fn continuous_root(symbol: &str) -> Option<&str> {
+    symbol
+        .trim()
+        .strip_prefix('@')
+        .filter(|root| !root.is_empty())
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+/// A single futures contract, like `"CLX24"` for November 2024 Crude Oil.
+pub struct FuturesContract {
+    /// The root symbol (`"CL"`).
+    pub root: String,
+
+    /// The contract year (`2024`).
+    pub year: i32,
+
+    /// The contract month (`1` through `12`).
+    pub month: u32,
+}
+impl FuturesContract {
+    /// Create a `FuturesContract` from it's root, year, and month.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::InvalidFuturesSymbol`] if the root is empty or the month isn't `1` through `12`.
+    pub fn new(root: impl Into<String>, year: i32, month: u32) -> Result<Self, Error> {
+        let root = root.into().trim().to_uppercase();
+        if root.is_empty() || !(1..=12).contains(&month) {
+            return Err(Error::InvalidFuturesSymbol(format!(
+                "{root} {year}-{month:02}"
+            )));
+        }
+
+        Ok(Self { root, year, month })
+    }
+
+    /// Parse a futures contract symbol, the root, month code,
+    /// then 2 digit year (`"CLX24"`, `"SR3Z24"`, `"FFZ29"`).
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::InvalidFuturesSymbol`] if the symbol isn't a futures contract symbol.
+    pub fn parse(symbol: &str) -> Result<Self, Error> {
+        let invalid = || Error::InvalidFuturesSymbol(symbol.to_string());
+        let trimmed = symbol.trim().to_uppercase();
+
+        let split_at = trimmed.len().checked_sub(3).ok_or_else(invalid)?;
+        let (root, contract) = (
+            trimmed.get(..split_at).ok_or_else(invalid)?,
+            trimmed.get(split_at..).ok_or_else(invalid)?,
+        );
+        if root.is_empty() || root.starts_with('@') || root.contains(' ') {
+            return Err(invalid());
+        }
+
+        let mut contract = contract.chars();
+        let month = contract
+            .next()
+            .and_then(month_from_code)
+            .ok_or_else(invalid)?;
+        let year = contract.as_str();
+        if !year.bytes().all(|byte| byte.is_ascii_digit()) {
+            return Err(invalid());
+        }
+
+        Ok(Self {
+            root: root.to_string(),
+            year: 2000 + year.parse::<i32>().map_err(|_| invalid())?,
+            month,
+        })
+    }
+
+    /// Get the month code of the contract (`'X'` for November).
+    pub fn month_code(&self) -> char {
+        month_code(self.month).unwrap_or('?')
+    }
+
+    /// Get the continuous symbol of the contract's root (`"@CL"`).
+    pub fn continuous_symbol(&self) -> String {
+        format!("@{}", self.root)
+    }
+}
+impl fmt::Display for FuturesContract {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}{}{:02}",
+            self.root,
+            self.month_code(),
+            self.year.rem_euclid(100)
+        )
+    }
+}
+impl FromStr for FuturesContract {
+    type Err = Error;
+
+    fn from_str(symbol: &str) -> Result<Self, Self::Err> {
+        Self::parse(symbol)
+    }
+}
+impl Ord for FuturesContract {
+    /// Contracts are ordered by when they expire, then by root.
+    fn cmp(&self, other: &Self) -> Ordering {
+        (self.year, self.month, &self.root).cmp(&(other.year, other.month, &other.root))
+    }
+}
+impl PartialOrd for FuturesContract {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+#[derive(Clone, Debug)]
+/// The listed contract months of a futures root.
+pub struct FuturesChain {
+    root: String,
+    months: Vec<u32>,
+}
+impl FuturesChain {
+    /// Create a `FuturesChain` for a root listing contracts in the given months.
+    ///
+    /// NOTE: Months outside of `1` through `12` are ignored.
+    pub fn new(root: impl Into<String>, months: &[u32]) -> Self {
+        let mut months: Vec<u32> = months
+            .iter()
+            .copied()
+            .filter(|month| (1..=12).contains(month))
+            .collect();
+        months.sort_unstable();
+        months.dedup();
+
+        Self {
+            root: root.into().trim().to_uppercase(),
+            months,
+        }
+    }
+
+    /// Create a `FuturesChain` listing a contract every month (like `"CL"`).
+    pub fn monthly(root: impl Into<String>) -> Self {
+        Self::new(root, &[1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12])
+    }
+
+    /// Create a `FuturesChain` listing March, June, September,
+    /// and December contracts (like `"ES"`, or `"SR3"`).
+    pub fn quarterly(root: impl Into<String>) -> Self {
+        Self::new(root, &[3, 6, 9, 12])
+    }
+
+    /// Get the root symbol of the chain.
+    pub fn root(&self) -> &str {
+        &self.root
+    }
+
+    /// Get the first listed contract in, or after, a year and month.
+    pub fn front_contract(&self, year: i32, month: u32) -> Option<FuturesContract> {
+        self.contracts_from(year, month, 1).pop()
+    }
+
+    /// Get the next `count` listed contracts starting in a year and month.
+    pub fn contracts_from(&self, year: i32, month: u32, count: usize) -> Vec<FuturesContract> {
+        let mut contracts = Vec::with_capacity(count);
+        if self.months.is_empty() {
+            return contracts;
+        }
+
+        let mut year = year;
+        let mut month = month.clamp(1, 12);
+        while contracts.len() < count {
+            match self.months.iter().find(|listed| **listed >= month) {
+                Some(listed) => {
+                    contracts.push(FuturesContract {
+                        root: self.root.clone(),
+                        year,
+                        month: *listed,
+                    });
+                    month = listed + 1;
+                }
+                None => {
+                    year += 1;
+                    month = 1;
+                }
+            }
+        }
+
+        contracts
+    }
+
+    /// Get every listed contract from one contract through another.
+    pub fn contracts_between(
+        &self,
+        first: &FuturesContract,
+        last: &FuturesContract,
+    ) -> Vec<FuturesContract> {
+        let mut contracts = Vec::new();
+        let (mut year, mut month) = (first.year, first.month);
+
+        while let Some(contract) = self.front_contract(year, month) {
+            if (contract.year, contract.month) > (last.year, last.month) {
+                break;
+            }
+
+            (year, month) = if contract.month == 12 {
+                (contract.year + 1, 1)
+            } else {
+                (contract.year, contract.month + 1)
+            };
+            contracts.push(contract);
+        }
+
+        contracts
+    }
+
+    /// Get the next listed contract after a contract.
+    pub fn next_contract(&self, contract: &FuturesContract) -> Option<FuturesContract> {
+        if contract.month == 12 {
+            self.front_contract(contract.year + 1, 1)
+        } else {
+            self.front_contract(contract.year, contract.month + 1)
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+/// The dates of a futures contract that roll schedules are built from.
+pub struct ContractDates {
+    /// The contract.
+    pub contract: FuturesContract,
+
+    /// The expiration date of the contract, from [`SymbolDetails::expiration_date`].
+    pub expiration_date: Option<String>,
+
+    /// The first notice date of the contract, from [`Quote::first_notice_date`].
+    ///
+    /// NOTE: Cash settled contracts have no first notice date.
+    pub first_notice_date: Option<String>,
+
+    /// The last trading date of the contract, from [`Quote::last_trading_date`].
+    pub last_trading_date: Option<String>,
+}
+impl ContractDates {
+    /// Get the dates of a contract from it's [`SymbolDetails`].
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::InvalidFuturesSymbol`] if the symbol isn't a futures contract.
+    pub fn from_details(details: &SymbolDetails) -> Result<Self, Error> {
+        Ok(Self {
+            contract: FuturesContract::parse(&details.symbol)?,
+            expiration_date: details.expiration_date.clone(),
+            first_notice_date: None,
+            last_trading_date: None,
+        })
+    }
+
+    /// Add the first notice and last trading dates from a [`Quote`] of the contract.
+    pub fn with_quote(mut self, quote: &Quote) -> Self {
+        if quote.first_notice_date.is_some() {
+            self.first_notice_date = quote.first_notice_date.clone();
+        }
+        if quote.last_trading_date.is_some() {
+            self.last_trading_date = quote.last_trading_date.clone();
+        }
+
+        self
+    }
+
+    /// Get the date (unix epoch seconds) of the contract for a roll rule,
+    /// falling back to the later dates when the rule's date is missing.
+    fn roll_reference(&self, rule: RollRule) -> Option<i64> {
+        let dates = match rule {
+            RollRule::FirstNoticeDate => [
+                &self.first_notice_date,
+                &self.last_trading_date,
+                &self.expiration_date,
+            ],
+            RollRule::LastTradingDate => [
+                &self.last_trading_date,
+                &self.expiration_date,
+                &self.first_notice_date,
+            ],
+            RollRule::ExpirationDate => [
+                &self.expiration_date,
+                &self.last_trading_date,
+                &self.first_notice_date,
+            ],
+        };
+
+        dates
+            .into_iter()
+            .flatten()
+            .find_map(|date| parse_timestamp(date).ok())
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+/// Which date of a contract a roll is scheduled from.
+///
+/// NOTE: When a contract is missing the date, the next available of
+/// first notice date, last trading date, and expiration date is used.
+pub enum RollRule {
+    /// Roll before the first notice date, for physically delivered contracts.
+    #[default]
+    FirstNoticeDate,
+
+    /// Roll before the last trading date.
+    LastTradingDate,
+
+    /// Roll before the expiration date.
+    ExpirationDate,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+/// A roll from one contract to the next.
+pub struct Roll {
+    /// The contract being rolled out of.
+    pub from: FuturesContract,
+
+    /// The contract being rolled into.
+    pub to: FuturesContract,
+
+    /// The date (`"2024-10-18"`) of the roll, the new contract is active from this date.
+    pub date: String,
+}
+
+#[derive(Clone, Debug)]
+/// When to roll from each contract to the next.
+pub struct RollSchedule {
+    contracts: Vec<FuturesContract>,
+    roll_dates: Vec<i64>,
+}
+impl RollSchedule {
+    /// Build a `RollSchedule` from the dates of each contract, rolling `days_before`
+    /// calendar days before the date chosen by the [`RollRule`].
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::RollDateNotFound`] if any contract, besides the last, has no dates.
+    pub fn new(
+        mut contracts: Vec<ContractDates>,
+        rule: RollRule,
+        days_before: u32,
+    ) -> Result<Self, Error> {
+        contracts.sort_by(|a, b| a.contract.cmp(&b.contract));
+        contracts.dedup_by(|a, b| a.contract == b.contract);
+
+        let mut roll_dates = Vec::with_capacity(contracts.len().saturating_sub(1));
+        for dates in contracts.iter().take(contracts.len().saturating_sub(1)) {
+            let reference = dates
+                .roll_reference(rule)
+                .ok_or_else(|| Error::RollDateNotFound(dates.contract.to_string()))?;
+            let day = reference.div_euclid(SECONDS_PER_DAY) - days_before as i64;
+
+            roll_dates.push(day * SECONDS_PER_DAY);
+        }
+
+        Ok(Self {
+            contracts: contracts.into_iter().map(|dates| dates.contract).collect(),
+            roll_dates,
+        })
+    }
+
+    /// Get the contracts of the schedule, in order.
+    pub fn contracts(&self) -> &[FuturesContract] {
+        &self.contracts
+    }
+
+    /// Get each roll of the schedule, in order.
+    pub fn rolls(&self) -> Vec<Roll> {
+        self.contracts
+            .windows(2)
+            .zip(&self.roll_dates)
+            .map(|(contracts, roll_date)| Roll {
+                from: contracts[0].clone(),
+                to: contracts[1].clone(),
+                date: format_date(*roll_date),
+            })
+            .collect()
+    }
+
+    /// Get the contract that's active on a date (`"2024-10-18"`, or an RFC3339 timestamp).
+    pub fn active_contract(&self, date: &str) -> Option<&FuturesContract> {
+        let timestamp = parse_timestamp(date).ok()?;
+
+        self.contracts.get(self.active_index(timestamp))
+    }
+
+    /// The index of the contract active at a timestamp (unix epoch seconds).
+    fn active_index(&self, timestamp: i64) -> usize {
+        self.roll_dates
+            .iter()
+            .take_while(|roll_date| **roll_date <= timestamp)
+            .count()
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+/// How earlier contracts are adjusted to remove the gap at each roll
+/// when stitching a continuous bar series.
+pub enum BackAdjustment {
+    /// Add the price difference between the contracts at each roll.
+    #[default]
+    Difference,
+
+    /// Multiply by the price ratio between the contracts at each roll.
+    Ratio,
+
+    /// Don't adjust, leaving the gaps in the series.
+    None,
+}
+
+/// Stitch the bars of each contract into a single back adjusted continuous
+/// series, using each contract's bars while it's active in the schedule.
+///
+/// NOTE: The gap at each roll is measured between the closes of the old and
+/// new contract at the old contract's last bar before the roll, when the new
+/// contract has no bar at (or before) that time the roll isn't adjusted.
+pub fn back_adjust(
+    contract_bars: &[(FuturesContract, Vec<Bar>)],
+    schedule: &RollSchedule,
+    adjustment: BackAdjustment,
+) -> Result<Vec<Bar>, Error> {
+    let bars_of = |contract: &FuturesContract| {
+        contract_bars
+            .iter()
+            .find(|(bars_contract, _)| bars_contract == contract)
+            .map(|(_, bars)| bars.as_slice())
+            .unwrap_or_default()
+    };
+
+    // The active bars of each contract, and the gap to the next contract at it's roll
+    let mut segments: Vec<ContractSegment> = Vec::new();
+    for (index, contract) in schedule.contracts.iter().enumerate() {
+        let active_bars: Vec<&Bar> = bars_of(contract)
+            .iter()
+            .filter(|bar| schedule.active_index(bar.epoch.div_euclid(1000)) == index)
+            .collect();
+
+        let gap = match (active_bars.last(), schedule.contracts.get(index + 1)) {
+            (Some(last_bar), Some(next_contract)) => {
+                let next_bar = bars_of(next_contract)
+                    .iter()
+                    .filter(|bar| bar.epoch <= last_bar.epoch)
+                    .max_by_key(|bar| bar.epoch);
+
+                match next_bar {
+                    Some(next_bar) => Some((
+                        parse_number(&last_bar.close)?,
+                        parse_number(&next_bar.close)?,
+                    )),
+                    None => {
+                        warn!(
+                            target: "tradestation::client",
+                            from = %contract,
+                            to = %next_contract,
+                            "no overlapping bar to back adjust the roll with"
+                        );
+                        None
+                    }
+                }
+            }
+            _ => None,
+        };
+
+        segments.push(ContractSegment { active_bars, gap });
+    }
+
+    // Walk backwards from the latest contract, accumulating each roll's adjustment
+    let (mut offset, mut factor) = (0.0, 1.0);
+    let mut continuous_bars = Vec::new();
+    for segment in segments.into_iter().rev() {
+        if let Some((old_close, new_close)) = segment.gap {
+            match adjustment {
+                BackAdjustment::Difference => offset += new_close - old_close,
+                BackAdjustment::Ratio if old_close != 0.0 => factor *= new_close / old_close,
+                BackAdjustment::Ratio | BackAdjustment::None => {}
+            }
+        }
+
+        for bar in segment.active_bars.into_iter().rev() {
+            let adjust = |price: &str| -> Result<String, Error> {
+                Ok(format_number(parse_number(price)? * factor + offset))
+            };
+
+            let mut adjusted_bar = bar.clone();
+            adjusted_bar.open = adjust(&bar.open)?;
+            adjusted_bar.high = adjust(&bar.high)?;
+            adjusted_bar.low = adjust(&bar.low)?;
+            adjusted_bar.close = adjust(&bar.close)?;
+            continuous_bars.push(adjusted_bar);
+        }
+    }
+    continuous_bars.reverse();
+
+    Ok(continuous_bars)
+}
+
+impl Client {
+    /// Resolve a continuous futures symbol (`"@CL"`) to the contract it currently points to.
+    ///
+    /// # Example
+    /// ---
+    ///
+    /// Find the current front month crude oil contract.
+    ///
+    /// ```ignore
+    /// let front_month = client.resolve_continuous_contract("@CL").await?;
+    /// println!("@CL is currently {front_month}");
+    /// ```
+    pub async fn resolve_continuous_contract(
+        &self,
+        symbol: &str,
+    ) -> Result<FuturesContract, Error> {
+        if continuous_root(symbol).is_none() {
+            return Err(Error::InvalidFuturesSymbol(symbol.to_string()));
+        }
+
+        let details = self.get_symbol_details(vec![symbol]).await?;
+        let underlying = details
+            .first()
+            .and_then(|details| details.underlying.as_deref())
+            .ok_or_else(|| Error::InvalidFuturesSymbol(symbol.to_string()))?;
+
+        FuturesContract::parse(underlying)
+    }
+
+    /// Build a [`RollSchedule`] for contracts, using the expiration dates from
+    /// their [`SymbolDetails`] and the first notice and last trading dates from
+    /// their [`Quote`]s.
+    ///
+    /// # Example
+    /// ---
+    ///
+    /// Roll crude oil 5 days before first notice, for the 2024 contracts.
+    ///
+    /// ```ignore
+    /// let chain = FuturesChain::monthly("CL");
+    /// let contracts = chain.contracts_from(2024, 1, 12);
+    ///
+    /// let schedule = client
+    ///     .get_roll_schedule(&contracts, RollRule::FirstNoticeDate, 5)
+    ///     .await?;
+    ///
+    /// for roll in schedule.rolls() {
+    ///     println!("Roll {} into {} on {}", roll.from, roll.to, roll.date);
+    /// }
+    /// ```
+    pub async fn get_roll_schedule(
+        &self,
+        contracts: &[FuturesContract],
+        rule: RollRule,
+        days_before: u32,
+    ) -> Result<RollSchedule, Error> {
+        let symbols: Vec<String> = contracts.iter().map(|c| c.to_string()).collect();
+        let symbols: Vec<&str> = symbols.iter().map(String::as_str).collect();
+
+        let details = self.get_cached_symbol_details(&symbols).await?;
+        let quotes = self.get_quotes(symbols).await?;
+
+        let dates = details
+            .iter()
+            .map(|details| {
+                let dates = ContractDates::from_details(details)?;
+                Ok(
+                    match quotes.iter().find(|quote| quote.symbol == details.symbol) {
+                        Some(quote) => dates.with_quote(quote),
+                        None => dates,
+                    },
+                )
+            })
+            .collect::<Result<Vec<_>, Error>>()?;
+
+        RollSchedule::new(dates, rule, days_before)
+    }
+
+    /// Fetch the bars of every contract in a [`RollSchedule`] and stitch
+    /// them into a single back adjusted continuous series.
+    ///
+    /// NOTE: The query is used for every contract, with it's symbol replaced.
+    ///
+    /// # Example
+    /// ---
+    ///
+    /// Fetch a difference adjusted daily crude oil series for 2024.
+    ///
+    /// ```ignore
+    /// let query = GetBarsQueryBuilder::new()
+    ///     .symbol("@CL")
+    ///     .unit(BarUnit::Daily)
+    ///     .first_date("2024-01-01")
+    ///     .last_date("2024-12-31")
+    ///     .build()?;
+    ///
+    /// let bars = client
+    ///     .get_continuous_bars(&schedule, &query, BackAdjustment::Difference)
+    ///     .await?;
+    /// ```
+    pub async fn get_continuous_bars(
+        &self,
+        schedule: &RollSchedule,
+        query: &GetBarsQuery,
+        adjustment: BackAdjustment,
+    ) -> Result<Vec<Bar>, Error> {
+        let mut contract_bars = Vec::with_capacity(schedule.contracts.len());
+        for contract in &schedule.contracts {
+            let mut contract_query = query.clone();
+            contract_query.symbol = contract.to_string();
+
+            contract_bars.push((contract.clone(), self.get_bars(&contract_query).await?));
+        }
+
+        back_adjust(&contract_bars, schedule, adjustment)
+    }
+}
+
+/// The bars of a contract while it's active, and the closes of it and
+/// the next contract (old, new) at it's roll.
+struct ContractSegment<'a> {
+    active_bars: Vec<&'a Bar>,
+    gap: Option<(f64, f64)>,
+}
+
+/// Format a unix epoch timestamp in seconds as a date (`"2024-10-18"`).
+fn format_date(timestamp: i64) -> String {
+    let (year, month, day) = civil_from_days(timestamp.div_euclid(SECONDS_PER_DAY));
+
+    format!("{year:04}-{month:02}-{day:02}")
+}
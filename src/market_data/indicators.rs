@@ -0,0 +1,846 @@
+//! Every indicator updates in O(1) per [`Bar`] and is driven by a [`BarSeries`],
+//! which takes care of a [`Bar`] being streamed multiple times while it's still
+//! open ([`BarStatus::Open`]) before it's closed. Open bars only produce a
+//! provisional value, while closed bars are committed into the indicator.
+//!
+//! [`Bar`]: crate::market_data::Bar
+//! [`BarSeries`]: crate::market_data::indicators::BarSeries
+//! [`BarStatus::Open`]: crate::market_data::bar::BarStatus::Open
+//!
+//! # Example
+//! ---
+//!
+//! Keep a 3 period simple moving average of the close price, while
+//! the last bar is still trading.
+//!
+//! ```rust
+//! use tradestation::market_data::indicators::{BarSeries, BarValues, Sma};
+//!
+//! let mut sma = BarSeries::new(Sma::new(3));
+//! for (epoch, close) in [(1, 10.0), (2, 11.0), (3, 12.0)] {
+//!     sma.update_values(BarValues::from_close(epoch, close, true));
+//! }
+//! assert_eq!(sma.value(), Some(&11.0));
+//!
+//! // The next bar is still open, so it's value can change
+//! sma.update_values(BarValues::from_close(4, 14.0, false));
+//! assert_eq!(sma.value(), Some(&(37.0 / 3.0)));
+//! sma.update_values(BarValues::from_close(4, 13.0, false));
+//! assert_eq!(sma.value(), Some(&12.0));
+//!
+//! // Once the bar closes it's committed into the average
+//! sma.update_values(BarValues::from_close(4, 13.0, true));
+//! assert_eq!(sma.value(), Some(&12.0));
+//! ```
+
+use crate::{
+    market_data::{bar::BarStatus, Bar},
+    responses::market_data::StreamBarsResp,
+    utils::parse_number,
+    Error,
+};
+use std::collections::VecDeque;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+/// The numeric values of a [`Bar`] used by indicators.
+pub struct BarValues {
+    /// The unix epoch time of the bar, used to tell bars apart.
+    pub epoch: i64,
+
+    /// The open price of the bar.
+    pub open: f64,
+
+    /// The high price of the bar.
+    pub high: f64,
+
+    /// The low price of the bar.
+    pub low: f64,
+
+    /// The close (or latest) price of the bar.
+    pub close: f64,
+
+    /// The total volume of the bar.
+    pub volume: f64,
+
+    /// If the bar is finished trading.
+    pub is_closed: bool,
+}
+impl BarValues {
+    /// Create `BarValues` where the open, high, low, and close are all the same price.
+    ///
+    /// NOTE: Useful for indicators on a single price series, volume is set to 0.
+    pub fn from_close(epoch: i64, close: f64, is_closed: bool) -> Self {
+        Self {
+            epoch,
+            open: close,
+            high: close,
+            low: close,
+            close,
+            volume: 0.0,
+            is_closed,
+        }
+    }
+
+    /// Get a specific price of the bar.
+    pub fn price(&self, source: PriceSource) -> f64 {
+        match source {
+            PriceSource::Open => self.open,
+            PriceSource::High => self.high,
+            PriceSource::Low => self.low,
+            PriceSource::Close => self.close,
+            PriceSource::HL2 => (self.high + self.low) / 2.0,
+            PriceSource::HLC3 => (self.high + self.low + self.close) / 3.0,
+            PriceSource::OHLC4 => (self.open + self.high + self.low + self.close) / 4.0,
+        }
+    }
+}
+impl TryFrom<&Bar> for BarValues {
+    type Error = Error;
+
+    fn try_from(bar: &Bar) -> Result<Self, Self::Error> {
+        Ok(Self {
+            epoch: bar.epoch,
+            open: parse_number(&bar.open)?,
+            high: parse_number(&bar.high)?,
+            low: parse_number(&bar.low)?,
+            close: parse_number(&bar.close)?,
+            volume: parse_number(&bar.total_volume)?,
+            is_closed: matches!(bar.bar_status, BarStatus::Closed),
+        })
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+/// The price of a bar an indicator is computed on.
+pub enum PriceSource {
+    /// The open price.
+    Open,
+
+    /// The high price.
+    High,
+
+    /// The low price.
+    Low,
+
+    /// The close price.
+    #[default]
+    Close,
+
+    /// The average of the high and low prices.
+    HL2,
+
+    /// The average of the high, low, and close prices (typical price).
+    HLC3,
+
+    /// The average of the open, high, low, and close prices.
+    OHLC4,
+}
+
+/// An indicator which can be updated incrementally bar by bar.
+pub trait Indicator {
+    /// The value produced by the indicator.
+    type Output;
+
+    /// Get the value of the indicator if `bar` was the next bar,
+    /// without committing `bar` into the indicator.
+    ///
+    /// NOTE: Returns `None` until enough bars were seen.
+    fn peek(&self, bar: &BarValues) -> Option<Self::Output>;
+
+    /// Commit a finished `bar` into the indicator, returning the new value.
+    ///
+    /// NOTE: Returns `None` until enough bars were seen.
+    fn commit(&mut self, bar: &BarValues) -> Option<Self::Output>;
+
+    /// Clear everything the indicator has seen.
+    fn reset(&mut self);
+}
+
+#[derive(Clone, Debug)]
+/// Drives an [`Indicator`] with [`Bar`]'s, handling an open bar
+/// being updated in place versus a newly closed bar.
+///
+/// - An open bar only produces a provisional value which gets
+///   replaced by the next update of the same bar.
+/// - A closed bar is committed into the indicator.
+/// - An open bar that's followed by a different bar without ever being
+///   streamed as closed, gets committed using it's last update.
+/// - A closed bar that's sent again, or any bar older than the last
+///   committed or open bar (out of order), is ignored.
+pub struct BarSeries<I: Indicator> {
+    indicator: I,
+    pending: Option<BarValues>,
+    last_closed_epoch: Option<i64>,
+    value: Option<I::Output>,
+}
+impl<I: Indicator> BarSeries<I>
+where
+    I::Output: Clone,
+{
+    /// Create a new `BarSeries` driving the given indicator.
+    pub fn new(indicator: I) -> Self {
+        Self {
+            indicator,
+            pending: None,
+            last_closed_epoch: None,
+            value: None,
+        }
+    }
+
+    /// Update the indicator with a [`Bar`], returning the latest value.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::InvalidNumber`] if a price or volume of the bar isn't a valid number.
+    pub fn update(&mut self, bar: &Bar) -> Result<Option<I::Output>, Error> {
+        Ok(self.update_values(BarValues::try_from(bar)?))
+    }
+
+    /// Update the indicator with a streamed in bar event, returning the latest value.
+    ///
+    /// NOTE: Any event other than [`StreamBarsResp::Bar`] leaves the indicator untouched.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::InvalidNumber`] if a price or volume of the bar isn't a valid number.
+    pub fn update_from_stream(
+        &mut self,
+        stream_event: &StreamBarsResp,
+    ) -> Result<Option<I::Output>, Error> {
+        match stream_event {
+            StreamBarsResp::Bar(bar) => self.update(bar),
+            _ => Ok(self.value.clone()),
+        }
+    }
+
+    /// Update the indicator with many [`Bar`]'s, like the ones from
+    /// [`crate::Client::get_bars`], returning the latest value.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::InvalidNumber`] if a price or volume of a bar isn't a valid number.
+    pub fn extend(&mut self, bars: &[Bar]) -> Result<Option<I::Output>, Error> {
+        for bar in bars {
+            self.update(bar)?;
+        }
+
+        Ok(self.value.clone())
+    }
+
+    /// Update the indicator with the values of a bar, returning the latest value.
+    ///
+    /// NOTE: A bar older than the last committed or open bar is ignored.
+    pub fn update_values(&mut self, bar: BarValues) -> Option<I::Output> {
+        let is_stale = self
+            .last_closed_epoch
+            .is_some_and(|epoch| bar.epoch <= epoch)
+            || self
+                .pending
+                .is_some_and(|pending| bar.epoch < pending.epoch);
+        if is_stale {
+            return self.value.clone();
+        }
+
+        if let Some(pending) = self.pending.take() {
+            if pending.epoch != bar.epoch {
+                self.value = self.indicator.commit(&pending);
+                self.last_closed_epoch = Some(pending.epoch);
+            }
+        }
+
+        if bar.is_closed {
+            self.value = self.indicator.commit(&bar);
+            self.last_closed_epoch = Some(bar.epoch);
+        } else {
+            self.value = self.indicator.peek(&bar);
+            self.pending = Some(bar);
+        }
+
+        self.value.clone()
+    }
+
+    /// Get the latest value of the indicator, including any open bar.
+    pub fn value(&self) -> Option<&I::Output> {
+        self.value.as_ref()
+    }
+
+    /// Get the indicator being driven.
+    pub fn indicator(&self) -> &I {
+        &self.indicator
+    }
+
+    /// Clear everything seen, for example at the start of a new session.
+    pub fn reset(&mut self) {
+        self.indicator.reset();
+        self.pending = None;
+        self.last_closed_epoch = None;
+        self.value = None;
+    }
+}
+
+#[derive(Clone, Debug)]
+/// Simple Moving Average.
+pub struct Sma {
+    period: usize,
+    source: PriceSource,
+    window: VecDeque<f64>,
+    sum: f64,
+}
+impl Sma {
+    /// Create a new `Sma` over `period` bars of the close price.
+    ///
+    /// NOTE: A period of 0 is treated as a period of 1.
+    pub fn new(period: usize) -> Self {
+        let period = period.max(1);
+
+        Self {
+            period,
+            source: PriceSource::Close,
+            window: VecDeque::with_capacity(period),
+            sum: 0.0,
+        }
+    }
+
+    /// Set the price the average is computed on.
+    pub fn source(mut self, source: PriceSource) -> Self {
+        self.source = source;
+        self
+    }
+
+    /// The sum and the amount of values in the window if `price` was added.
+    fn next_sum(&self, price: f64) -> (f64, usize) {
+        if self.window.len() == self.period {
+            let oldest = self.window.front().copied().unwrap_or_default();
+            (self.sum - oldest + price, self.period)
+        } else {
+            (self.sum + price, self.window.len() + 1)
+        }
+    }
+}
+impl Indicator for Sma {
+    type Output = f64;
+
+    fn peek(&self, bar: &BarValues) -> Option<f64> {
+        let (sum, len) = self.next_sum(bar.price(self.source));
+        (len == self.period).then(|| sum / self.period as f64)
+    }
+
+    fn commit(&mut self, bar: &BarValues) -> Option<f64> {
+        let price = bar.price(self.source);
+        let (sum, len) = self.next_sum(price);
+
+        if self.window.len() == self.period {
+            self.window.pop_front();
+        }
+        self.window.push_back(price);
+        self.sum = sum;
+
+        (len == self.period).then(|| sum / self.period as f64)
+    }
+
+    fn reset(&mut self) {
+        self.window.clear();
+        self.sum = 0.0;
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default)]
+/// The running state of an exponential moving average,
+/// seeded with the simple average of the first `period` values.
+struct EmaState {
+    count: usize,
+    seed_sum: f64,
+    value: Option<f64>,
+}
+impl EmaState {
+    /// The state after adding `value`.
+    fn next(self, value: f64, period: usize) -> Self {
+        match self.value {
+            Some(ema) => {
+                let alpha = 2.0 / (period as f64 + 1.0);
+                Self {
+                    value: Some(alpha * value + (1.0 - alpha) * ema),
+                    ..self
+                }
+            }
+            None => {
+                let count = self.count + 1;
+                let seed_sum = self.seed_sum + value;
+                Self {
+                    count,
+                    seed_sum,
+                    value: (count == period).then(|| seed_sum / period as f64),
+                }
+            }
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+/// Exponential Moving Average.
+///
+/// NOTE: Seeded with the simple average of the first `period` bars.
+pub struct Ema {
+    period: usize,
+    source: PriceSource,
+    state: EmaState,
+}
+impl Ema {
+    /// Create a new `Ema` over `period` bars of the close price.
+    ///
+    /// NOTE: A period of 0 is treated as a period of 1.
+    pub fn new(period: usize) -> Self {
+        Self {
+            period: period.max(1),
+            source: PriceSource::Close,
+            state: EmaState::default(),
+        }
+    }
+
+    /// Set the price the average is computed on.
+    pub fn source(mut self, source: PriceSource) -> Self {
+        self.source = source;
+        self
+    }
+}
+impl Indicator for Ema {
+    type Output = f64;
+
+    fn peek(&self, bar: &BarValues) -> Option<f64> {
+        self.state.next(bar.price(self.source), self.period).value
+    }
+
+    fn commit(&mut self, bar: &BarValues) -> Option<f64> {
+        self.state = self.state.next(bar.price(self.source), self.period);
+        self.state.value
+    }
+
+    fn reset(&mut self) {
+        self.state = EmaState::default();
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default)]
+/// The running state of a Wilder smoothed average, seeded with
+/// the simple average of the first `period` values.
+struct WilderState {
+    count: usize,
+    seed_sum: f64,
+    value: Option<f64>,
+}
+impl WilderState {
+    /// The state after adding `value`.
+    fn next(self, value: f64, period: usize) -> Self {
+        match self.value {
+            Some(average) => Self {
+                value: Some((average * (period as f64 - 1.0) + value) / period as f64),
+                ..self
+            },
+            None => {
+                let count = self.count + 1;
+                let seed_sum = self.seed_sum + value;
+                Self {
+                    count,
+                    seed_sum,
+                    value: (count == period).then(|| seed_sum / period as f64),
+                }
+            }
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default)]
+/// The running state of a [`Rsi`].
+struct RsiState {
+    previous: Option<f64>,
+    gains: WilderState,
+    losses: WilderState,
+}
+
+#[derive(Clone, Debug)]
+/// Relative Strength Index, using Wilder's smoothing.
+pub struct Rsi {
+    period: usize,
+    source: PriceSource,
+    state: RsiState,
+}
+impl Rsi {
+    /// Create a new `Rsi` over `period` bars of the close price.
+    ///
+    /// NOTE: A period of 0 is treated as a period of 1.
+    pub fn new(period: usize) -> Self {
+        Self {
+            period: period.max(1),
+            source: PriceSource::Close,
+            state: RsiState::default(),
+        }
+    }
+
+    /// Set the price the index is computed on.
+    pub fn source(mut self, source: PriceSource) -> Self {
+        self.source = source;
+        self
+    }
+
+    /// The state after adding `price`.
+    fn next(&self, price: f64) -> RsiState {
+        let Some(previous) = self.state.previous else {
+            return RsiState {
+                previous: Some(price),
+                ..self.state
+            };
+        };
+
+        let change = price - previous;
+        RsiState {
+            previous: Some(price),
+            gains: self.state.gains.next(change.max(0.0), self.period),
+            losses: self.state.losses.next((-change).max(0.0), self.period),
+        }
+    }
+
+    /// The index of a state, if it has enough values.
+    fn value(state: &RsiState) -> Option<f64> {
+        let (gain, loss) = (state.gains.value?, state.losses.value?);
+
+        if loss == 0.0 {
+            Some(if gain == 0.0 { 50.0 } else { 100.0 })
+        } else {
+            Some(100.0 - 100.0 / (1.0 + gain / loss))
+        }
+    }
+}
+impl Indicator for Rsi {
+    type Output = f64;
+
+    fn peek(&self, bar: &BarValues) -> Option<f64> {
+        Self::value(&self.next(bar.price(self.source)))
+    }
+
+    fn commit(&mut self, bar: &BarValues) -> Option<f64> {
+        self.state = self.next(bar.price(self.source));
+        Self::value(&self.state)
+    }
+
+    fn reset(&mut self) {
+        self.state = RsiState::default();
+    }
+}
+
+#[derive(Clone, Debug)]
+/// Average True Range, using Wilder's smoothing.
+pub struct Atr {
+    period: usize,
+    previous_close: Option<f64>,
+    state: WilderState,
+}
+impl Atr {
+    /// Create a new `Atr` over `period` bars.
+    ///
+    /// NOTE: A period of 0 is treated as a period of 1.
+    pub fn new(period: usize) -> Self {
+        Self {
+            period: period.max(1),
+            previous_close: None,
+            state: WilderState::default(),
+        }
+    }
+
+    /// The true range of a bar given the previous close.
+    fn true_range(&self, bar: &BarValues) -> f64 {
+        let range = bar.high - bar.low;
+
+        match self.previous_close {
+            Some(close) => range
+                .max((bar.high - close).abs())
+                .max((bar.low - close).abs()),
+            None => range,
+        }
+    }
+}
+impl Indicator for Atr {
+    type Output = f64;
+
+    fn peek(&self, bar: &BarValues) -> Option<f64> {
+        self.state.next(self.true_range(bar), self.period).value
+    }
+
+    fn commit(&mut self, bar: &BarValues) -> Option<f64> {
+        self.state = self.state.next(self.true_range(bar), self.period);
+        self.previous_close = Some(bar.close);
+
+        self.state.value
+    }
+
+    fn reset(&mut self) {
+        self.previous_close = None;
+        self.state = WilderState::default();
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+/// The value of [`BollingerBands`].
+pub struct Bands {
+    /// The upper band, `middle + (multiplier * standard deviation)`.
+    pub upper: f64,
+
+    /// The middle band, a simple moving average.
+    pub middle: f64,
+
+    /// The lower band, `middle - (multiplier * standard deviation)`.
+    pub lower: f64,
+}
+
+#[derive(Clone, Debug)]
+/// Bollinger Bands, using the population standard deviation.
+pub struct BollingerBands {
+    period: usize,
+    multiplier: f64,
+    source: PriceSource,
+    window: VecDeque<f64>,
+    sum: f64,
+    sum_of_squares: f64,
+}
+impl BollingerBands {
+    /// Create new `BollingerBands` over `period` bars of the close price,
+    /// with bands `multiplier` standard deviations away from the average.
+    ///
+    /// NOTE: A period of 0 is treated as a period of 1.
+    pub fn new(period: usize, multiplier: f64) -> Self {
+        let period = period.max(1);
+
+        Self {
+            period,
+            multiplier,
+            source: PriceSource::Close,
+            window: VecDeque::with_capacity(period),
+            sum: 0.0,
+            sum_of_squares: 0.0,
+        }
+    }
+
+    /// Set the price the bands are computed on.
+    pub fn source(mut self, source: PriceSource) -> Self {
+        self.source = source;
+        self
+    }
+
+    /// The sums and the amount of values in the window if `price` was added.
+    fn next_sums(&self, price: f64) -> (f64, f64, usize) {
+        if self.window.len() == self.period {
+            let oldest = self.window.front().copied().unwrap_or_default();
+            (
+                self.sum - oldest + price,
+                self.sum_of_squares - oldest * oldest + price * price,
+                self.period,
+            )
+        } else {
+            (
+                self.sum + price,
+                self.sum_of_squares + price * price,
+                self.window.len() + 1,
+            )
+        }
+    }
+
+    /// The bands for the given sums, if the window is full.
+    fn bands(&self, sum: f64, sum_of_squares: f64, len: usize) -> Option<Bands> {
+        if len < self.period {
+            return None;
+        }
+
+        let period = self.period as f64;
+        let middle = sum / period;
+        let deviation = (sum_of_squares / period - middle * middle).max(0.0).sqrt();
+
+        Some(Bands {
+            upper: middle + self.multiplier * deviation,
+            middle,
+            lower: middle - self.multiplier * deviation,
+        })
+    }
+}
+impl Indicator for BollingerBands {
+    type Output = Bands;
+
+    fn peek(&self, bar: &BarValues) -> Option<Bands> {
+        let (sum, sum_of_squares, len) = self.next_sums(bar.price(self.source));
+        self.bands(sum, sum_of_squares, len)
+    }
+
+    fn commit(&mut self, bar: &BarValues) -> Option<Bands> {
+        let price = bar.price(self.source);
+        let (sum, sum_of_squares, len) = self.next_sums(price);
+
+        if self.window.len() == self.period {
+            self.window.pop_front();
+        }
+        self.window.push_back(price);
+        self.sum = sum;
+        self.sum_of_squares = sum_of_squares;
+
+        self.bands(sum, sum_of_squares, len)
+    }
+
+    fn reset(&mut self) {
+        self.window.clear();
+        self.sum = 0.0;
+        self.sum_of_squares = 0.0;
+    }
+}
+
+#[derive(Clone, Debug)]
+/// Volume Weighted Average Price, of the typical price ([`PriceSource::HLC3`]).
+///
+/// NOTE: Accumulates from the first bar seen, use [`BarSeries::reset`]
+/// at the start of each session to anchor it to the session.
+pub struct Vwap {
+    source: PriceSource,
+    price_volume: f64,
+    volume: f64,
+}
+impl Default for Vwap {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+impl Vwap {
+    /// Create a new `Vwap`.
+    pub fn new() -> Self {
+        Self {
+            source: PriceSource::HLC3,
+            price_volume: 0.0,
+            volume: 0.0,
+        }
+    }
+
+    /// Set the price the average is weighted on.
+    pub fn source(mut self, source: PriceSource) -> Self {
+        self.source = source;
+        self
+    }
+
+    /// The running sums if `bar` was added.
+    fn next_sums(&self, bar: &BarValues) -> (f64, f64) {
+        (
+            self.price_volume + bar.price(self.source) * bar.volume,
+            self.volume + bar.volume,
+        )
+    }
+}
+impl Indicator for Vwap {
+    type Output = f64;
+
+    fn peek(&self, bar: &BarValues) -> Option<f64> {
+        let (price_volume, volume) = self.next_sums(bar);
+        (volume > 0.0).then(|| price_volume / volume)
+    }
+
+    fn commit(&mut self, bar: &BarValues) -> Option<f64> {
+        (self.price_volume, self.volume) = self.next_sums(bar);
+        (self.volume > 0.0).then(|| self.price_volume / self.volume)
+    }
+
+    fn reset(&mut self) {
+        self.price_volume = 0.0;
+        self.volume = 0.0;
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+/// The value of a [`Macd`].
+pub struct MacdValue {
+    /// The fast average minus the slow average.
+    pub macd: f64,
+
+    /// The average of the `macd` line.
+    pub signal: f64,
+
+    /// The `macd` line minus the `signal` line.
+    pub histogram: f64,
+}
+
+#[derive(Clone, Copy, Debug, Default)]
+/// The running state of a [`Macd`].
+struct MacdState {
+    fast: EmaState,
+    slow: EmaState,
+    signal: EmaState,
+}
+
+#[derive(Clone, Debug)]
+/// Moving Average Convergence Divergence.
+pub struct Macd {
+    fast_period: usize,
+    slow_period: usize,
+    signal_period: usize,
+    source: PriceSource,
+    state: MacdState,
+}
+impl Default for Macd {
+    /// The common 12, 26, 9 `Macd`.
+    fn default() -> Self {
+        Self::new(12, 26, 9)
+    }
+}
+impl Macd {
+    /// Create a new `Macd` of the close price.
+    ///
+    /// NOTE: A period of 0 is treated as a period of 1.
+    pub fn new(fast_period: usize, slow_period: usize, signal_period: usize) -> Self {
+        Self {
+            fast_period: fast_period.max(1),
+            slow_period: slow_period.max(1),
+            signal_period: signal_period.max(1),
+            source: PriceSource::Close,
+            state: MacdState::default(),
+        }
+    }
+
+    /// Set the price the averages are computed on.
+    pub fn source(mut self, source: PriceSource) -> Self {
+        self.source = source;
+        self
+    }
+
+    /// The state after adding `price`.
+    fn next(&self, price: f64) -> MacdState {
+        let fast = self.state.fast.next(price, self.fast_period);
+        let slow = self.state.slow.next(price, self.slow_period);
+
+        let signal = match (fast.value, slow.value) {
+            (Some(fast), Some(slow)) => self.state.signal.next(fast - slow, self.signal_period),
+            _ => self.state.signal,
+        };
+
+        MacdState { fast, slow, signal }
+    }
+
+    /// The value of a state, if it has enough values.
+    fn value(state: &MacdState) -> Option<MacdValue> {
+        let macd = state.fast.value? - state.slow.value?;
+        let signal = state.signal.value?;
+
+        Some(MacdValue {
+            macd,
+            signal,
+            histogram: macd - signal,
+        })
+    }
+}
+impl Indicator for Macd {
+    type Output = MacdValue;
+
+    fn peek(&self, bar: &BarValues) -> Option<MacdValue> {
+        Self::value(&self.next(bar.price(self.source)))
+    }
+
+    fn commit(&mut self, bar: &BarValues) -> Option<MacdValue> {
+        self.state = self.next(bar.price(self.source));
+        Self::value(&self.state)
+    }
+
+    fn reset(&mut self) {
+        self.state = MacdState::default();
+    }
+}
@@ -3,12 +3,14 @@
 //! - [Stream Bars](#endpoint-stream-bars): Streams price bars for a symbol.
 //! - [Get Option Expirations](#endpoint-get-option-expirations): Fetches available option expiration dates for an underlying symbol.
 //! - [Get Option Risk/Reward](#endpoint-get-option-riskreward): Analyzes maximum gain, maximum loss, and breakeven points for an options spread.
+//! - [Get Option Spread Types](#endpoint-get-option-spread-types): Fetches the supported option spread types.
 //! - [Get Option Spread Strikes](#endpoint-get-option-spread-strikes): Fetches valid strike combinations for an option spread type.
 //! - [Stream Option Chain](#endpoint-stream-option-chain): Streams option chain quotes for an underlying symbol.
 //! - [Stream Option Quotes](#endpoint-stream-option-quotes): Streams calculated quotes and greeks for an options spread.
 //! - [Get Quote Snapshots](#endpoint-get-quote-snapshots): Fetches latest quote snapshots for one or more symbols.
 //! - [Stream Quotes](#endpoint-stream-quotes): Streams quote updates for one or more symbols.
 //! - [Get Symbol Details](#endpoint-get-symbol-details): Fetches symbol metadata and formatting details.
+//! - [Get Crypto Symbol Names](#endpoint-get-crypto-symbol-names): Fetches the symbol names of all supported crypto pairs.
 //! - [Stream Market Depth Aggregates](#endpoint-stream-market-depth-aggregates): Streams aggregated market depth by price level.
 //! - [Stream Market Depth Quotes](#endpoint-stream-market-depth-quotes): Streams participant-level market depth quotes.
 //!
@@ -107,6 +109,24 @@
 //!
 //! ---
 //!
+//! # Endpoint: Get Option Spread Types
+//! - Fetches the supported option spread types, and whether each involves an interval of strikes or expirations.
+//! - Spread Type Reference: [`crate::market_data::OptionSpreadTypeDetails`]
+//! - **Example**: List the spread types involving multiple expirations.
+//!
+//! ```rust,no_run
+//! # use tradestation::{Client, Error};
+//! # async fn get_option_spread_types_example(client: &Client) -> Result<(), Error> {
+//! for spread_type in client.get_option_spread_types().await? {
+//!     if spread_type.involves_expiration_interval() {
+//!         println!("{} spans multiple expirations", spread_type.name);
+//!     }
+//! }
+//! # Ok(()) }
+//! ```
+//!
+//! ---
+//!
 //! # Endpoint: Get Option Spread Strikes
 //! - Fetches valid strike combinations for a spread type and expiration date.
 //! - Spread Strikes Reference: [`crate::market_data::OptionSpreadStrikes`]
@@ -236,6 +256,27 @@
 //!
 //! ---
 //!
+//! # Endpoint: Get Crypto Symbol Names
+//! - Fetches the symbol names of every crypto pair, e.g. `BTCUSD`.
+//! - Crypto Pair Reference: [`crate::market_data::CryptoPair`]
+//! - **Example**: List the crypto pairs quoted in US dollars.
+//!
+//! ```rust,no_run
+//! # use tradestation::{Client, Error};
+//! # async fn get_crypto_pairs_example(client: &Client) -> Result<(), Error> {
+//! let symbol_names = client.get_crypto_symbol_names().await?;
+//! println!("Loaded {} crypto pairs", symbol_names.len());
+//!
+//! for pair in client.get_crypto_pairs().await? {
+//!     if pair.quote == "USD" {
+//!         println!("{} priced in {}", pair.base, pair.quote);
+//!     }
+//! }
+//! # Ok(()) }
+//! ```
+//!
+//! ---
+//!
 //! # Endpoint: Stream Market Depth Aggregates
 //! - Streams market depth aggregated by price level.
 //! - Aggregate Reference: [`crate::market_data::MarketDepthAggregates`]
@@ -270,35 +311,75 @@
 //! # Ok(()) }
 //! ```
 //!
+/// Option pricing models, greeks, and implied volatility.
+pub mod analytics;
+
 /// Types and functionality for working with market data bars.
 pub mod bar;
 
+/// Types and functionality for working with crypto pairs.
+pub mod crypto;
+
+/// Futures contract symbology, chains, roll schedules, and continuous bars.
+pub mod futures;
+
+/// Incremental technical indicators computed from market data bars.
+pub mod indicators;
+
 /// Types and functionality for working with market depth.
 pub mod market_depth;
 
 /// Types and functionality for working with options market data.
 pub mod options;
 
+/// Parsing and formatting of equity, index, and futures option symbols.
+pub mod option_symbol;
+
+/// Local option payoff and risk vs reward analysis.
+pub mod payoff;
+
 /// Types and functionality for working with market data quotes.
 pub mod quote;
 
 /// Types and functionality for working with symbol details.
 pub mod symbol;
 
+/// Batched and cached symbol details lookups.
+pub mod symbol_cache;
+
+/// Types and functionality for multiplexing quote stream subscriptions.
+pub mod subscription;
+
+/// Implied volatility surfaces, term structures, and skew across option expirations.
+pub mod volatility;
+
+pub use analytics::{ChainComparison, EuropeanOption, Greeks, PricingModel, QuoteSide};
 pub use bar::{
     Bar, BarUnit, GetBarsQuery, GetBarsQueryBuilder, StreamBarsQuery, StreamBarsQueryBuilder,
 };
+pub use crypto::CryptoPair;
+pub use futures::{BackAdjustment, FuturesChain, FuturesContract, RollRule, RollSchedule};
+pub use indicators::{
+    Atr, BarSeries, BarValues, BollingerBands, Ema, Indicator, Macd, PriceSource, Rsi, Sma, Vwap,
+};
 pub use market_depth::{
     MarketDepthAggregate, MarketDepthAggregates, MarketDepthQuote, MarketDepthQuotes,
     MarketDepthSide,
 };
+pub use option_symbol::{OptionSymbol, OptionSymbolBuilder, OptionSymbolExpiration};
 pub use options::{
     OptionChain, OptionChainQuery, OptionChainQueryBuilder, OptionExpiration, OptionExpirationType,
     OptionQuote, OptionQuoteLeg, OptionQuoteQuery, OptionQuoteQueryBuilder,
     OptionRiskRewardAnalysis, OptionSpreadStrikes, OptionSpreadStrikesQuery,
-    OptionSpreadStrikesQueryBuilder, OptionSpreadType, OptionTradeAction, OptionsLeg,
+    OptionSpreadStrikesQueryBuilder, OptionSpreadType, OptionSpreadTypeDetails, OptionTradeAction,
+    OptionsLeg,
 };
+pub use payoff::{PayoffEngine, PayoffSurface};
 pub use quote::{MarketFlag, Quote, QuoteStreamUpdate};
+pub use subscription::{QuoteSubscription, QuoteSubscriptionManager};
 pub use symbol::{
-    Format, IncrementSchedule, IncrementStyle, PriceFormat, QuantityFormat, SymbolDetails,
+    Format, IncrementSchedule, IncrementStyle, PriceFormat, QuantityFormat, RoundingMode,
+    SymbolDetails,
 };
+pub use symbol_cache::SymbolDetailsCache;
+pub use volatility::{LiveVolatilitySurface, VolatilitySurface};
@@ -0,0 +1,400 @@
+//! Parses and formats TradeStation option symbols, so option legs can be
+//! built from structured data and validated before they're sent.
+//!
+//! TradeStation uses 2 option symbol formats:
+//! - Equity and index options: `"{ROOT} {YYMMDD}{C|P}{STRIKE}"`, for example `"MSFT 241220C420"`.
+//! - Futures options: `"{FUTURE} {C|P}{STRIKE}"`, where the future is the root,
+//!   month code, and year of the underlying contract, for example `"CLZ24 C75"`.
+//!
+//! # Example
+//! ---
+//!
+//! Parse an option symbol, then build the same symbol from structured data.
+//!
+//! ```rust
+//! use tradestation::{
+//!     market_data::option_symbol::{OptionSymbol, OptionSymbolBuilder},
+//!     orders::OptionType,
+//! };
+//!
+//! let parsed: OptionSymbol = "MSFT 241220C420".parse().unwrap();
+//! assert_eq!(parsed.root, "MSFT");
+//! assert_eq!(parsed.option_type, OptionType::Call);
+//! assert_eq!(parsed.strike, 420.0);
+//! assert_eq!(parsed.expiration_date().as_deref(), Some("2024-12-20"));
+//!
+//! let built = OptionSymbolBuilder::new()
+//!     .root("MSFT")
+//!     .expiration("2024-12-20")
+//!     .option_type(OptionType::Call)
+//!     .strike(420.0)
+//!     .build()
+//!     .unwrap();
+//! assert_eq!(built, parsed);
+//! assert_eq!(built.to_string(), "MSFT 241220C420");
+//!
+//! // Futures options work the same way
+//! let future_option: OptionSymbol = "CLZ24 P72.5".parse().unwrap();
+//! assert_eq!(future_option.root, "CL");
+//! assert_eq!(future_option.underlying(), "CLZ24");
+//! assert_eq!(future_option.to_string(), "CLZ24 P72.5");
+//! ```
+
+use crate::{
+    accounting::OptionType,
+    execution::{OrderRequestLeg, TradeAction},
+    market_data::{futures::FuturesContract, options::OptionSpreadLeg, OptionQuoteLeg},
+    utils::{civil_from_days, format_number, parse_number, parse_timestamp, SECONDS_PER_DAY},
+    Error,
+};
+use std::{fmt, str::FromStr};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+/// When an option expires.
+pub enum OptionSymbolExpiration {
+    /// The exact expiration date, used by equity and index options.
+    Date {
+        /// The year (`2024`).
+        year: i32,
+
+        /// The month (`1` through `12`).
+        month: u32,
+
+        /// The day of the month.
+        day: u32,
+    },
+
+    /// The contract month of the underlying future, used by futures options.
+    ContractMonth {
+        /// The year (`2024`).
+        year: i32,
+
+        /// The month (`1` through `12`).
+        month: u32,
+    },
+}
+
+#[derive(Clone, Debug, PartialEq)]
+/// A parsed TradeStation option symbol.
+pub struct OptionSymbol {
+    /// The root symbol, for example `"MSFT"` or `"CL"` for a futures option.
+    pub root: String,
+
+    /// When the option expires.
+    pub expiration: OptionSymbolExpiration,
+
+    /// Call or put.
+    pub option_type: OptionType,
+
+    /// The strike price.
+    pub strike: f64,
+}
+impl OptionSymbol {
+    /// Parse a TradeStation option symbol.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::InvalidOptionSymbol`] if the symbol isn't
+    /// an equity, index, or futures option symbol.
+    pub fn parse(symbol: &str) -> Result<Self, Error> {
+        let invalid = || Error::InvalidOptionSymbol(symbol.to_string());
+
+        let (underlying, contract) = symbol.trim().rsplit_once(' ').ok_or_else(invalid)?;
+        let underlying = underlying.trim().to_uppercase();
+        if underlying.is_empty() || underlying.contains(' ') {
+            return Err(invalid());
+        }
+
+        // Futures options have the option type right after the space
+        if let Some(strike) = contract.strip_prefix(['C', 'c', 'P', 'p']) {
+            let option_type = parse_option_type(&contract[..1]).ok_or_else(invalid)?;
+            let future = FuturesContract::parse(&underlying).map_err(|_| invalid())?;
+
+            return Ok(Self {
+                root: future.root,
+                expiration: OptionSymbolExpiration::ContractMonth {
+                    year: future.year,
+                    month: future.month,
+                },
+                option_type,
+                strike: parse_strike(strike).ok_or_else(invalid)?,
+            });
+        }
+
+        let date = contract
+            .get(..6)
+            .filter(|date| date.bytes().all(|byte| byte.is_ascii_digit()))
+            .ok_or_else(invalid)?;
+        let option_type = contract
+            .get(6..7)
+            .and_then(parse_option_type)
+            .ok_or_else(invalid)?;
+        let strike = contract
+            .get(7..)
+            .and_then(parse_strike)
+            .ok_or_else(invalid)?;
+
+        let (year, month, day) = (
+            2000 + date[..2].parse::<i32>().map_err(|_| invalid())?,
+            date[2..4].parse::<u32>().map_err(|_| invalid())?,
+            date[4..].parse::<u32>().map_err(|_| invalid())?,
+        );
+        if !is_valid_date(year, month, day) {
+            return Err(invalid());
+        }
+
+        Ok(Self {
+            root: underlying,
+            expiration: OptionSymbolExpiration::Date { year, month, day },
+            option_type,
+            strike,
+        })
+    }
+
+    /// Check if a symbol is a valid TradeStation option symbol.
+    pub fn is_valid(symbol: &str) -> bool {
+        Self::parse(symbol).is_ok()
+    }
+
+    /// Get the underlying symbol, the root for equity and index
+    /// options, or the futures contract (`"CLZ24"`) for futures options.
+    pub fn underlying(&self) -> String {
+        match self.expiration {
+            OptionSymbolExpiration::Date { .. } => self.root.clone(),
+            OptionSymbolExpiration::ContractMonth { year, month } => FuturesContract {
+                root: self.root.clone(),
+                year,
+                month,
+            }
+            .to_string(),
+        }
+    }
+
+    /// Check if this is an option on a futures contract.
+    pub fn is_futures_option(&self) -> bool {
+        matches!(
+            self.expiration,
+            OptionSymbolExpiration::ContractMonth { .. }
+        )
+    }
+
+    /// Get the expiration date (`"2024-12-20"`).
+    ///
+    /// NOTE: This is `None` for futures options, as the symbol
+    /// only has the contract month of the underlying future.
+    pub fn expiration_date(&self) -> Option<String> {
+        match self.expiration {
+            OptionSymbolExpiration::Date { year, month, day } => {
+                Some(format!("{year:04}-{month:02}-{day:02}"))
+            }
+            OptionSymbolExpiration::ContractMonth { .. } => None,
+        }
+    }
+
+    /// Build an [`OrderRequestLeg`] for this option.
+    pub fn order_leg(&self, quantity: u32, trade_action: TradeAction) -> OrderRequestLeg {
+        OrderRequestLeg {
+            symbol: self.to_string(),
+            quantity: quantity.to_string(),
+            trade_action,
+            strike_price: Some(format_number(self.strike)),
+            option_type: Some(self.option_type),
+            expiration_date: self
+                .expiration_date()
+                .map(|date| format!("{date}T00:00:00Z")),
+        }
+    }
+
+    /// Build an [`OptionQuoteLeg`] for this option.
+    ///
+    /// NOTE: Use a positive ratio for buying and a negative ratio for selling.
+    pub fn quote_leg(&self, ratio: i32) -> OptionQuoteLeg {
+        OptionQuoteLeg {
+            symbol: self.to_string(),
+            ratio,
+        }
+    }
+}
+impl fmt::Display for OptionSymbol {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let option_type = match self.option_type {
+            OptionType::Call => 'C',
+            OptionType::Put => 'P',
+        };
+        let strike = format_number(self.strike);
+
+        match self.expiration {
+            OptionSymbolExpiration::Date { year, month, day } => write!(
+                f,
+                "{} {:02}{month:02}{day:02}{option_type}{strike}",
+                self.root,
+                year.rem_euclid(100)
+            ),
+            OptionSymbolExpiration::ContractMonth { .. } => {
+                write!(f, "{} {option_type}{strike}", self.underlying())
+            }
+        }
+    }
+}
+impl FromStr for OptionSymbol {
+    type Err = Error;
+
+    fn from_str(symbol: &str) -> Result<Self, Self::Err> {
+        Self::parse(symbol)
+    }
+}
+impl TryFrom<&OptionSpreadLeg> for OptionSymbol {
+    type Error = Error;
+
+    fn try_from(leg: &OptionSpreadLeg) -> Result<Self, Self::Error> {
+        Self::parse(&leg.symbol)
+    }
+}
+impl From<OptionSymbol> for String {
+    fn from(symbol: OptionSymbol) -> Self {
+        symbol.to_string()
+    }
+}
+
+#[derive(Debug, Default)]
+/// Builder for [`OptionSymbol`]
+pub struct OptionSymbolBuilder {
+    root: Option<String>,
+    expiration: Option<Result<OptionSymbolExpiration, String>>,
+    option_type: Option<OptionType>,
+    strike: Option<f64>,
+}
+impl OptionSymbolBuilder {
+    /// Create a new builder for [`OptionSymbol`].
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the root symbol, for example `"MSFT"`, or `"CL"` for a futures option.
+    pub fn root<S: Into<String>>(mut self, root: S) -> Self {
+        self.root = Some(root.into());
+
+        self
+    }
+
+    /// Set the expiration date of an equity or index option.
+    ///
+    /// NOTE: Accepts a date (`"2024-12-20"`) or an RFC3339
+    /// timestamp like TradeStation's `"2024-12-20T00:00:00Z"`.
+    pub fn expiration(mut self, date: &str) -> Self {
+        self.expiration = Some(
+            parse_timestamp(date)
+                .map(|timestamp| {
+                    let (year, month, day) = civil_from_days(timestamp.div_euclid(SECONDS_PER_DAY));
+                    OptionSymbolExpiration::Date {
+                        year: year as i32,
+                        month,
+                        day,
+                    }
+                })
+                .map_err(|_| date.to_string()),
+        );
+
+        self
+    }
+
+    /// Set the contract month of the underlying future for a futures option.
+    pub fn contract_month(mut self, year: i32, month: u32) -> Self {
+        self.expiration = Some(if (1..=12).contains(&month) {
+            Ok(OptionSymbolExpiration::ContractMonth { year, month })
+        } else {
+            Err(format!("{year}-{month:02}"))
+        });
+
+        self
+    }
+
+    /// Set the option type (call or put).
+    pub fn option_type(mut self, option_type: OptionType) -> Self {
+        self.option_type = Some(option_type);
+
+        self
+    }
+
+    /// Set the strike price.
+    pub fn strike(mut self, strike: f64) -> Self {
+        self.strike = Some(strike);
+
+        self
+    }
+
+    /// Finish building [`OptionSymbol`]
+    ///
+    /// # Errors
+    ///
+    /// - [`Error::SymbolNotSet`], [`Error::ExpirationNotSet`], [`Error::OptionTypeNotSet`],
+    ///   or [`Error::StrikePriceNotSet`] if any of them weren't set.
+    /// - [`Error::InvalidTimestamp`] if the expiration isn't a valid date.
+    /// - [`Error::InvalidOptionSymbol`] if the root or strike can't be used in a symbol.
+    pub fn build(self) -> Result<OptionSymbol, Error> {
+        let root = self.root.ok_or(Error::SymbolNotSet)?.trim().to_uppercase();
+        let expiration = self
+            .expiration
+            .ok_or(Error::ExpirationNotSet)?
+            .map_err(Error::InvalidTimestamp)?;
+        let option_type = self.option_type.ok_or(Error::OptionTypeNotSet)?;
+        let strike = self.strike.ok_or(Error::StrikePriceNotSet)?;
+
+        let symbol = OptionSymbol {
+            root,
+            expiration,
+            option_type,
+            strike,
+        };
+        if symbol.root.is_empty() || symbol.root.contains(' ') || strike.is_nan() || strike <= 0.0 {
+            return Err(Error::InvalidOptionSymbol(symbol.to_string()));
+        }
+
+        Ok(symbol)
+    }
+}
+
+/// Validate the symbol of an option leg, anything with a space must be an
+/// option symbol, while symbols without one are the underlying itself.
+pub(crate) fn validate_leg_symbol(symbol: &str) -> Result<(), Error> {
+    if symbol.trim().contains(' ') {
+        OptionSymbol::parse(symbol)?;
+    }
+
+    Ok(())
+}
+
+/// Parse the option type character of a symbol.
+fn parse_option_type(option_type: &str) -> Option<OptionType> {
+    match option_type {
+        "C" | "c" => Some(OptionType::Call),
+        "P" | "p" => Some(OptionType::Put),
+        _ => None,
+    }
+}
+
+/// Parse the strike of a symbol, which must be a positive number.
+fn parse_strike(strike: &str) -> Option<f64> {
+    if strike.is_empty()
+        || !strike
+            .bytes()
+            .all(|byte| byte.is_ascii_digit() || byte == b'.')
+    {
+        return None;
+    }
+
+    parse_number(strike).ok().filter(|strike| *strike > 0.0)
+}
+
+/// Check if a date exists (no February 30th).
+fn is_valid_date(year: i32, month: u32, day: u32) -> bool {
+    let days_in_month = match month {
+        1 | 3 | 5 | 7 | 8 | 10 | 12 => 31,
+        4 | 6 | 9 | 11 => 30,
+        2 if (year % 4 == 0 && year % 100 != 0) || year % 400 == 0 => 29,
+        2 => 28,
+        _ => return false,
+    };
+
+    (1..=days_in_month).contains(&day)
+}
@@ -1,8 +1,9 @@
 use crate::{
+    market_data::{option_symbol::validate_leg_symbol, PayoffEngine},
     responses::{
         market_data::{
-            GetOptionExpirationsResp, GetOptionExpirationsRespRaw, GetOptionsRiskRewardResp,
-            GetOptionsRiskRewardRespRaw,
+            GetOptionExpirationsResp, GetOptionExpirationsRespRaw, GetOptionSpreadTypesResp,
+            GetOptionsRiskRewardResp, GetOptionsRiskRewardRespRaw,
         },
         market_data::{
             OptionSpreadStrikesResp, OptionSpreadStrikesRespRaw, StreamOptionChainResp,
@@ -10,11 +11,12 @@ use crate::{
         },
         ApiResponse,
     },
-    Client, Error,
+    Client, ClientEnvironment, Error,
 };
 use futures::{Stream, StreamExt};
 use serde::{Deserialize, Serialize};
 use serde_json::json;
+use tracing::debug;
 
 #[derive(Clone, Debug, Deserialize, Serialize)]
 #[serde(rename_all = "PascalCase")]
@@ -306,28 +308,106 @@ impl OptionSpreadType {
         matches!(self, Self::Calendar | Self::Diagonal)
     }
 }
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "PascalCase")]
+/// Metadata on an options spread type supported by TradeStation.
+///
+/// NOTE: The `name` is kept as is, so spread types TradeStation adds in the
+/// future (which aren't an [`OptionSpreadType`] yet) can still be used.
+pub struct OptionSpreadTypeDetails {
+    /// The name of the spread type, e.g. `"IronCondor"`.
+    pub name: String,
+
+    /// Does the spread type involve an interval of strike prices?
+    pub strike_interval: bool,
+
+    /// Does the spread type involve an interval of expirations?
+    pub expiration_interval: bool,
+}
+impl OptionSpreadTypeDetails {
+    /// Fetch the option spread types supported by TradeStation.
+    ///
+    /// NOTE: In a [`ClientEnvironment::Mock`] environment this falls back to
+    /// [`OptionSpreadType::all()`] if the request fails.
+    pub async fn fetch(client: &Client) -> Result<Vec<OptionSpreadTypeDetails>, Error> {
+        let endpoint = "marketdata/options/spreadtypes";
+
+        let result = match client.get(endpoint).await {
+            Ok(resp) => match resp.json::<ApiResponse<GetOptionSpreadTypesResp>>().await {
+                Ok(ApiResponse::Success(resp)) => Ok(resp.spread_types),
+                Ok(ApiResponse::Error(resp)) => Err(Error::from_api_error(resp)),
+                Err(e) => Err(e.into()),
+            },
+            Err(e) => Err(e),
+        };
+
+        match (result, &client.environment) {
+            (Err(e), ClientEnvironment::Mock(_)) => {
+                debug!(
+                    target: "tradestation::client",
+                    error = %e,
+                    "falling back to the static option spread types"
+                );
+                Ok(OptionSpreadType::all()
+                    .into_iter()
+                    .map(Into::into)
+                    .collect())
+            }
+            (result, _) => result,
+        }
+    }
+
+    /// Get the [`OptionSpreadType`] of this spread type.
+    ///
+    /// NOTE: Will be `None` for spread types this crate doesn't know about yet.
+    pub fn spread_type(&self) -> Option<OptionSpreadType> {
+        serde_json::from_value(serde_json::Value::String(self.name.clone())).ok()
+    }
+
+    /// Does the spread type involve an interval of strike prices?
+    pub fn involves_strike_interval(&self) -> bool {
+        self.strike_interval
+    }
+
+    /// Does the spread type involve an interval of expirations?
+    pub fn involves_expiration_interval(&self) -> bool {
+        self.expiration_interval
+    }
+}
+impl From<OptionSpreadType> for OptionSpreadTypeDetails {
+    fn from(spread_type: OptionSpreadType) -> Self {
+        OptionSpreadTypeDetails {
+            name: format!("{spread_type:?}"),
+            strike_interval: spread_type.involves_strike_interval(),
+            expiration_interval: spread_type.involves_expiration_interval(),
+        }
+    }
+}
 impl Client {
-    /// Get a vector of all the option spread types.
+    /// Fetch the option spread types supported by TradeStation, with
+    /// whether each involves an interval of strikes or expirations.
+    ///
+    /// NOTE: In a [`ClientEnvironment::Mock`] environment this falls back to
+    /// [`OptionSpreadType::all()`] if the request fails.
     ///
     /// # Example
     /// ---
     ///
     /// Get all the spread types and print information about them:
     ///
-    /// ```rust
-    /// use tradestation::market_data::OptionSpreadType;
-    ///
-    /// let option_spread_types = OptionSpreadType::all();
+    /// ```ignore
+    /// let option_spread_types = client.get_option_spread_types().await?;
     /// for spread_type in option_spread_types.iter() {
     ///     println!(
-    ///         "{spread_type:?} | contains stike interval {} | contains expiration interval: {}",
+    ///         "{} | contains stike interval {} | contains expiration interval: {}",
+    ///         spread_type.name,
     ///         spread_type.involves_strike_interval(),
     ///         spread_type.involves_expiration_interval()
     ///     );
     /// }
     /// ```
-    pub fn get_option_spread_types(&self) -> Vec<OptionSpreadType> {
-        OptionSpreadType::all()
+    pub async fn get_option_spread_types(&self) -> Result<Vec<OptionSpreadTypeDetails>, Error> {
+        OptionSpreadTypeDetails::fetch(self).await
     }
 }
 
@@ -420,6 +500,39 @@ impl OptionRiskRewardAnalysis {
             ApiResponse::Error(resp) => Err(Error::from_api_error(resp)),
         }
     }
+
+    /// Run the same analysis on an options trade locally, without calling
+    /// TradeStation, using a [`crate::market_data::PayoffEngine`].
+    ///
+    /// NOTE: All the option legs must be the same symbol, and expiration.
+    ///
+    /// # Example
+    /// ---
+    ///
+    /// Analyze a bull call spread on MSFT without a round trip to TradeStation.
+    ///
+    /// ```ignore
+    /// let risk_reward_analysis = OptionRiskRewardAnalysis::run_locally(
+    ///     4.10,
+    ///     &[
+    ///         OptionsLeg {
+    ///             symbol: String::from("MSFT 241220C420"),
+    ///             quantity: 1,
+    ///             trade_action: OptionTradeAction::Buy,
+    ///         },
+    ///         OptionsLeg {
+    ///             symbol: String::from("MSFT 241220C430"),
+    ///             quantity: 1,
+    ///             trade_action: OptionTradeAction::Sell,
+    ///         },
+    ///     ],
+    /// )?;
+    ///
+    /// println!("MSFT Bull Call Spread Risk vs Reward Analysis: {risk_reward_analysis:?}");
+    /// ```
+    pub fn run_locally(price: f64, legs: &[OptionsLeg]) -> Result<Self, Error> {
+        PayoffEngine::new(price, legs).map(|engine| engine.analyze())
+    }
 }
 impl Client {
     /// Run analysis on an options trade given a price and option legs.
@@ -2021,6 +2134,7 @@ pub struct OptionQuoteQueryBuilder {
     legs: Option<Vec<OptionQuoteLeg>>,
     risk_free_rate: Option<f64>,
     enable_greeks: Option<bool>,
+    validate_option_symbols: Option<bool>,
 }
 impl OptionQuoteQueryBuilder {
     /// Create a new builder for [`OptionQuoteQuery`].
@@ -2052,10 +2166,31 @@ impl OptionQuoteQueryBuilder {
         self
     }
 
+    /// Set if the option leg symbols should be validated as
+    /// [`crate::market_data::OptionSymbol`]'s when building.
+    ///
+    /// NOTE: Defaults to `false`, since not every symbol TradeStation
+    /// accepts can be parsed as an [`crate::market_data::OptionSymbol`].
+    pub fn validate_option_symbols(mut self, validate: bool) -> Self {
+        self.validate_option_symbols = Some(validate);
+
+        self
+    }
+
     /// Finish building [`OptionQuoteQuery`]
+    ///
+    /// NOTE: When `validate_option_symbols` is set, option leg symbols are validated, returning
+    /// [`Error::InvalidOptionSymbol`] if any can't be parsed as an [`crate::market_data::OptionSymbol`].
     pub fn build(self) -> Result<OptionQuoteQuery, Error> {
+        let legs = self.legs.ok_or_else(|| Error::OptionLegsNotSet)?;
+        if self.validate_option_symbols.unwrap_or(false) {
+            for leg in &legs {
+                validate_leg_symbol(&leg.symbol)?;
+            }
+        }
+
         Ok(OptionQuoteQuery {
-            legs: self.legs.ok_or_else(|| Error::OptionLegsNotSet)?,
+            legs,
             enable_greeks: self.enable_greeks.unwrap_or(true),
             risk_free_rate: self.risk_free_rate,
         })
@@ -0,0 +1,468 @@
+//! Computes the payoff and risk vs reward of an options trade locally, without
+//! a round trip to TradeStation for every what-if like
+//! [`OptionRiskRewardAnalysis::run`] does.
+//!
+//! The analysis is the same [`OptionRiskRewardAnalysis`] TradeStation returns
+//! (max gain and loss in dollars, if either is infinite, and the breakeven points),
+//! so results can be cross-checked. On top of that you get the payoff curve at
+//! expiration, and a profit and loss surface before expiration using the pricing
+//! models in [`crate::market_data::analytics`].
+//!
+//! # Example
+//! ---
+//!
+//! Analyze the same long TLT straddle as the
+//! [`OptionRiskRewardAnalysis::run`] example, but locally.
+//!
+//! ```rust
+//! use tradestation::market_data::{
+//!     payoff::PayoffEngine, OptionRiskRewardAnalysis, OptionTradeAction, OptionsLeg,
+//! };
+//!
+//! let legs = vec![
+//!     OptionsLeg {
+//!         symbol: "TLT 250516C93".into(),
+//!         quantity: 10,
+//!         trade_action: OptionTradeAction::Buy,
+//!     },
+//!     OptionsLeg {
+//!         symbol: "TLT 250516P93".into(),
+//!         quantity: 10,
+//!         trade_action: OptionTradeAction::Buy,
+//!     },
+//! ];
+//!
+//! let analysis = OptionRiskRewardAnalysis::run_locally(4.40, &legs).unwrap();
+//! assert!(analysis.max_gain_is_infinite);
+//! assert_eq!(analysis.adjusted_max_loss, "-4400");
+//! assert_eq!(analysis.breakeven_points, vec!["88.6", "97.4"]);
+//!
+//! // Profit and loss at expiration if TLT closes at $100
+//! let engine = PayoffEngine::new(4.40, &legs).unwrap();
+//! assert!((engine.payoff_at_expiration(100.0) - 2600.0).abs() < 1e-9);
+//! ```
+//!
+//! [`OptionRiskRewardAnalysis`]: crate::market_data::OptionRiskRewardAnalysis
+//! [`OptionRiskRewardAnalysis::run`]: crate::market_data::OptionRiskRewardAnalysis::run
+
+use crate::{
+    accounting::OptionType,
+    market_data::{
+        analytics::{years_until, EuropeanOption, PricingModel},
+        option_symbol::{OptionSymbol, OptionSymbolExpiration},
+        OptionRiskRewardAnalysis, OptionTradeAction, OptionsLeg,
+    },
+    utils::{format_number, now_epoch_seconds, SECONDS_PER_DAY},
+    Error,
+};
+use serde::Serialize;
+
+/// The default amount of shares each option contract is for.
+pub const DEFAULT_CONTRACT_MULTIPLIER: f64 = 100.0;
+
+/// Payoffs are rounded to this precision in the analysis, so float
+/// noise doesn't hide a breakeven at a strike.
+const PRICE_PRECISION: f64 = 1e-6;
+
+#[derive(Clone, Debug, PartialEq)]
+/// What a leg of an options trade is for.
+pub enum PayoffInstrument {
+    /// Shares of the underlying.
+    Underlying,
+
+    /// An option contract on the underlying.
+    Option(OptionSymbol),
+}
+
+#[derive(Clone, Debug)]
+/// A leg of an options trade parsed for payoff analysis.
+pub struct PayoffLeg {
+    /// The symbol of the leg.
+    pub symbol: String,
+
+    /// What the leg is for.
+    pub instrument: PayoffInstrument,
+
+    /// The signed quantity of the leg, positive when buying and negative when selling.
+    ///
+    /// NOTE: This is in contracts for options, and shares for the underlying.
+    pub quantity: i32,
+}
+
+#[derive(Clone, Copy, Debug, Serialize)]
+#[serde(rename_all = "PascalCase")]
+/// The profit and loss of a trade at a single underlying price.
+pub struct PayoffPoint {
+    /// The price of the underlying.
+    pub underlying_price: f64,
+
+    /// The profit and loss in dollars.
+    pub profit_and_loss: f64,
+}
+
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "PascalCase")]
+/// The profit and loss of a trade by days forward (rows) and underlying price (columns).
+pub struct PayoffSurface {
+    /// The prices of the underlying (columns).
+    pub underlying_prices: Vec<f64>,
+
+    /// The amount of days from now (rows).
+    pub days_forward: Vec<f64>,
+
+    /// The profit and loss in dollars at each amount of days forward and underlying price.
+    pub profit_and_loss: Vec<Vec<f64>>,
+}
+
+#[derive(Clone, Debug)]
+/// Computes the payoff and risk vs reward of an options trade locally.
+///
+/// NOTE: Like [`OptionRiskRewardAnalysis::run`], the spread price is the net
+/// price of one unit of the spread (negative for a net credit), and all the
+/// legs must be the same underlying and expiration.
+pub struct PayoffEngine {
+    legs: Vec<PayoffLeg>,
+    price: f64,
+    multiplier: f64,
+    model: PricingModel,
+    risk_free_rate: f64,
+}
+impl PayoffEngine {
+    /// Create a `PayoffEngine` for an options trade given a spread price and option legs.
+    ///
+    /// NOTE: Legs can also be shares of the underlying, by using the underlying
+    /// symbol (`"TLT"`) with a quantity in shares.
+    ///
+    /// # Errors
+    ///
+    /// - [`Error::OptionLegsNotSet`] if there are no legs.
+    /// - [`Error::QuantityNotSet`] if a leg has a quantity of 0.
+    /// - [`Error::InvalidOptionSymbol`] if a leg's symbol can't be parsed.
+    /// - [`Error::MismatchedOptionLegs`] if the legs aren't the same underlying and expiration.
+    pub fn new(price: f64, legs: &[OptionsLeg]) -> Result<Self, Error> {
+        if legs.is_empty() {
+            return Err(Error::OptionLegsNotSet);
+        }
+
+        let mut underlying: Option<String> = None;
+        let mut expiration: Option<OptionSymbolExpiration> = None;
+        let mut parsed_legs = Vec::with_capacity(legs.len());
+        for leg in legs {
+            if leg.quantity == 0 {
+                return Err(Error::QuantityNotSet);
+            }
+
+            let (leg_underlying, instrument) = if leg.symbol.trim().contains(' ') {
+                let option = OptionSymbol::parse(&leg.symbol)?;
+                (option.underlying(), PayoffInstrument::Option(option))
+            } else {
+                (
+                    leg.symbol.trim().to_uppercase(),
+                    PayoffInstrument::Underlying,
+                )
+            };
+            if underlying.get_or_insert_with(|| leg_underlying.clone()) != &leg_underlying {
+                return Err(Error::MismatchedOptionLegs(format!(
+                    "`{}` isn't on the same underlying as the other legs",
+                    leg.symbol
+                )));
+            }
+            if let PayoffInstrument::Option(option) = &instrument {
+                if *expiration.get_or_insert(option.expiration) != option.expiration {
+                    return Err(Error::MismatchedOptionLegs(format!(
+                        "`{}` doesn't expire with the other legs",
+                        leg.symbol
+                    )));
+                }
+            }
+
+            let quantity = leg.quantity.abs();
+            parsed_legs.push(PayoffLeg {
+                symbol: leg.symbol.clone(),
+                instrument,
+                quantity: match leg.trade_action {
+                    OptionTradeAction::Buy => quantity,
+                    OptionTradeAction::Sell => -quantity,
+                },
+            });
+        }
+
+        Ok(Self {
+            legs: parsed_legs,
+            price,
+            multiplier: DEFAULT_CONTRACT_MULTIPLIER,
+            model: PricingModel::BlackScholes,
+            risk_free_rate: 0.0,
+        })
+    }
+
+    /// Set the amount of shares each option contract is for, defaults to 100.
+    pub fn multiplier(mut self, multiplier: f64) -> Self {
+        self.multiplier = multiplier;
+        self
+    }
+
+    /// Set the pricing model used for the profit and loss before expiration.
+    pub fn model(mut self, model: PricingModel) -> Self {
+        self.model = model;
+        self
+    }
+
+    /// Set the risk free rate used for the profit and loss before expiration.
+    pub fn risk_free_rate(mut self, risk_free_rate: f64) -> Self {
+        self.risk_free_rate = risk_free_rate;
+        self
+    }
+
+    /// Get the parsed legs of the trade.
+    pub fn legs(&self) -> &[PayoffLeg] {
+        &self.legs
+    }
+
+    /// Get the amount of spread units being traded, the greatest common
+    /// divisor of the option leg quantities (`10` for a 10 lot straddle).
+    pub fn spread_quantity(&self) -> i32 {
+        let quantities = self
+            .legs
+            .iter()
+            .filter(|leg| matches!(leg.instrument, PayoffInstrument::Option(_)));
+
+        quantities
+            .fold(0, |divisor, leg| gcd(divisor, leg.quantity.abs()))
+            .max(1)
+    }
+
+    /// Get the net cost of the trade in dollars, negative for a net credit.
+    pub fn cost(&self) -> f64 {
+        self.price * self.multiplier * self.spread_quantity() as f64
+    }
+
+    /// Get the profit and loss in dollars of the trade at expiration
+    /// given the price of the underlying at expiration.
+    pub fn payoff_at_expiration(&self, underlying_price: f64) -> f64 {
+        let value: f64 = self
+            .legs
+            .iter()
+            .map(|leg| {
+                let value = match &leg.instrument {
+                    PayoffInstrument::Underlying => underlying_price,
+                    PayoffInstrument::Option(option) => {
+                        self.multiplier * intrinsic_value(option, underlying_price)
+                    }
+                };
+
+                value * leg.quantity as f64
+            })
+            .sum();
+
+        value - self.cost()
+    }
+
+    /// Get the payoff curve at expiration from one underlying price to
+    /// another, evaluated at `steps + 1` evenly spaced prices.
+    pub fn payoff_curve(&self, from: f64, to: f64, steps: usize) -> Vec<PayoffPoint> {
+        let steps = steps.max(1);
+        let step = (to - from) / steps as f64;
+
+        (0..=steps)
+            .map(|i| {
+                let underlying_price = from + step * i as f64;
+                PayoffPoint {
+                    underlying_price,
+                    profit_and_loss: self.payoff_at_expiration(underlying_price),
+                }
+            })
+            .collect()
+    }
+
+    /// Analyze the max gain, max loss, and breakeven points of the trade at
+    /// expiration, the same as TradeStation's risk vs reward analysis.
+    ///
+    /// NOTE: Like TradeStation, the max gain or loss is `"0"` when it's infinite.
+    pub fn analyze(&self) -> OptionRiskRewardAnalysis {
+        let mut prices: Vec<f64> = vec![0.0];
+        prices.extend(self.legs.iter().filter_map(|leg| match &leg.instrument {
+            PayoffInstrument::Option(option) => Some(option.strike),
+            PayoffInstrument::Underlying => None,
+        }));
+        prices.sort_by(f64::total_cmp);
+        prices.dedup();
+
+        let payoffs: Vec<f64> = prices
+            .iter()
+            .map(|price| round_price(self.payoff_at_expiration(*price)))
+            .collect();
+
+        // The payoff is linear past the highest strike, so it's slope decides
+        // if the gain or loss is infinite as the underlying keeps rising.
+        let slope = self.slope_above_strikes();
+        let max_gain_is_infinite = slope > PRICE_PRECISION;
+        let max_loss_is_infinite = slope < -PRICE_PRECISION;
+
+        let max_gain = payoffs.iter().copied().fold(f64::MIN, f64::max);
+        let max_loss = payoffs.iter().copied().fold(f64::MAX, f64::min);
+
+        let mut breakevens = Vec::new();
+        for (i, (price, payoff)) in prices.iter().zip(&payoffs).enumerate() {
+            if *payoff == 0.0 {
+                breakevens.push(*price);
+            }
+
+            match (prices.get(i + 1), payoffs.get(i + 1)) {
+                (Some(next_price), Some(next_payoff)) => {
+                    if payoff * next_payoff < 0.0 {
+                        breakevens
+                            .push(price + (next_price - price) * payoff / (payoff - next_payoff));
+                    }
+                }
+                _ => {
+                    if *payoff != 0.0 && slope != 0.0 && payoff.signum() != slope.signum() {
+                        breakevens.push(price - payoff / slope);
+                    }
+                }
+            }
+        }
+        breakevens.dedup_by(|a, b| (*a - *b).abs() < PRICE_PRECISION);
+
+        OptionRiskRewardAnalysis {
+            max_gain_is_infinite,
+            adjusted_max_gain: format_number(if max_gain_is_infinite { 0.0 } else { max_gain }),
+            max_loss_is_infinite,
+            adjusted_max_loss: format_number(if max_loss_is_infinite { 0.0 } else { max_loss }),
+            breakeven_points: breakevens.into_iter().map(format_number).collect(),
+        }
+    }
+
+    /// Get the profit and loss in dollars of the trade before expiration, given
+    /// the price of the underlying, how many days from now, and the volatility.
+    ///
+    /// NOTE: Legs at or past their expiration are valued at their intrinsic value, as are
+    /// futures options since their symbol only has the contract month of the future.
+    pub fn profit_and_loss(
+        &self,
+        underlying_price: f64,
+        days_forward: f64,
+        volatility: f64,
+    ) -> f64 {
+        let as_of = now_epoch_seconds() + (days_forward * SECONDS_PER_DAY as f64) as i64;
+
+        let value: f64 = self
+            .legs
+            .iter()
+            .map(|leg| {
+                let value = match &leg.instrument {
+                    PayoffInstrument::Underlying => underlying_price,
+                    PayoffInstrument::Option(option) => {
+                        let time_to_expiration = option
+                            .expiration_date()
+                            .and_then(|date| years_until(&date, as_of).ok())
+                            .unwrap_or_default();
+                        let option = EuropeanOption {
+                            model: self.model,
+                            option_type: option.option_type,
+                            underlying_price,
+                            strike: option.strike,
+                            time_to_expiration,
+                            risk_free_rate: self.risk_free_rate,
+                            dividend_yield: 0.0,
+                        };
+
+                        self.multiplier * option.price(volatility)
+                    }
+                };
+
+                value * leg.quantity as f64
+            })
+            .sum();
+
+        value - self.cost()
+    }
+
+    /// Get the profit and loss surface of the trade before expiration at
+    /// every combination of underlying price and days forward.
+    pub fn profit_and_loss_surface(
+        &self,
+        underlying_prices: &[f64],
+        days_forward: &[f64],
+        volatility: f64,
+    ) -> PayoffSurface {
+        PayoffSurface {
+            underlying_prices: underlying_prices.to_vec(),
+            days_forward: days_forward.to_vec(),
+            profit_and_loss: days_forward
+                .iter()
+                .map(|days| {
+                    underlying_prices
+                        .iter()
+                        .map(|price| self.profit_and_loss(*price, *days, volatility))
+                        .collect()
+                })
+                .collect(),
+        }
+    }
+
+    /// The change in profit and loss for every dollar the
+    /// underlying rises above the highest strike.
+    fn slope_above_strikes(&self) -> f64 {
+        self.legs
+            .iter()
+            .map(|leg| match &leg.instrument {
+                PayoffInstrument::Underlying => leg.quantity as f64,
+                PayoffInstrument::Option(option) => match option.option_type {
+                    OptionType::Call => leg.quantity as f64 * self.multiplier,
+                    OptionType::Put => 0.0,
+                },
+            })
+            .sum()
+    }
+}
+
+impl PayoffSurface {
+    /// Export the surface as CSV, with the underlying prices as the header
+    /// row and the days forward as the first column of each row.
+    pub fn to_csv(&self) -> String {
+        let mut csv = String::from("days_forward");
+        for price in &self.underlying_prices {
+            csv.push_str(&format!(",{price}"));
+        }
+        csv.push('\n');
+
+        for (days, row) in self.days_forward.iter().zip(&self.profit_and_loss) {
+            csv.push_str(&days.to_string());
+            for profit_and_loss in row {
+                csv.push_str(&format!(",{profit_and_loss}"));
+            }
+            csv.push('\n');
+        }
+
+        csv
+    }
+}
+
+/// The value of an option at expiration.
+fn intrinsic_value(option: &OptionSymbol, underlying_price: f64) -> f64 {
+    match option.option_type {
+        OptionType::Call => (underlying_price - option.strike).max(0.0),
+        OptionType::Put => (option.strike - underlying_price).max(0.0),
+    }
+}
+
+/// The greatest common divisor of two numbers.
+fn gcd(a: i32, b: i32) -> i32 {
+    if b == 0 {
+        a
+    } else {
+        gcd(b, a % b)
+    }
+}
+
+/// Round a price to remove float noise.
+fn round_price(price: f64) -> f64 {
+    let rounded = (price / PRICE_PRECISION).round() * PRICE_PRECISION;
+    if rounded == 0.0 {
+        // Avoid `-0`
+        0.0
+    } else {
+        rounded
+    }
+}
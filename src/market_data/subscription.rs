@@ -0,0 +1,512 @@
+use crate::{
+    market_data::{Quote, QuoteStreamUpdate},
+    responses::market_data::StreamQuotesResp,
+    Client, Error,
+};
+use futures::StreamExt;
+use std::{
+    collections::{BTreeSet, HashMap, HashSet},
+    sync::{Arc, Mutex, MutexGuard, Weak},
+    time::Duration,
+};
+use tokio::{
+    runtime::Handle,
+    sync::broadcast::{self, error::RecvError},
+    task::JoinHandle,
+};
+use tracing::{debug, warn};
+
+/// How many quote updates a subscription buffers by default.
+const DEFAULT_SUBSCRIPTION_BUFFER: usize = 1024;
+
+/// The max amount of symbols TradeStation allows in a single quote stream.
+pub const MAX_SYMBOLS_PER_STREAM: usize = 100;
+
+/// The max amount of concurrent streams TradeStation allows.
+pub const MAX_CONCURRENT_STREAMS: usize = 10;
+
+/// How long a shard waits before reconnecting after its stream ended or failed.
+const RECONNECT_DELAY: Duration = Duration::from_secs(1);
+
+#[derive(Clone, Debug)]
+/// Multiplexes dynamic quote subscriptions over as few quote streams as possible.
+///
+/// Symbols are sharded across underlying `marketdata/stream/quotes` connections
+/// of at most [`MAX_SYMBOLS_PER_STREAM`] symbols each. Subscribing to, or
+/// unsubscribing from, a symbol only reopens the stream (shard) that symbol
+/// lives in, every other shard keeps streaming untouched.
+///
+/// Each [`QuoteSubscription`] gets its own channel of [`QuoteStreamUpdate`]'s
+/// for only the symbols it subscribed to, so many tasks can share the same
+/// manager (it's cheap to clone) without opening their own streams.
+///
+/// NOTE: Each subscription buffers up to 1024 updates,
+/// a subscription falling further behind skips it's oldest updates.
+///
+/// <div class="warning">WARNING: Every shard counts towards the max of 10 concurrent streams allowed.</div>
+///
+/// NOTE: Must be created from within a tokio runtime.
+///
+/// # Example
+/// ---
+///
+/// Stream quotes for a couple symbols, then later on add another symbol
+/// without interrupting the other subscribers.
+///
+/// ```rust,no_run
+/// # use tradestation::{Client, Error, market_data::QuoteSubscriptionManager};
+/// # async fn example(client: &Client) -> Result<(), Error> {
+/// let manager = QuoteSubscriptionManager::new(client);
+///
+/// let mut subscription = manager.subscribe(vec!["SPY", "QQQ"])?;
+/// while let Some(update) = subscription.recv().await {
+///     println!("{}: {:?}", update.symbol, update.last);
+///
+///     if update.symbol == "SPY" {
+///         subscription.add(vec!["IWM"])?;
+///     }
+/// }
+/// # Ok(()) }
+/// ```
+pub struct QuoteSubscriptionManager {
+    inner: Arc<Inner>,
+}
+impl QuoteSubscriptionManager {
+    /// Create a new `QuoteSubscriptionManager` which can use up to
+    /// [`MAX_CONCURRENT_STREAMS`] streams.
+    ///
+    /// NOTE: Must be called from within a tokio runtime.
+    pub fn new(client: &Client) -> Self {
+        Self::with_max_streams(client, MAX_CONCURRENT_STREAMS)
+    }
+
+    /// Create a new `QuoteSubscriptionManager` limited to `max_streams` streams.
+    ///
+    /// NOTE: Useful to leave room for other streams (orders, positions, bars, etc)
+    /// under TradeStation's concurrent stream limit.
+    ///
+    /// NOTE: Must be called from within a tokio runtime.
+    pub fn with_max_streams(client: &Client, max_streams: usize) -> Self {
+        Self {
+            inner: Arc::new(Inner {
+                client: client.clone(),
+                runtime: Handle::current(),
+                max_streams,
+                state: Mutex::new(State::default()),
+            }),
+        }
+    }
+
+    /// Subscribe to quote updates for the given symbols.
+    ///
+    /// NOTE: Dropping the returned [`QuoteSubscription`] unsubscribes it from all of its symbols.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::StreamLimitReached`] if the symbols can't fit in the
+    /// current shards without exceeding the max amount of streams.
+    pub fn subscribe(&self, symbols: Vec<&str>) -> Result<QuoteSubscription, Error> {
+        self.subscribe_with_buffer(symbols, DEFAULT_SUBSCRIPTION_BUFFER)
+    }
+
+    /// Subscribe to quote updates for the given symbols, buffering up to
+    /// `buffer` updates before skipping the oldest ones.
+    ///
+    /// NOTE: A buffer of 0 is treated as a buffer of 1.
+    ///
+    /// NOTE: Dropping the returned [`QuoteSubscription`] unsubscribes it from all of its symbols.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::StreamLimitReached`] if the symbols can't fit in the
+    /// current shards without exceeding the max amount of streams.
+    pub fn subscribe_with_buffer(
+        &self,
+        symbols: Vec<&str>,
+        buffer: usize,
+    ) -> Result<QuoteSubscription, Error> {
+        let (sender, receiver) = broadcast::channel(buffer.max(1));
+
+        let id = {
+            let mut state = self.inner.state();
+            let id = state.next_subscriber_id;
+            state.next_subscriber_id += 1;
+            state.subscribers.insert(
+                id,
+                Subscriber {
+                    sender,
+                    symbols: BTreeSet::new(),
+                },
+            );
+
+            id
+        };
+
+        let subscription = QuoteSubscription {
+            id,
+            manager: self.clone(),
+            receiver,
+        };
+        subscription.add(symbols)?;
+
+        Ok(subscription)
+    }
+
+    /// Get all the symbols currently being streamed across every shard.
+    pub fn symbols(&self) -> Vec<String> {
+        let mut symbols: Vec<String> = self.inner.state().symbol_shards.keys().cloned().collect();
+        symbols.sort();
+
+        symbols
+    }
+
+    /// Get the amount of underlying streams (shards) currently open.
+    pub fn stream_count(&self) -> usize {
+        self.inner.state().shards.len()
+    }
+
+    /// Add symbols to a subscriber, placing any symbols not already
+    /// streamed into shards with room and reopening only those shards.
+    fn add_symbols(&self, subscriber_id: u64, symbols: Vec<&str>) -> Result<(), Error> {
+        let mut state = self.inner.state();
+
+        let Some(subscriber) = state.subscribers.get(&subscriber_id) else {
+            return Ok(());
+        };
+        let new_symbols: BTreeSet<String> = symbols
+            .into_iter()
+            .map(|symbol| symbol.trim().to_uppercase())
+            .filter(|symbol| !symbol.is_empty() && !subscriber.symbols.contains(symbol))
+            .collect();
+
+        let unstreamed: Vec<String> = new_symbols
+            .iter()
+            .filter(|symbol| !state.symbol_shards.contains_key(*symbol))
+            .cloned()
+            .collect();
+
+        let open_slots: usize = state
+            .shards
+            .values()
+            .map(|shard| MAX_SYMBOLS_PER_STREAM - shard.symbols.len())
+            .sum::<usize>()
+            + self.inner.max_streams.saturating_sub(state.shards.len()) * MAX_SYMBOLS_PER_STREAM;
+        if unstreamed.len() > open_slots {
+            return Err(Error::StreamLimitReached(self.inner.max_streams));
+        }
+
+        let mut affected_shards = BTreeSet::new();
+        for symbol in unstreamed {
+            let shard_id = match state
+                .shards
+                .iter()
+                .filter(|(_, shard)| shard.symbols.len() < MAX_SYMBOLS_PER_STREAM)
+                .map(|(id, _)| *id)
+                .min()
+            {
+                Some(id) => id,
+                None => {
+                    let id = state.next_shard_id;
+                    state.next_shard_id += 1;
+                    state.shards.insert(
+                        id,
+                        Shard {
+                            symbols: BTreeSet::new(),
+                            task: None,
+                        },
+                    );
+
+                    id
+                }
+            };
+
+            if let Some(shard) = state.shards.get_mut(&shard_id) {
+                shard.symbols.insert(symbol.clone());
+            }
+            state.symbol_shards.insert(symbol, shard_id);
+            affected_shards.insert(shard_id);
+        }
+
+        for symbol in new_symbols {
+            state
+                .symbol_subscribers
+                .entry(symbol.clone())
+                .or_default()
+                .insert(subscriber_id);
+
+            if let Some(subscriber) = state.subscribers.get_mut(&subscriber_id) {
+                subscriber.symbols.insert(symbol);
+            }
+        }
+
+        for shard_id in affected_shards {
+            self.reopen_shard(&mut state, shard_id);
+        }
+
+        Ok(())
+    }
+
+    /// Remove symbols from a subscriber, taking any symbols no longer
+    /// wanted by anyone out of their shards and reopening only those shards.
+    fn remove_symbols(&self, state: &mut State, subscriber_id: u64, symbols: Vec<String>) {
+        let mut affected_shards = BTreeSet::new();
+
+        for symbol in symbols {
+            if let Some(subscriber) = state.subscribers.get_mut(&subscriber_id) {
+                subscriber.symbols.remove(&symbol);
+            }
+
+            let Some(subscriber_ids) = state.symbol_subscribers.get_mut(&symbol) else {
+                continue;
+            };
+            subscriber_ids.remove(&subscriber_id);
+            if !subscriber_ids.is_empty() {
+                continue;
+            }
+
+            state.symbol_subscribers.remove(&symbol);
+            if let Some(shard_id) = state.symbol_shards.remove(&symbol) {
+                if let Some(shard) = state.shards.get_mut(&shard_id) {
+                    shard.symbols.remove(&symbol);
+                }
+                affected_shards.insert(shard_id);
+            }
+        }
+
+        for shard_id in affected_shards {
+            self.reopen_shard(state, shard_id);
+        }
+    }
+
+    /// Restart the stream of a shard with its current symbols,
+    /// or close it completely if it no longer has any symbols.
+    fn reopen_shard(&self, state: &mut State, shard_id: u64) {
+        let Some(shard) = state.shards.get_mut(&shard_id) else {
+            return;
+        };
+
+        if let Some(task) = shard.task.take() {
+            task.abort();
+        }
+
+        if shard.symbols.is_empty() {
+            debug!(
+                target: "tradestation::stream",
+                shard_id,
+                "closing empty quote subscription shard"
+            );
+            state.shards.remove(&shard_id);
+            return;
+        }
+
+        let symbols: Vec<String> = shard.symbols.iter().cloned().collect();
+        debug!(
+            target: "tradestation::stream",
+            shard_id,
+            symbol_count = symbols.len(),
+            "opening quote subscription shard"
+        );
+
+        shard.task = Some(self.inner.runtime.spawn(run_shard(
+            self.inner.client.clone(),
+            Arc::downgrade(&self.inner),
+            shard_id,
+            symbols,
+        )));
+    }
+}
+
+#[derive(Debug)]
+/// A subscription to quote updates from a [`QuoteSubscriptionManager`].
+///
+/// NOTE: Dropping this unsubscribes from all of its symbols, closing
+/// any shards that are left without symbols.
+pub struct QuoteSubscription {
+    id: u64,
+    manager: QuoteSubscriptionManager,
+    receiver: broadcast::Receiver<QuoteStreamUpdate>,
+}
+impl QuoteSubscription {
+    /// Receive the next [`QuoteStreamUpdate`] for any of the subscribed symbols.
+    ///
+    /// NOTE: The subscription keeps it's [`QuoteSubscriptionManager`] alive, so this
+    /// waits for the next update for as long as the subscription exists, and never
+    /// returns `None` while it's subscribed.
+    ///
+    /// NOTE: When the subscription falls further behind than it's buffer,
+    /// the oldest updates are skipped.
+    pub async fn recv(&mut self) -> Option<QuoteStreamUpdate> {
+        loop {
+            match self.receiver.recv().await {
+                Ok(update) => return Some(update),
+                Err(RecvError::Lagged(missed)) => {
+                    warn!(
+                        target: "tradestation::stream",
+                        subscriber_id = self.id,
+                        missed,
+                        "quote subscriber lagged, dropped oldest updates"
+                    );
+                }
+                Err(RecvError::Closed) => return None,
+            }
+        }
+    }
+
+    /// Subscribe to more symbols.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::StreamLimitReached`] if the symbols can't fit in the
+    /// current shards without exceeding the max amount of streams.
+    pub fn add(&self, symbols: Vec<&str>) -> Result<(), Error> {
+        self.manager.add_symbols(self.id, symbols)
+    }
+
+    /// Unsubscribe from some symbols.
+    pub fn remove(&self, symbols: Vec<&str>) {
+        let symbols = symbols
+            .into_iter()
+            .map(|symbol| symbol.trim().to_uppercase())
+            .collect();
+
+        let mut state = self.manager.inner.state();
+        self.manager.remove_symbols(&mut state, self.id, symbols);
+    }
+
+    /// Get the symbols this subscription is subscribed to.
+    pub fn symbols(&self) -> Vec<String> {
+        self.manager
+            .inner
+            .state()
+            .subscribers
+            .get(&self.id)
+            .map(|subscriber| subscriber.symbols.iter().cloned().collect())
+            .unwrap_or_default()
+    }
+}
+impl Drop for QuoteSubscription {
+    fn drop(&mut self) {
+        let mut state = self.manager.inner.state();
+
+        let symbols = state
+            .subscribers
+            .get(&self.id)
+            .map(|subscriber| subscriber.symbols.iter().cloned().collect())
+            .unwrap_or_default();
+        self.manager.remove_symbols(&mut state, self.id, symbols);
+
+        state.subscribers.remove(&self.id);
+    }
+}
+
+#[derive(Debug)]
+/// State shared between the manager, its subscriptions, and its shards.
+struct Inner {
+    client: Client,
+    runtime: Handle,
+    max_streams: usize,
+    state: Mutex<State>,
+}
+impl Inner {
+    /// Lock the state, recovering it if a holder of the lock panicked.
+    fn state(&self) -> MutexGuard<'_, State> {
+        self.state
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    /// Send a quote update to every subscriber of it's symbol.
+    fn fan_out(&self, update: QuoteStreamUpdate) {
+        let state = self.state();
+
+        if let Some(subscriber_ids) = state.symbol_subscribers.get(&update.symbol) {
+            for subscriber_id in subscriber_ids {
+                if let Some(subscriber) = state.subscribers.get(subscriber_id) {
+                    // NOTE: A send only fails when the subscription is being dropped,
+                    // and never blocks, a full buffer overwrites it's oldest update.
+                    let _ = subscriber.sender.send(update.clone());
+                }
+            }
+        }
+    }
+}
+impl Drop for Inner {
+    fn drop(&mut self) {
+        let state = self.state();
+
+        for shard in state.shards.values() {
+            if let Some(task) = &shard.task {
+                task.abort();
+            }
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+/// The bookkeeping of which subscriber wants which symbols, and which shard streams them.
+struct State {
+    next_subscriber_id: u64,
+    next_shard_id: u64,
+    subscribers: HashMap<u64, Subscriber>,
+    symbol_subscribers: HashMap<String, HashSet<u64>>,
+    symbol_shards: HashMap<String, u64>,
+    shards: HashMap<u64, Shard>,
+}
+
+#[derive(Debug)]
+/// A single subscriber's channel and symbols.
+struct Subscriber {
+    sender: broadcast::Sender<QuoteStreamUpdate>,
+    symbols: BTreeSet<String>,
+}
+
+#[derive(Debug)]
+/// A single underlying quote stream.
+struct Shard {
+    symbols: BTreeSet<String>,
+    task: Option<JoinHandle<()>>,
+}
+
+/// Stream quotes for a shard's symbols, fanning each update out
+/// to it's subscribers and reconnecting whenever the stream ends.
+async fn run_shard(client: Client, inner: Weak<Inner>, shard_id: u64, symbols: Vec<String>) {
+    let symbols: Vec<&str> = symbols.iter().map(String::as_str).collect();
+
+    loop {
+        let stream = Quote::stream(&client, symbols.clone());
+        tokio::pin!(stream);
+
+        while let Some(stream_event) = stream.next().await {
+            match stream_event {
+                Ok(StreamQuotesResp::Quote(update)) => match inner.upgrade() {
+                    Some(inner) => inner.fan_out(*update),
+                    None => return,
+                },
+                Ok(StreamQuotesResp::Error(err)) => {
+                    warn!(
+                        target: "tradestation::stream",
+                        shard_id,
+                        error = err.error,
+                        message = err.message,
+                        "quote subscription shard received an error event"
+                    );
+                }
+                Ok(_) => {}
+                Err(e) => {
+                    warn!(
+                        target: "tradestation::stream",
+                        shard_id,
+                        error = %e,
+                        "quote subscription shard stream failed"
+                    );
+                    break;
+                }
+            }
+        }
+
+        if inner.strong_count() == 0 {
+            return;
+        }
+        tokio::time::sleep(RECONNECT_DELAY).await;
+    }
+}
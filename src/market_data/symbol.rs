@@ -4,10 +4,17 @@ use crate::{
         market_data::{GetSymbolDetailsResp, GetSymbolDetailsRespRaw},
         ApiResponse,
     },
+    utils::{format_number, parse_number},
     Client, Error,
 };
 use serde::{Deserialize, Serialize};
 
+/// How close a price must be to a tick to be on it, which absorbs float noise.
+const TICK_TOLERANCE: f64 = 1e-9;
+
+/// Rounded prices are scaled by this to remove float noise.
+const PRICE_SCALE: f64 = 1e9;
+
 #[derive(Clone, Debug, Deserialize, Serialize)]
 #[serde(rename_all = "PascalCase")]
 /// Detailed Information on a sepecifc symbol
@@ -172,6 +179,231 @@ pub struct PriceFormat {
     /// The symbol's point value.
     pub point_value: String,
 }
+impl PriceFormat {
+    /// Get the tick size (minimum price movement) at a price.
+    ///
+    /// NOTE: For [`IncrementStyle::Schedule`] the tick size depends on the
+    /// price, for example options with a tick of `0.01` under `3.00` and
+    /// `0.05` from `3.00` and up.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::InvalidPriceFormat`] if the increment is missing or can't be parsed.
+    pub fn tick_size(&self, price: f64) -> Result<f64, Error> {
+        self.tick_tier(price.abs()).map(|(_, tick_size)| tick_size)
+    }
+
+    /// Round a price onto a valid tick, either up, down, or to the nearest tick.
+    ///
+    /// # Example
+    /// ---
+    ///
+    /// Round a limit price for an option with scheduled ticks.
+    ///
+    /// ```rust
+    /// use tradestation::market_data::{
+    ///     Format, IncrementSchedule, IncrementStyle, PriceFormat, RoundingMode,
+    /// };
+    ///
+    /// // The price format of an option, from `Client::get_symbol_details`
+    /// let price_format = PriceFormat {
+    ///     format: Format::Decimal,
+    ///     decimals: Some("2".into()),
+    ///     fraction: None,
+    ///     sub_fraction: None,
+    ///     increment_style: IncrementStyle::Schedule,
+    ///     increment: None,
+    ///     increment_schedule: Some(vec![
+    ///         IncrementSchedule { increment: "0.01".into(), starts_at: "0".into() },
+    ///         IncrementSchedule { increment: "0.05".into(), starts_at: "3".into() },
+    ///     ]),
+    ///     point_value: "100".into(),
+    /// };
+    ///
+    /// // Under $3.00 the tick is $0.01, from $3.00 and up it's $0.05
+    /// assert_eq!(price_format.round_price(2.333, RoundingMode::Nearest).unwrap(), 2.33);
+    /// assert_eq!(price_format.round_price(3.12, RoundingMode::Up).unwrap(), 3.15);
+    /// assert_eq!(price_format.round_price(3.12, RoundingMode::Down).unwrap(), 3.10);
+    /// assert!(price_format.validate_price("3.12").is_err());
+    /// assert_eq!(price_format.format_price(3.1).unwrap(), "3.10");
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::InvalidPriceFormat`] if the increment is missing or can't be parsed.
+    pub fn round_price(&self, price: f64, mode: RoundingMode) -> Result<f64, Error> {
+        // Round the size of negative prices (like credit spreads) the other way
+        let mode = match (price < 0.0, mode) {
+            (true, RoundingMode::Up) => RoundingMode::Down,
+            (true, RoundingMode::Down) => RoundingMode::Up,
+            (_, mode) => mode,
+        };
+        let size = price.abs();
+        let (starts_at, tick_size) = self.tick_tier(size)?;
+
+        let ticks = (size - starts_at) / tick_size;
+        let nearest_ticks = ticks.round();
+        let ticks = if (ticks - nearest_ticks).abs() < TICK_TOLERANCE {
+            nearest_ticks
+        } else {
+            match mode {
+                RoundingMode::Up => ticks.ceil(),
+                RoundingMode::Down => ticks.floor(),
+                RoundingMode::Nearest => nearest_ticks,
+            }
+        };
+
+        // Remove float noise like `3.1500000000000004`
+        let rounded = ((starts_at + ticks * tick_size) * PRICE_SCALE).round() / PRICE_SCALE;
+        Ok(if price < 0.0 { -rounded } else { rounded })
+    }
+
+    /// Check if a price is on a valid tick.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::InvalidPriceFormat`] if the increment is missing or can't be parsed.
+    pub fn is_valid_price(&self, price: f64) -> Result<bool, Error> {
+        let rounded = self.round_price(price, RoundingMode::Nearest)?;
+
+        Ok((rounded - price).abs() < TICK_TOLERANCE)
+    }
+
+    /// Validate a price is on a valid tick.
+    ///
+    /// # Errors
+    ///
+    /// - [`Error::InvalidNumber`] if the price can't be parsed.
+    /// - [`Error::PriceNotOnTick`] if the price isn't on a valid tick.
+    /// - [`Error::InvalidPriceFormat`] if the increment is missing or can't be parsed.
+    pub fn validate_price(&self, price: &str) -> Result<(), Error> {
+        let value = parse_number(price)?;
+
+        if self.is_valid_price(value)? {
+            Ok(())
+        } else {
+            Err(Error::PriceNotOnTick(format!(
+                "{price} (tick size: {})",
+                format_number(self.tick_size(value)?)
+            )))
+        }
+    }
+
+    /// Format a price for display in the symbol's format.
+    ///
+    /// - [`Format::Decimal`] like `"123.20"`
+    /// - [`Format::Fraction`] like `"534 4/8"`
+    /// - [`Format::SubFraction`] like `"125'29.7"`
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::InvalidPriceFormat`] if the format's decimals or fractions can't be parsed.
+    pub fn format_price(&self, price: f64) -> Result<String, Error> {
+        let sign = if price < 0.0 { "-" } else { "" };
+        let size = price.abs();
+
+        match self.format {
+            Format::Decimal => {
+                let decimals = self.format_part(&self.decimals, "Decimals")?.unwrap_or(2);
+                Ok(format!("{price:.decimals$}", decimals = decimals as usize))
+            }
+            Format::Fraction => {
+                let fraction = self.format_part(&self.fraction, "Fraction")?.unwrap_or(1);
+                let whole = size.trunc();
+                let numerator = ((size - whole) * fraction as f64).round() as u32;
+
+                Ok(match numerator {
+                    0 => format!("{sign}{whole}"),
+                    numerator if numerator >= fraction => format!("{sign}{}", whole + 1.0),
+                    numerator => format!("{sign}{whole} {numerator}/{fraction}"),
+                })
+            }
+            Format::SubFraction => {
+                let fraction = self.format_part(&self.fraction, "Fraction")?.unwrap_or(1);
+                let sub_fraction = self
+                    .format_part(&self.sub_fraction, "SubFraction")?
+                    .unwrap_or(1);
+
+                // Round onto the smallest sub fraction before splitting it apart
+                let smallest = (fraction * sub_fraction.max(1)) as f64;
+                let size = (size * smallest).round() / smallest;
+                let whole = size.trunc();
+                let fractions = (size - whole) * fraction as f64;
+                let whole_fractions = (fractions + TICK_TOLERANCE).trunc();
+                let sub_digit = ((fractions - whole_fractions) * 10.0 + TICK_TOLERANCE).trunc();
+
+                Ok(format!(
+                    "{sign}{whole}'{:02}.{}",
+                    whole_fractions as u32, sub_digit as u32
+                ))
+            }
+        }
+    }
+
+    /// Get the start of the increment tier, and it's tick size, for the size of a price.
+    fn tick_tier(&self, size: f64) -> Result<(f64, f64), Error> {
+        let invalid = |reason: &str| Error::InvalidPriceFormat(reason.into());
+
+        let (starts_at, increment) = match self.increment_style {
+            IncrementStyle::Simple => (
+                0.0,
+                self.increment
+                    .as_deref()
+                    .ok_or_else(|| invalid("missing increment"))?,
+            ),
+            IncrementStyle::Schedule => {
+                let mut tiers = self
+                    .increment_schedule
+                    .iter()
+                    .flatten()
+                    .map(|tier| Ok((parse_number(&tier.starts_at)?, tier.increment.as_str())))
+                    .collect::<Result<Vec<_>, Error>>()?;
+                tiers.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+                tiers
+                    .iter()
+                    .rev()
+                    .find(|(starts_at, _)| *starts_at <= size + TICK_TOLERANCE)
+                    .copied()
+                    .or_else(|| tiers.first().map(|(_, increment)| (0.0, *increment)))
+                    .ok_or_else(|| invalid("missing increment schedule"))?
+            }
+        };
+
+        let tick_size = parse_number(increment)?;
+        if tick_size <= 0.0 {
+            return Err(invalid(&format!("increment of {increment}")));
+        }
+
+        Ok((starts_at, tick_size))
+    }
+
+    /// Parse a part of the format (decimals, or fractions).
+    fn format_part(&self, part: &Option<String>, name: &str) -> Result<Option<u32>, Error> {
+        part.as_deref()
+            .map(|value| {
+                value
+                    .trim()
+                    .parse::<u32>()
+                    .map_err(|_| Error::InvalidPriceFormat(format!("{name} of {value}")))
+            })
+            .transpose()
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+/// Which way to round a price onto a valid tick.
+pub enum RoundingMode {
+    /// Round up to the next tick.
+    Up,
+
+    /// Round down to the previous tick.
+    Down,
+
+    /// Round to the nearest tick.
+    #[default]
+    Nearest,
+}
 
 #[derive(Clone, Debug, Deserialize, Serialize)]
 /// The different types of formats for
@@ -0,0 +1,427 @@
+use crate::{
+    market_data::{PriceFormat, SymbolDetails},
+    utils::parse_number,
+    Client, Error,
+};
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex, MutexGuard},
+    time::Duration,
+};
+use tokio::{sync::watch, time::Instant};
+use tracing::{debug, warn};
+
+/// The max amount of symbols TradeStation allows in a single symbol details request.
+pub const MAX_SYMBOLS_PER_REQUEST: usize = 50;
+
+/// How long cached [`SymbolDetails`] are used before being fetched again by default.
+pub const DEFAULT_SYMBOL_DETAILS_TTL: Duration = Duration::from_secs(30 * 60);
+
+/// How long lookups are collected into a single request by default.
+pub const DEFAULT_BATCH_WINDOW: Duration = Duration::from_millis(10);
+
+#[derive(Clone, Debug)]
+/// A concurrent cache of [`SymbolDetails`], shared by every clone of a [`Client`].
+///
+/// Lookups for symbols that aren't cached (or have expired) made within a short
+/// batch window are collected into a single `marketdata/symbols/{a,b,c}` request,
+/// split into requests of at most [`MAX_SYMBOLS_PER_REQUEST`] symbols. Lookups for
+/// a symbol that's already being fetched wait on that request instead of sending
+/// their own.
+///
+/// NOTE: Use [`Client::symbol_cache`] to configure the cache of a [`Client`].
+///
+/// # Example
+/// ---
+///
+/// Look up the tick size and point value of a few symbols at once, where
+/// every lookup shares a single request.
+///
+/// ```rust,no_run
+/// # use tradestation::{Client, Error};
+/// # async fn example(client: &Client) -> Result<(), Error> {
+/// let (spy, es, ty) = tokio::join!(
+///     client.get_price_format("SPY"),
+///     client.get_point_value("@ES"),
+///     client.get_cached_symbol_details(&["@TY"]),
+/// );
+/// println!("SPY tick size: {}", spy?.tick_size(500.0)?);
+/// println!("@ES point value: {}", es?);
+/// println!("@TY details: {:?}", ty?);
+/// # Ok(()) }
+/// ```
+pub struct SymbolDetailsCache {
+    inner: Arc<Mutex<Inner>>,
+}
+impl SymbolDetailsCache {
+    /// Create a new empty `SymbolDetailsCache` using the default TTL and batch window.
+    pub fn new() -> Self {
+        Self::with_settings(DEFAULT_SYMBOL_DETAILS_TTL, DEFAULT_BATCH_WINDOW)
+    }
+
+    /// Create a new empty `SymbolDetailsCache` using a custom TTL and batch window.
+    pub fn with_settings(ttl: Duration, batch_window: Duration) -> Self {
+        Self {
+            inner: Arc::new(Mutex::new(Inner {
+                ttl,
+                batch_window,
+                entries: HashMap::new(),
+                in_flight: HashMap::new(),
+                queued: Vec::new(),
+                collecting: None,
+            })),
+        }
+    }
+
+    /// Get how long cached [`SymbolDetails`] are used before being fetched again.
+    pub fn ttl(&self) -> Duration {
+        self.inner().ttl
+    }
+
+    /// Set how long cached [`SymbolDetails`] are used before being fetched again.
+    pub fn set_ttl(&self, ttl: Duration) {
+        self.inner().ttl = ttl;
+    }
+
+    /// Get how long lookups are collected into a single request.
+    pub fn batch_window(&self) -> Duration {
+        self.inner().batch_window
+    }
+
+    /// Set how long lookups are collected into a single request.
+    ///
+    /// NOTE: A longer window batches more lookups together, at
+    /// the cost of delaying the first lookup by the window.
+    pub fn set_batch_window(&self, batch_window: Duration) {
+        self.inner().batch_window = batch_window;
+    }
+
+    /// Get the amount of symbols currently cached, including expired ones.
+    pub fn len(&self) -> usize {
+        self.inner().entries.len()
+    }
+
+    /// Check if there are no symbols cached.
+    pub fn is_empty(&self) -> bool {
+        self.inner().entries.is_empty()
+    }
+
+    /// Remove a symbol from the cache, so the next lookup fetches it again.
+    pub fn invalidate(&self, symbol: &str) {
+        self.inner().entries.remove(&symbol.to_uppercase());
+    }
+
+    /// Remove every symbol from the cache.
+    pub fn clear(&self) {
+        self.inner().entries.clear();
+    }
+
+    /// Get the [`SymbolDetails`] of symbols, in the same order as the symbols,
+    /// only fetching the symbols which aren't cached or have expired.
+    ///
+    /// # Errors
+    ///
+    /// - [`Error::SymbolNotFound`] if TradeStation didn't return details for a symbol.
+    /// - [`Error::SymbolDetailsUnavailable`] if the request fetching a symbol failed.
+    pub async fn get(
+        &self,
+        client: &Client,
+        symbols: &[&str],
+    ) -> Result<Vec<SymbolDetails>, Error> {
+        let batches = self.enqueue(client, symbols);
+
+        let mut failure = None;
+        for batch in batches {
+            let mut done = batch.subscribe();
+            let result = done
+                .wait_for(Option::is_some)
+                .await
+                .map(|result| result.clone().unwrap_or(Ok(())))
+                .unwrap_or_else(|_| Err("symbol details request was dropped".into()));
+
+            if let Err(e) = result {
+                failure = Some(e);
+            }
+        }
+
+        let inner = self.inner();
+        symbols
+            .iter()
+            .map(|symbol| match inner.entries.get(&symbol.to_uppercase()) {
+                Some(cached) => Ok(cached.details.clone()),
+                None => Err(match &failure {
+                    Some(e) => Error::SymbolDetailsUnavailable(format!("{symbol}: {e}")),
+                    None => Error::SymbolNotFound(symbol.to_string()),
+                }),
+            })
+            .collect()
+    }
+
+    /// Queue the symbols which need fetching, returning the batches to wait on.
+    fn enqueue(&self, client: &Client, symbols: &[&str]) -> Vec<Arc<Batch>> {
+        let mut inner = self.inner();
+        let now = Instant::now();
+        let ttl = inner.ttl;
+
+        let mut batches: Vec<Arc<Batch>> = Vec::new();
+        for symbol in symbols {
+            let key = symbol.to_uppercase();
+            if inner
+                .entries
+                .get(&key)
+                .is_some_and(|cached| now.duration_since(cached.fetched_at) < ttl)
+            {
+                continue;
+            }
+
+            let batch = match inner.in_flight.get(&key) {
+                Some(batch) => Arc::clone(batch),
+                None => {
+                    let batch = self.collecting_batch(&mut inner, client);
+                    inner.queued.push(key.clone());
+                    inner.in_flight.insert(key, Arc::clone(&batch));
+                    batch
+                }
+            };
+
+            if !batches.iter().any(|b| Arc::ptr_eq(b, &batch)) {
+                batches.push(batch);
+            }
+        }
+
+        batches
+    }
+
+    /// Get the batch currently collecting symbols, or start a new one
+    /// which gets flushed once the batch window has passed.
+    fn collecting_batch(&self, inner: &mut Inner, client: &Client) -> Arc<Batch> {
+        if let Some(batch) = &inner.collecting {
+            return Arc::clone(batch);
+        }
+
+        let batch = Arc::new(watch::channel(None).0);
+        inner.collecting = Some(Arc::clone(&batch));
+
+        let cache = self.clone();
+        let client = client.clone();
+        let flushing = Arc::clone(&batch);
+        let batch_window = inner.batch_window;
+        tokio::spawn(async move {
+            tokio::time::sleep(batch_window).await;
+            cache.flush(&client, flushing).await;
+        });
+
+        batch
+    }
+
+    /// Fetch every queued symbol, then let everyone waiting on the batch know it's done.
+    async fn flush(&self, client: &Client, batch: Arc<Batch>) {
+        let symbols = {
+            let mut inner = self.inner();
+            inner.collecting = None;
+            std::mem::take(&mut inner.queued)
+        };
+
+        let mut result = Ok(());
+        for chunk in symbols.chunks(MAX_SYMBOLS_PER_REQUEST) {
+            debug!(
+                target: "tradestation::client",
+                symbols = chunk.len(),
+                "fetching symbol details"
+            );
+
+            let chunk: Vec<&str> = chunk.iter().map(String::as_str).collect();
+            match SymbolDetails::fetch(client, chunk.clone()).await {
+                Ok(details) => {
+                    let mut inner = self.inner();
+                    let fetched_at = Instant::now();
+                    for (requested, details) in pair_with_requested(&chunk, details) {
+                        // Cache the details under the requested symbol too, so
+                        // lookups by it find them when TradeStation renames it
+                        if let Some(requested) = requested {
+                            if requested != details.symbol.to_uppercase() {
+                                inner.entries.insert(
+                                    requested.to_string(),
+                                    CachedDetails {
+                                        details: details.clone(),
+                                        fetched_at,
+                                    },
+                                );
+                            }
+                        }
+
+                        inner.entries.insert(
+                            details.symbol.to_uppercase(),
+                            CachedDetails {
+                                details,
+                                fetched_at,
+                            },
+                        );
+                    }
+                }
+                Err(e) => {
+                    warn!(
+                        target: "tradestation::client",
+                        error = %e,
+                        "failed to fetch symbol details"
+                    );
+                    result = Err(e.to_string());
+                }
+            }
+        }
+
+        let mut inner = self.inner();
+        for symbol in &symbols {
+            inner.in_flight.remove(symbol);
+        }
+        drop(inner);
+
+        batch.send_replace(Some(result));
+    }
+
+    /// Lock the cache state.
+    fn inner(&self) -> MutexGuard<'_, Inner> {
+        self.inner
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+impl Default for SymbolDetailsCache {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Pair fetched [`SymbolDetails`] with the (uppercased) symbols they were requested by.
+///
+/// NOTE: TradeStation can return details under a different symbol than the one
+/// requested, so details which don't match a requested symbol are paired with the
+/// remaining requested symbols in order, but only when there's the same amount of
+/// each, otherwise it's ambiguous which symbol was renamed and they're left unpaired.
+fn pair_with_requested<'a>(
+    requested: &[&'a str],
+    details: Vec<SymbolDetails>,
+) -> Vec<(Option<&'a str>, SymbolDetails)> {
+    let mut paired: Vec<(Option<&'a str>, SymbolDetails)> = details
+        .into_iter()
+        .map(|details| {
+            let symbol = details.symbol.to_uppercase();
+            let requested = requested.iter().copied().find(|req| *req == symbol);
+            (requested, details)
+        })
+        .collect();
+
+    let unmatched_requested: Vec<&str> = requested
+        .iter()
+        .copied()
+        .filter(|req| !paired.iter().any(|(matched, _)| *matched == Some(*req)))
+        .collect();
+    let unmatched_details = paired.iter().filter(|(matched, _)| matched.is_none());
+    if unmatched_requested.len() == unmatched_details.count() {
+        let mut unmatched_requested = unmatched_requested.into_iter();
+        for (matched, _) in paired.iter_mut().filter(|(matched, _)| matched.is_none()) {
+            *matched = unmatched_requested.next();
+        }
+    }
+
+    paired
+}
+
+/// A batch of symbols being fetched together, which is
+/// `None` until the batch is done, then the request result.
+type Batch = watch::Sender<Option<Result<(), String>>>;
+
+#[derive(Debug)]
+/// The state of a [`SymbolDetailsCache`].
+struct Inner {
+    /// How long cached details are used before being fetched again.
+    ttl: Duration,
+
+    /// How long lookups are collected into a single request.
+    batch_window: Duration,
+
+    /// The cached details, keyed by the uppercased symbol, both as
+    /// requested and as returned by TradeStation when they differ.
+    entries: HashMap<String, CachedDetails>,
+
+    /// The batch each symbol currently being fetched belongs to.
+    in_flight: HashMap<String, Arc<Batch>>,
+
+    /// The symbols of the batch currently collecting symbols.
+    queued: Vec<String>,
+
+    /// The batch currently collecting symbols.
+    collecting: Option<Arc<Batch>>,
+}
+
+#[derive(Debug)]
+/// A cached [`SymbolDetails`] and when it was fetched.
+struct CachedDetails {
+    details: SymbolDetails,
+    fetched_at: Instant,
+}
+
+impl Client {
+    /// Get the [`SymbolDetailsCache`] shared by this client and all of it's clones.
+    ///
+    /// # Example
+    /// ---
+    ///
+    /// Cache symbol details for the trading day, and batch lookups made within 25ms.
+    ///
+    /// ```ignore
+    /// client.symbol_cache().set_ttl(Duration::from_secs(8 * 60 * 60));
+    /// client.symbol_cache().set_batch_window(Duration::from_millis(25));
+    /// ```
+    pub fn symbol_cache(&self) -> &SymbolDetailsCache {
+        &self.symbol_cache
+    }
+
+    /// Get the [`SymbolDetails`] of one or more symbols through the
+    /// client's [`SymbolDetailsCache`], in the same order as the symbols.
+    ///
+    /// NOTE: Use `Client::get_symbol_details()` to always fetch fresh details.
+    ///
+    /// # Example
+    /// ---
+    ///
+    /// ```ignore
+    /// let details = client.get_cached_symbol_details(&["SPY", "@ES"]).await?;
+    /// println!("Symbol Details: {details:?}");
+    /// ```
+    pub async fn get_cached_symbol_details(
+        &self,
+        symbols: &[&str],
+    ) -> Result<Vec<SymbolDetails>, Error> {
+        self.symbol_cache.get(self, symbols).await
+    }
+
+    /// Get the [`PriceFormat`] of a symbol through the client's [`SymbolDetailsCache`].
+    ///
+    /// # Example
+    /// ---
+    ///
+    /// ```ignore
+    /// let price_format = client.get_price_format("@ES").await?;
+    /// println!("@ES tick size: {}", price_format.tick_size(5000.0)?);
+    /// ```
+    pub async fn get_price_format(&self, symbol: &str) -> Result<PriceFormat, Error> {
+        let mut details = self.get_cached_symbol_details(&[symbol]).await?;
+
+        Ok(details.remove(0).price_format)
+    }
+
+    /// Get the point value (dollar value of a full point move) of a
+    /// symbol through the client's [`SymbolDetailsCache`].
+    ///
+    /// # Example
+    /// ---
+    ///
+    /// ```ignore
+    /// let point_value = client.get_point_value("@ES").await?;
+    /// assert_eq!(point_value, 50.0);
+    /// ```
+    pub async fn get_point_value(&self, symbol: &str) -> Result<f64, Error> {
+        parse_number(&self.get_price_format(symbol).await?.point_value)
+    }
+}
@@ -0,0 +1,741 @@
+//! Builds an implied volatility surface across option expirations from
+//! [`OptionChain`]'s, with an implied volatility by (expiration, strike)
+//! and (expiration, delta) grid that interpolates missing points.
+//!
+//! From the surface you can get the at the money term structure, the skew
+//! (25 delta risk reversals), and smile snapshots, all of which can be
+//! exported for plotting as JSON (`serde`) or CSV.
+//!
+//! # Example
+//! ---
+//!
+//! Keep a live surface of the next 4 SPY expirations and log it's term structure.
+//!
+//! ```rust,no_run
+//! # use tradestation::{Client, Error, market_data::volatility::{LiveVolatilitySurface, VolatilitySurface}};
+//! # async fn example(client: &Client) -> Result<(), Error> {
+//! let surface = VolatilitySurface::new("SPY").risk_free_rate(0.045);
+//! let live_surface = LiveVolatilitySurface::from_next_expirations(client, surface, 4, 10).await?;
+//!
+//! loop {
+//!     tokio::time::sleep(std::time::Duration::from_secs(60)).await;
+//!
+//!     let snapshot = live_surface.snapshot();
+//!     for point in snapshot.atm_term_structure() {
+//!         println!("{}: {:.2}%", point.expiration, point.implied_volatility * 100.0);
+//!     }
+//!     println!("{}", snapshot.grid(&[560.0, 570.0, 580.0]).to_csv());
+//! }
+//! # }
+//! ```
+//!
+//! [`OptionChain`]: crate::market_data::OptionChain
+
+use crate::{
+    accounting::OptionType,
+    market_data::{
+        analytics::{years_until, EuropeanOption, PricingModel, QuoteSide},
+        options::OptionType as ChainOptionType,
+        subscription::MAX_CONCURRENT_STREAMS,
+        OptionChain, OptionChainQuery, OptionChainQueryBuilder, OptionExpiration,
+    },
+    responses::market_data::StreamOptionChainResp,
+    utils::{now_epoch_seconds, parse_number, parse_timestamp},
+    Client, Error,
+};
+use futures::StreamExt;
+use serde::Serialize;
+use std::{
+    collections::BTreeMap,
+    sync::{Arc, Mutex, MutexGuard},
+    time::Duration,
+};
+use tokio::task::JoinHandle;
+use tracing::warn;
+
+/// How long a chain stream waits before reconnecting after it ended or failed.
+const RECONNECT_DELAY: Duration = Duration::from_secs(1);
+
+/// Strikes are keyed at this precision so they can be ordered.
+const STRIKE_KEY_SCALE: f64 = 10_000.0;
+
+#[derive(Clone, Debug, Default, Serialize)]
+#[serde(rename_all = "PascalCase")]
+/// The implied volatility and delta of the call and put at a single strike.
+pub struct SmilePoint {
+    /// The strike price.
+    pub strike: f64,
+
+    /// The implied volatility of the call as a decimal (`0.20` for 20%).
+    pub call_implied_volatility: Option<f64>,
+
+    /// The implied volatility of the put as a decimal (`0.20` for 20%).
+    pub put_implied_volatility: Option<f64>,
+
+    /// The delta of the call.
+    pub call_delta: Option<f64>,
+
+    /// The delta of the put.
+    pub put_delta: Option<f64>,
+}
+impl SmilePoint {
+    /// The implied volatility at this strike, the average of the
+    /// call and put implied volatility when both are known.
+    pub fn implied_volatility(&self) -> Option<f64> {
+        match (self.call_implied_volatility, self.put_implied_volatility) {
+            (Some(call), Some(put)) => Some((call + put) / 2.0),
+            (call, put) => call.or(put),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "PascalCase")]
+/// A snapshot of the volatility smile of a single expiration.
+pub struct Smile {
+    /// The expiration as an RFC3339 formatted date.
+    pub expiration: String,
+
+    /// The time until the expiration in years.
+    pub time_to_expiration: f64,
+
+    /// The points of the smile ordered by strike.
+    pub points: Vec<SmilePoint>,
+}
+impl Smile {
+    /// Export the smile as CSV with a header row.
+    pub fn to_csv(&self) -> String {
+        let mut csv = String::from(
+            "strike,implied_volatility,call_implied_volatility,put_implied_volatility,call_delta,put_delta\n",
+        );
+
+        for point in &self.points {
+            csv.push_str(&format!(
+                "{},{},{},{},{},{}\n",
+                point.strike,
+                csv_value(point.implied_volatility()),
+                csv_value(point.call_implied_volatility),
+                csv_value(point.put_implied_volatility),
+                csv_value(point.call_delta),
+                csv_value(point.put_delta),
+            ));
+        }
+
+        csv
+    }
+}
+
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "PascalCase")]
+/// The at the money implied volatility of a single expiration.
+pub struct TermStructurePoint {
+    /// The expiration as an RFC3339 formatted date.
+    pub expiration: String,
+
+    /// The time until the expiration in years.
+    pub time_to_expiration: f64,
+
+    /// The at the money implied volatility as a decimal (`0.20` for 20%).
+    pub implied_volatility: f64,
+}
+
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "PascalCase")]
+/// The risk reversal (skew) of a single expiration.
+pub struct RiskReversal {
+    /// The expiration as an RFC3339 formatted date.
+    pub expiration: String,
+
+    /// The time until the expiration in years.
+    pub time_to_expiration: f64,
+
+    /// The implied volatility of the call at the delta.
+    pub call_implied_volatility: f64,
+
+    /// The implied volatility of the put at the (negative) delta.
+    pub put_implied_volatility: f64,
+
+    /// The call implied volatility minus the put implied volatility.
+    pub risk_reversal: f64,
+}
+
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "PascalCase")]
+/// An implied volatility grid by expiration (rows) and strike (columns).
+pub struct VolatilityGrid {
+    /// The expirations (rows) as RFC3339 formatted dates.
+    pub expirations: Vec<String>,
+
+    /// The strikes (columns).
+    pub strikes: Vec<f64>,
+
+    /// The implied volatility of each expiration and strike, `None`
+    /// when there's no data to interpolate from.
+    pub implied_volatilities: Vec<Vec<Option<f64>>>,
+}
+impl VolatilityGrid {
+    /// Export the grid as CSV, with the strikes as the header row
+    /// and the expiration as the first column of each row.
+    pub fn to_csv(&self) -> String {
+        let mut csv = String::from("expiration");
+        for strike in &self.strikes {
+            csv.push_str(&format!(",{strike}"));
+        }
+        csv.push('\n');
+
+        for (expiration, row) in self.expirations.iter().zip(&self.implied_volatilities) {
+            csv.push_str(expiration);
+            for implied_volatility in row {
+                csv.push(',');
+                csv.push_str(&csv_value(*implied_volatility));
+            }
+            csv.push('\n');
+        }
+
+        csv
+    }
+}
+
+#[derive(Clone, Debug)]
+/// The smile of a single expiration.
+struct ExpirySmile {
+    expiration: String,
+    points: BTreeMap<i64, SmilePoint>,
+}
+
+#[derive(Clone, Debug)]
+/// An implied volatility surface across expirations.
+///
+/// Fed by [`OptionChain`]'s (see [`VolatilitySurface::update`]), using the
+/// implied volatility and delta TradeStation reports when the chain is
+/// streamed with greeks enabled. Otherwise, if an underlying price was set,
+/// they're solved from the mid price with [`crate::market_data::analytics`].
+pub struct VolatilitySurface {
+    underlying: String,
+    underlying_price: Option<f64>,
+    risk_free_rate: f64,
+    model: PricingModel,
+    expirations: BTreeMap<i64, ExpirySmile>,
+}
+impl VolatilitySurface {
+    /// Create a new, empty, `VolatilitySurface` for an underlying symbol.
+    pub fn new(underlying: impl Into<String>) -> Self {
+        Self {
+            underlying: underlying.into(),
+            underlying_price: None,
+            risk_free_rate: 0.0,
+            model: PricingModel::BlackScholes,
+            expirations: BTreeMap::new(),
+        }
+    }
+
+    /// Set the pricing model used when implied volatility or delta has to be solved.
+    ///
+    /// NOTE: Use [`PricingModel::Black76`] for options on futures.
+    pub fn model(mut self, model: PricingModel) -> Self {
+        self.model = model;
+        self
+    }
+
+    /// Set the risk free rate used when implied volatility or delta has to be solved.
+    pub fn risk_free_rate(mut self, risk_free_rate: f64) -> Self {
+        self.risk_free_rate = risk_free_rate;
+        self
+    }
+
+    /// Set the latest price of the underlying, used for at the money strikes
+    /// and to solve implied volatility and delta when they're not reported.
+    pub fn set_underlying_price(&mut self, price: f64) {
+        self.underlying_price = Some(price);
+    }
+
+    /// Get the underlying symbol of the surface.
+    pub fn underlying(&self) -> &str {
+        &self.underlying
+    }
+
+    /// Update the surface with a single option [`OptionChain`].
+    ///
+    /// NOTE: Chains without a reported implied volatility are skipped when no
+    /// underlying price is set, as there's nothing to solve it from.
+    ///
+    /// # Errors
+    ///
+    /// - [`Error::OptionLegsNotSet`] if the chain has no legs.
+    /// - [`Error::InvalidNumber`] or [`Error::InvalidTimestamp`] if the chain can't be parsed.
+    pub fn update(&mut self, chain: &OptionChain) -> Result<(), Error> {
+        let leg = chain.legs.first().ok_or(Error::OptionLegsNotSet)?;
+        let option_type = match leg.option_type {
+            ChainOptionType::Call => OptionType::Call,
+            ChainOptionType::Put => OptionType::Put,
+            ChainOptionType::All => return Ok(()),
+        };
+        let strike = parse_number(&leg.strike_price)?;
+        let expires_at = parse_timestamp(&leg.expiration)?;
+
+        let reported_implied_volatility = parse_optional(&chain.implied_volatility)?;
+        let reported_delta = parse_optional(&chain.delta)?;
+
+        let solver = self
+            .underlying_price
+            .map(|underlying_price| EuropeanOption {
+                model: self.model,
+                option_type,
+                underlying_price,
+                strike,
+                time_to_expiration: years_until(&leg.expiration, now_epoch_seconds())
+                    .unwrap_or_default(),
+                risk_free_rate: self.risk_free_rate,
+                dividend_yield: 0.0,
+            });
+
+        let implied_volatility = match (reported_implied_volatility, &solver) {
+            (Some(implied_volatility), _) => Some(implied_volatility),
+            (None, Some(solver)) => solver
+                .implied_volatility_from_chain(chain, QuoteSide::Mid)
+                .ok(),
+            (None, None) => None,
+        };
+        let Some(implied_volatility) = implied_volatility.filter(|iv| *iv > 0.0) else {
+            return Ok(());
+        };
+        let delta = reported_delta.or_else(|| {
+            solver
+                .as_ref()
+                .map(|solver| solver.greeks(implied_volatility).delta)
+        });
+
+        let smile = self
+            .expirations
+            .entry(expires_at)
+            .or_insert_with(|| ExpirySmile {
+                expiration: leg.expiration.clone(),
+                points: BTreeMap::new(),
+            });
+        let point = smile
+            .points
+            .entry(strike_key(strike))
+            .or_insert_with(|| SmilePoint {
+                strike,
+                ..SmilePoint::default()
+            });
+
+        match option_type {
+            OptionType::Call => {
+                point.call_implied_volatility = Some(implied_volatility);
+                point.call_delta = delta;
+            }
+            OptionType::Put => {
+                point.put_implied_volatility = Some(implied_volatility);
+                point.put_delta = delta;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Get the expirations in the surface, in order.
+    pub fn expirations(&self) -> Vec<String> {
+        self.expirations
+            .values()
+            .map(|smile| smile.expiration.clone())
+            .collect()
+    }
+
+    /// Get a snapshot of the smile of an expiration.
+    ///
+    /// NOTE: The expiration can be an RFC3339 timestamp or a plain date (`"2024-12-20"`).
+    pub fn smile(&self, expiration: &str) -> Option<Smile> {
+        let smile = self.find_smile(expiration)?;
+
+        Some(Smile {
+            expiration: smile.expiration.clone(),
+            time_to_expiration: time_to_expiration(&smile.expiration),
+            points: smile.points.values().cloned().collect(),
+        })
+    }
+
+    /// Get snapshots of the smile of every expiration.
+    pub fn smiles(&self) -> Vec<Smile> {
+        self.expirations
+            .values()
+            .filter_map(|smile| self.smile(&smile.expiration))
+            .collect()
+    }
+
+    /// Get the implied volatility of an expiration at a strike,
+    /// interpolating linearly between the nearest strikes.
+    ///
+    /// NOTE: Strikes outside of the smile get the implied volatility of the nearest strike.
+    pub fn implied_volatility_at_strike(&self, expiration: &str, strike: f64) -> Option<f64> {
+        smile_at_strike(self.find_smile(expiration)?, strike)
+    }
+
+    /// Get the implied volatility of an expiration at a delta, interpolating
+    /// linearly between the nearest deltas. Positive deltas use the calls, and
+    /// negative deltas use the puts (`-0.25` for the 25 delta put).
+    pub fn implied_volatility_at_delta(&self, expiration: &str, delta: f64) -> Option<f64> {
+        smile_at_delta(self.find_smile(expiration)?, delta)
+    }
+
+    /// Get the implied volatility at any time to expiration (in years) and strike,
+    /// interpolating linearly in total variance between expirations.
+    pub fn implied_volatility(&self, time_to_expiration: f64, strike: f64) -> Option<f64> {
+        let points: Vec<(f64, f64)> = self
+            .expirations
+            .values()
+            .filter_map(|smile| {
+                let time = self::time_to_expiration(&smile.expiration);
+                let implied_volatility = smile_at_strike(smile, strike)?;
+                Some((time, implied_volatility))
+            })
+            .collect();
+
+        interpolate_total_variance(&points, time_to_expiration)
+    }
+
+    /// Get the at the money implied volatility of every expiration.
+    ///
+    /// NOTE: At the money is the underlying price strike when an underlying
+    /// price is set, otherwise it's the 50 delta call.
+    pub fn atm_term_structure(&self) -> Vec<TermStructurePoint> {
+        self.expirations
+            .values()
+            .filter_map(|smile| {
+                let implied_volatility = match self.underlying_price {
+                    Some(price) => smile_at_strike(smile, price),
+                    None => smile_at_delta(smile, 0.5),
+                }?;
+
+                Some(TermStructurePoint {
+                    expiration: smile.expiration.clone(),
+                    time_to_expiration: time_to_expiration(&smile.expiration),
+                    implied_volatility,
+                })
+            })
+            .collect()
+    }
+
+    /// Get the risk reversal of every expiration at a delta, for example
+    /// `0.25` for the 25 delta call minus the 25 delta put.
+    pub fn risk_reversals(&self, delta: f64) -> Vec<RiskReversal> {
+        let delta = delta.abs();
+
+        self.expirations
+            .values()
+            .filter_map(|smile| {
+                let call_implied_volatility = smile_at_delta(smile, delta)?;
+                let put_implied_volatility = smile_at_delta(smile, -delta)?;
+
+                Some(RiskReversal {
+                    expiration: smile.expiration.clone(),
+                    time_to_expiration: time_to_expiration(&smile.expiration),
+                    call_implied_volatility,
+                    put_implied_volatility,
+                    risk_reversal: call_implied_volatility - put_implied_volatility,
+                })
+            })
+            .collect()
+    }
+
+    /// Get the 25 delta risk reversal (skew) of every expiration.
+    pub fn skew(&self) -> Vec<RiskReversal> {
+        self.risk_reversals(0.25)
+    }
+
+    /// Get an implied volatility grid of every expiration at the given strikes.
+    ///
+    /// NOTE: Missing strikes are interpolated within the expiration's smile, and
+    /// expirations without any data are interpolated between the other expirations.
+    pub fn grid(&self, strikes: &[f64]) -> VolatilityGrid {
+        let implied_volatilities = self
+            .expirations
+            .values()
+            .map(|smile| {
+                strikes
+                    .iter()
+                    .map(|strike| {
+                        smile_at_strike(smile, *strike).or_else(|| {
+                            self.implied_volatility(time_to_expiration(&smile.expiration), *strike)
+                        })
+                    })
+                    .collect()
+            })
+            .collect();
+
+        VolatilityGrid {
+            expirations: self.expirations(),
+            strikes: strikes.to_vec(),
+            implied_volatilities,
+        }
+    }
+
+    /// Get an implied volatility grid of every expiration at the given deltas.
+    ///
+    /// NOTE: The columns of the returned grid are the deltas instead of strikes.
+    pub fn delta_grid(&self, deltas: &[f64]) -> VolatilityGrid {
+        VolatilityGrid {
+            expirations: self.expirations(),
+            strikes: deltas.to_vec(),
+            implied_volatilities: self
+                .expirations
+                .values()
+                .map(|smile| {
+                    deltas
+                        .iter()
+                        .map(|delta| smile_at_delta(smile, *delta))
+                        .collect()
+                })
+                .collect(),
+        }
+    }
+
+    /// Find the smile of an expiration by it's timestamp or date.
+    fn find_smile(&self, expiration: &str) -> Option<&ExpirySmile> {
+        let expires_at = parse_timestamp(expiration).ok()?;
+
+        self.expirations.get(&expires_at).or_else(|| {
+            // Match on the date alone, ignoring the time of day
+            let day = expires_at.div_euclid(86_400);
+            self.expirations
+                .iter()
+                .find(|(at, _)| at.div_euclid(86_400) == day)
+                .map(|(_, smile)| smile)
+        })
+    }
+}
+
+/// A [`VolatilitySurface`] kept up to date by option chain streams,
+/// one per expiration.
+///
+/// <div class="warning">WARNING: Every expiration is it's own stream, and there's a max of 10 concurrent streams allowed.</div>
+///
+/// NOTE: Dropping this closes all of it's streams.
+#[derive(Debug)]
+pub struct LiveVolatilitySurface {
+    surface: Arc<Mutex<VolatilitySurface>>,
+    tasks: Vec<JoinHandle<()>>,
+}
+impl LiveVolatilitySurface {
+    /// Start streaming option chains for the given expirations into a surface.
+    ///
+    /// NOTE: `strike_proximity` is the amount of strikes above and below the
+    /// at the money strike to stream for each expiration.
+    ///
+    /// NOTE: Must be called from within a tokio runtime.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::StreamLimitReached`] if there are more expirations than
+    /// concurrent streams allowed.
+    pub fn start(
+        client: &Client,
+        surface: VolatilitySurface,
+        expirations: &[OptionExpiration],
+        strike_proximity: i32,
+    ) -> Result<Self, Error> {
+        if expirations.len() > MAX_CONCURRENT_STREAMS {
+            return Err(Error::StreamLimitReached(MAX_CONCURRENT_STREAMS));
+        }
+
+        let queries = expirations
+            .iter()
+            .map(|expiration| {
+                OptionChainQueryBuilder::new()
+                    .underlying(surface.underlying())
+                    .expiration(expiration.date.get(..10).unwrap_or(&expiration.date))
+                    .strike_proximity(strike_proximity)
+                    .enable_greeks(true)
+                    .build()
+            })
+            .collect::<Result<Vec<_>, Error>>()?;
+
+        let surface = Arc::new(Mutex::new(surface));
+        let tasks = queries
+            .into_iter()
+            .map(|query| tokio::spawn(stream_chains(client.clone(), query, surface.clone())))
+            .collect();
+
+        Ok(Self { surface, tasks })
+    }
+
+    /// Fetch the next `count` expirations of the surface's underlying
+    /// and start streaming their option chains into the surface.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`Error`] if the expirations can't be fetched, or there are more
+    /// expirations than concurrent streams allowed.
+    pub async fn from_next_expirations(
+        client: &Client,
+        surface: VolatilitySurface,
+        count: usize,
+        strike_proximity: i32,
+    ) -> Result<Self, Error> {
+        let expirations = client
+            .get_option_expirations(surface.underlying(), None)
+            .await?;
+        let count = count.min(expirations.len());
+
+        Self::start(client, surface, &expirations[..count], strike_proximity)
+    }
+
+    /// Get a snapshot of the surface as it currently is.
+    pub fn snapshot(&self) -> VolatilitySurface {
+        self.surface().clone()
+    }
+
+    /// Set the latest price of the underlying.
+    pub fn set_underlying_price(&self, price: f64) {
+        self.surface().set_underlying_price(price);
+    }
+
+    /// Lock the surface, recovering it if a holder of the lock panicked.
+    fn surface(&self) -> MutexGuard<'_, VolatilitySurface> {
+        self.surface
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+impl Drop for LiveVolatilitySurface {
+    fn drop(&mut self) {
+        for task in &self.tasks {
+            task.abort();
+        }
+    }
+}
+
+/// Stream the option chains of a query into a surface, reconnecting whenever the stream ends.
+async fn stream_chains(
+    client: Client,
+    query: OptionChainQuery,
+    surface: Arc<Mutex<VolatilitySurface>>,
+) {
+    loop {
+        let stream = OptionChain::stream(&client, &query);
+        tokio::pin!(stream);
+
+        while let Some(stream_event) = stream.next().await {
+            match stream_event {
+                Ok(StreamOptionChainResp::OptionChain(chain)) => {
+                    let mut surface = surface
+                        .lock()
+                        .unwrap_or_else(|poisoned| poisoned.into_inner());
+
+                    if let Err(e) = surface.update(&chain) {
+                        warn!(
+                            target: "tradestation::stream",
+                            underlying = query.underlying,
+                            error = %e,
+                            "skipped option chain for volatility surface"
+                        );
+                    }
+                }
+                Ok(_) => {}
+                Err(e) => {
+                    warn!(
+                        target: "tradestation::stream",
+                        underlying = query.underlying,
+                        error = %e,
+                        "volatility surface option chain stream failed"
+                    );
+                    break;
+                }
+            }
+        }
+
+        tokio::time::sleep(RECONNECT_DELAY).await;
+    }
+}
+
+/// The key of a strike in a smile.
+fn strike_key(strike: f64) -> i64 {
+    (strike * STRIKE_KEY_SCALE).round() as i64
+}
+
+/// The time until an expiration in years from now.
+fn time_to_expiration(expiration: &str) -> f64 {
+    years_until(expiration, now_epoch_seconds()).unwrap_or_default()
+}
+
+/// Parse an optional number TradeStation sends as a string.
+fn parse_optional(value: &Option<String>) -> Result<Option<f64>, Error> {
+    value.as_deref().map(parse_number).transpose()
+}
+
+/// The implied volatility of a smile at a strike.
+fn smile_at_strike(smile: &ExpirySmile, strike: f64) -> Option<f64> {
+    let points: Vec<(f64, f64)> = smile
+        .points
+        .values()
+        .filter_map(|point| Some((point.strike, point.implied_volatility()?)))
+        .collect();
+
+    interpolate(&points, strike)
+}
+
+/// The implied volatility of a smile at a call (positive) or put (negative) delta.
+fn smile_at_delta(smile: &ExpirySmile, delta: f64) -> Option<f64> {
+    let mut points: Vec<(f64, f64)> = smile
+        .points
+        .values()
+        .filter_map(|point| {
+            if delta >= 0.0 {
+                Some((point.call_delta?, point.call_implied_volatility?))
+            } else {
+                Some((point.put_delta?, point.put_implied_volatility?))
+            }
+        })
+        .collect();
+    points.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+    interpolate(&points, delta)
+}
+
+/// Interpolate linearly between points sorted by `x`, using the
+/// nearest point for any `x` outside of the points.
+fn interpolate(points: &[(f64, f64)], x: f64) -> Option<f64> {
+    let (first, last) = (points.first()?, points.last()?);
+    if x <= first.0 {
+        return Some(first.1);
+    }
+    if x >= last.0 {
+        return Some(last.1);
+    }
+
+    points.windows(2).find_map(|window| {
+        let ((x0, y0), (x1, y1)) = (window[0], window[1]);
+        if x < x0 || x > x1 {
+            return None;
+        }
+        if x1 == x0 {
+            return Some(y0);
+        }
+
+        Some(y0 + (y1 - y0) * (x - x0) / (x1 - x0))
+    })
+}
+
+/// Interpolate implied volatility between expirations (points of time
+/// and implied volatility sorted by time) linearly in total variance.
+fn interpolate_total_variance(points: &[(f64, f64)], time: f64) -> Option<f64> {
+    let (first, last) = (points.first()?, points.last()?);
+    if time <= first.0 || first.0 <= 0.0 {
+        return Some(first.1);
+    }
+    if time >= last.0 {
+        return Some(last.1);
+    }
+
+    let variances: Vec<(f64, f64)> = points
+        .iter()
+        .map(|(time, implied_volatility)| (*time, implied_volatility * implied_volatility * time))
+        .collect();
+
+    interpolate(&variances, time).map(|variance| (variance / time).max(0.0).sqrt())
+}
+
+/// Format an optional value for CSV, empty when missing.
+fn csv_value(value: Option<f64>) -> String {
+    value.map(|value| value.to_string()).unwrap_or_default()
+}
@@ -1,5 +1,5 @@
 use crate::{
-    accounting::{Account, BODBalance, Balance, Order, Position},
+    accounting::{Account, BODBalance, Balance, Order, Position, Wallet},
     responses::stream,
 };
 use serde::{de, Deserialize, Serialize};
@@ -158,3 +158,56 @@ impl<'de> Deserialize<'de> for StreamPositionsResp {
         }
     }
 }
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "PascalCase")]
+/// The TradeStation API Response for getting a crypto account's wallets.
+pub(crate) struct GetWalletsResp {
+    pub wallets: Vec<Wallet>,
+
+    #[serde(default)]
+    pub errors: Vec<AccountApiError>,
+}
+
+/// The TradeStation API Response for streaming `Wallet`(s).
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "PascalCase")]
+pub enum StreamWalletsResp {
+    /// The main response which contains wallet data
+    Wallet(Box<self::Wallet>),
+
+    /// Periodic signal to know the connection is still alive
+    Heartbeat(stream::Heartbeat),
+
+    /// Signal sent on state changes in the stream (closed, opened, paused, resumed)
+    Status(stream::StreamStatus),
+
+    /// Response for when an error was encountered, with details on the error
+    Error(stream::ErrorResp),
+}
+impl<'de> Deserialize<'de> for StreamWalletsResp {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let value = serde_json::Value::deserialize(deserializer)?;
+
+        if value.get("Currency").is_some() {
+            // Deserialize into the `Wallet` variant
+            let wallet = serde_json::from_value(value).map_err(de::Error::custom)?;
+            Ok(StreamWalletsResp::Wallet(Box::new(wallet)))
+        } else if value.get("StreamStatus").is_some() {
+            // Deserialize into the `Status` variant
+            let status = serde_json::from_value(value).map_err(de::Error::custom)?;
+            Ok(StreamWalletsResp::Status(status))
+        } else if value.get("Heartbeat").is_some() {
+            // Deserialize into the `Heartbeat` variant
+            let heartbeat = serde_json::from_value(value).map_err(de::Error::custom)?;
+            Ok(StreamWalletsResp::Heartbeat(heartbeat))
+        } else {
+            // Default to `Error` variant if nothing else matches
+            let error = serde_json::from_value(value).map_err(de::Error::custom)?;
+            Ok(StreamWalletsResp::Error(error))
+        }
+    }
+}
@@ -114,6 +114,7 @@ impl From<ModifyOrderResp> for OrderTicket {
             order_id: raw.order_id,
             message: raw.message.unwrap_or_default(),
             error,
+            dry_run: false,
         }
     }
 }
@@ -1,8 +1,8 @@
 use crate::{
     market_data::{
         Bar, MarketDepthAggregates, MarketDepthQuotes, OptionChain, OptionExpiration, OptionQuote,
-        OptionRiskRewardAnalysis, OptionSpreadStrikes, OptionSpreadType, Quote, QuoteStreamUpdate,
-        SymbolDetails,
+        OptionRiskRewardAnalysis, OptionSpreadStrikes, OptionSpreadType, OptionSpreadTypeDetails,
+        Quote, QuoteStreamUpdate, SymbolDetails,
     },
     responses::{stream, ApiError},
     Error,
@@ -95,6 +95,22 @@ impl From<GetOptionsRiskRewardRespRaw> for GetOptionsRiskRewardResp {
     }
 }
 
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "PascalCase")]
+/// The TradeStation API Response for fetching option spread types.
+pub(crate) struct GetOptionSpreadTypesResp {
+    /// The option spread types.
+    pub spread_types: Vec<OptionSpreadTypeDetails>,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "PascalCase")]
+/// The TradeStation API Response for fetching crypto pair symbol names.
+pub(crate) struct GetCryptoSymbolNamesResp {
+    /// The crypto pair symbol names.
+    pub symbol_names: Vec<String>,
+}
+
 #[derive(Clone, Debug, Deserialize, Serialize)]
 #[serde(rename_all = "PascalCase")]
 /// The TradeStation API Response for fetching symbol details.
@@ -0,0 +1,279 @@
+use crate::{Client, Error};
+use futures::{Stream, StreamExt};
+use serde::de::DeserializeOwned;
+use std::sync::Arc;
+use tokio::sync::broadcast::{self, error::RecvError};
+use tokio_util::sync::{CancellationToken, DropGuard};
+use tracing::{debug, warn};
+
+/// The default amount of events buffered for subscribers of a [`SharedStream`].
+pub const DEFAULT_SHARED_STREAM_BUFFER: usize = 1024;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+/// What happens to a [`SharedStream`] subscriber that falls further
+/// behind than the buffer of the stream.
+pub enum LagPolicy {
+    /// Skip the oldest events the subscriber missed and keep going
+    /// from the oldest event still buffered.
+    #[default]
+    DropOldest,
+
+    /// Disconnect the subscriber, it receives an [`Error::StreamLagged`]
+    /// and then no more events.
+    Disconnect,
+}
+
+#[derive(Clone, Copy, Debug)]
+/// Configuration for a [`SharedStream`].
+pub struct SharedStreamConfig {
+    /// The amount of events buffered for slow subscribers.
+    pub buffer: usize,
+
+    /// What to do with subscribers that fall behind the buffer.
+    pub lag_policy: LagPolicy,
+}
+impl Default for SharedStreamConfig {
+    fn default() -> Self {
+        Self {
+            buffer: DEFAULT_SHARED_STREAM_BUFFER,
+            lag_policy: LagPolicy::default(),
+        }
+    }
+}
+impl SharedStreamConfig {
+    /// Create a new `SharedStreamConfig` with the default buffer and `LagPolicy`.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the amount of events buffered for slow subscribers.
+    ///
+    /// NOTE: A buffer of 0 is treated as a buffer of 1.
+    pub fn buffer(mut self, buffer: usize) -> Self {
+        self.buffer = buffer.max(1);
+        self
+    }
+
+    /// Set what to do with subscribers that fall behind the buffer.
+    pub fn lag_policy(mut self, lag_policy: LagPolicy) -> Self {
+        self.lag_policy = lag_policy;
+        self
+    }
+}
+
+/// A single stream connection shared by many subscribers.
+///
+/// Every clone of a `SharedStream` is another subscriber receiving every
+/// event from the point it was cloned, all from a single underlying stream
+/// connection. The underlying stream is shutdown as soon as the last
+/// subscriber is dropped.
+///
+/// NOTE: Must be opened from within a tokio runtime.
+///
+/// # Example
+/// ---
+///
+/// Share one order stream between a risk monitor and a trade logger.
+///
+/// ```rust,no_run
+/// # use tradestation::{Client, Error, accounting::Account, stream::{LagPolicy, SharedStreamConfig}};
+/// # async fn example(client: &Client, account: &Account) -> Result<(), Error> {
+/// let config = SharedStreamConfig::new()
+///     .buffer(256)
+///     .lag_policy(LagPolicy::Disconnect);
+///
+/// let mut risk_monitor = account.share_orders_stream(client, config);
+/// let mut trade_logger = risk_monitor.clone();
+///
+/// tokio::spawn(async move {
+///     while let Some(event) = trade_logger.recv().await {
+///         println!("Order Stream Event: {event:?}");
+///     }
+/// });
+///
+/// while let Some(event) = risk_monitor.recv().await {
+///     // ...
+/// #   let _ = event?;
+/// }
+/// # Ok(()) }
+/// ```
+pub struct SharedStream<T> {
+    receiver: broadcast::Receiver<Result<T, String>>,
+    lag_policy: LagPolicy,
+    disconnected: bool,
+    shutdown_guard: Arc<DropGuard>,
+}
+impl<T: DeserializeOwned + Clone + Send + 'static> SharedStream<T> {
+    /// Open a `SharedStream` on a TradeStation streaming endpoint,
+    /// deserializing each streamed event into `T`.
+    ///
+    /// <div class="warning">WARNING: There's a max of 10 concurrent streams allowed.</div>
+    ///
+    /// NOTE: The endpoint is relative to the base url of the [`Client`]'s
+    /// environment, for example: `"marketdata/stream/quotes/SPY,QQQ"`.
+    pub fn open(client: &Client, endpoint: impl Into<String>, config: SharedStreamConfig) -> Self {
+        let client = client.clone();
+        let endpoint = endpoint.into();
+
+        Self::spawn(config, move |sender, shutdown| async move {
+            let stream = client.stream(endpoint).map(|chunk| {
+                chunk.and_then(|value| serde_json::from_value::<T>(value).map_err(Error::Json))
+            });
+
+            forward(stream, sender, shutdown).await;
+        })
+    }
+}
+impl<T: Clone + Send + 'static> SharedStream<T> {
+    /// Share an already built stream between subscribers.
+    ///
+    /// NOTE: The stream must own everything it uses, for streams borrowing
+    /// a [`Client`] use [`SharedStream::open`] instead.
+    pub fn from_stream<S>(stream: S, config: SharedStreamConfig) -> Self
+    where
+        S: Stream<Item = Result<T, Error>> + Send + 'static,
+    {
+        Self::spawn(config, move |sender, shutdown| {
+            forward(stream, sender, shutdown)
+        })
+    }
+
+    /// Spawn the task driving the underlying stream.
+    fn spawn<F, Fut>(config: SharedStreamConfig, drive: F) -> Self
+    where
+        F: FnOnce(broadcast::Sender<Result<T, String>>, CancellationToken) -> Fut,
+        Fut: std::future::Future<Output = ()> + Send + 'static,
+    {
+        let (sender, receiver) = broadcast::channel(config.buffer.max(1));
+        let shutdown = CancellationToken::new();
+
+        tokio::spawn(drive(sender, shutdown.clone()));
+
+        Self {
+            receiver,
+            lag_policy: config.lag_policy,
+            disconnected: false,
+            shutdown_guard: Arc::new(shutdown.drop_guard()),
+        }
+    }
+}
+impl<T: Clone> SharedStream<T> {
+    /// Receive the next event from the stream.
+    ///
+    /// NOTE: Returns `None` once the underlying stream has ended,
+    /// or after this subscriber was disconnected for lagging.
+    ///
+    /// # Errors
+    ///
+    /// Yields [`Error::StreamIssue`] if the underlying stream failed, and
+    /// [`Error::StreamLagged`] if this subscriber fell too far behind while
+    /// using [`LagPolicy::Disconnect`].
+    pub async fn recv(&mut self) -> Option<Result<T, Error>> {
+        if self.disconnected {
+            return None;
+        }
+
+        loop {
+            match self.receiver.recv().await {
+                Ok(Ok(event)) => return Some(Ok(event)),
+                Ok(Err(e)) => return Some(Err(Error::StreamIssue(e))),
+                Err(RecvError::Closed) => return None,
+                Err(RecvError::Lagged(missed)) => match self.lag_policy {
+                    LagPolicy::DropOldest => {
+                        debug!(
+                            target: "tradestation::stream",
+                            missed,
+                            "shared stream subscriber lagged, dropped oldest events"
+                        );
+                    }
+                    LagPolicy::Disconnect => {
+                        warn!(
+                            target: "tradestation::stream",
+                            missed,
+                            "shared stream subscriber lagged, disconnecting"
+                        );
+                        self.disconnected = true;
+                        return Some(Err(Error::StreamLagged(missed)));
+                    }
+                },
+            }
+        }
+    }
+
+    /// Get the amount of subscribers currently sharing the stream.
+    pub fn subscriber_count(&self) -> usize {
+        Arc::strong_count(&self.shutdown_guard)
+    }
+
+    /// Turn this subscriber into a [`Stream`] of events.
+    pub fn into_stream(mut self) -> impl Stream<Item = Result<T, Error>> {
+        async_stream::stream! {
+            while let Some(event) = self.recv().await {
+                yield event;
+            }
+        }
+    }
+}
+impl<T: Clone> Clone for SharedStream<T> {
+    /// Create another subscriber, receiving events from this point onwards.
+    fn clone(&self) -> Self {
+        Self {
+            receiver: self.receiver.resubscribe(),
+            lag_policy: self.lag_policy,
+            disconnected: false,
+            shutdown_guard: Arc::clone(&self.shutdown_guard),
+        }
+    }
+}
+impl<T: Clone> std::fmt::Debug for SharedStream<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SharedStream")
+            .field("subscribers", &self.subscriber_count())
+            .field("lag_policy", &self.lag_policy)
+            .field("disconnected", &self.disconnected)
+            .finish()
+    }
+}
+
+/// Forward every event of a stream to the subscribers until either
+/// the stream ends, it fails, or every subscriber is gone.
+async fn forward<T, S>(
+    stream: S,
+    sender: broadcast::Sender<Result<T, String>>,
+    shutdown: CancellationToken,
+) where
+    S: Stream<Item = Result<T, Error>>,
+{
+    tokio::pin!(stream);
+
+    loop {
+        let event = tokio::select! {
+            _ = shutdown.cancelled() => {
+                debug!(
+                    target: "tradestation::stream",
+                    "last shared stream subscriber dropped, shutting down"
+                );
+                return;
+            }
+            event = stream.next() => event,
+        };
+
+        match event {
+            Some(Ok(event)) => {
+                if sender.send(Ok(event)).is_err() {
+                    return;
+                }
+            }
+            Some(Err(e)) => {
+                warn!(
+                    target: "tradestation::stream",
+                    error = %e,
+                    "shared stream failed"
+                );
+                let _ = sender.send(Err(e.to_string()));
+                return;
+            }
+            None => return,
+        }
+    }
+}
@@ -0,0 +1,143 @@
+use crate::Error;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// The amount of seconds in a day.
+pub(crate) const SECONDS_PER_DAY: i64 = 86_400;
+
+/// Parse a number TradeStation sends as a string.
+pub(crate) fn parse_number(value: &str) -> Result<f64, Error> {
+    value
+        .trim()
+        .parse::<f64>()
+        .map_err(|_| Error::InvalidNumber(value.to_string()))
+}
+
+/// The current unix epoch time in seconds.
+pub(crate) fn now_epoch_seconds() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs() as i64)
+        .unwrap_or_default()
+}
+
+/// The amount of days since the unix epoch for a civil (proleptic gregorian) date.
+pub(crate) fn days_from_civil(year: i64, month: u32, day: u32) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let month = month as i64;
+    let day_of_year =
+        (153 * (if month > 2 { month - 3 } else { month + 9 }) + 2) / 5 + day as i64 - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+
+    era * 146_097 + day_of_era - 719_468
+}
+
+/// The civil (proleptic gregorian) date `(year, month, day)` of an amount of days since the unix epoch.
+pub(crate) fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let days = days + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days - era * 146_097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = (day_of_year - (153 * month_index + 2) / 5 + 1) as u32;
+    let month = if month_index < 10 {
+        month_index + 3
+    } else {
+        month_index - 9
+    } as u32;
+    let year = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+
+    (year, month, day)
+}
+
+/// Format a number without trailing zeros (`"420"`, `"602.5"`), rounded
+/// to 6 decimals to remove float noise like `88.60000000000001`.
+pub(crate) fn format_number(value: f64) -> String {
+    let rounded = (value * 1e6).round() / 1e6;
+    // Avoid formatting `-0`
+    let rounded = if rounded == 0.0 { 0.0 } else { rounded };
+
+    format!("{rounded:.6}")
+        .trim_end_matches('0')
+        .trim_end_matches('.')
+        .to_string()
+}
+
+/// Parse an RFC3339 timestamp (`"2024-12-20T00:00:00Z"`), or a plain
+/// date (`"2024-12-20"`), into unix epoch seconds.
+///
+/// NOTE: Fractional seconds are ignored, and offsets (`+05:00`) are applied.
+pub(crate) fn parse_timestamp(value: &str) -> Result<i64, Error> {
+    let invalid = || Error::InvalidTimestamp(value.to_string());
+    let trimmed = value.trim();
+
+    let number = |range: std::ops::Range<usize>| -> Result<i64, Error> {
+        trimmed
+            .get(range)
+            .filter(|digits| digits.bytes().all(|byte| byte.is_ascii_digit()))
+            .and_then(|digits| digits.parse::<i64>().ok())
+            .ok_or_else(invalid)
+    };
+
+    if trimmed.len() < 10 || trimmed.as_bytes()[4] != b'-' || trimmed.as_bytes()[7] != b'-' {
+        return Err(invalid());
+    }
+    let (year, month, day) = (number(0..4)?, number(5..7)?, number(8..10)?);
+    if !(1..=12).contains(&month) || !(1..=31).contains(&day) {
+        return Err(invalid());
+    }
+    let date_seconds = days_from_civil(year, month as u32, day as u32) * SECONDS_PER_DAY;
+
+    if trimmed.len() == 10 {
+        return Ok(date_seconds);
+    }
+    if trimmed.len() < 19 || !matches!(trimmed.as_bytes()[10], b'T' | b't' | b' ') {
+        return Err(invalid());
+    }
+    let (hour, minute, second) = (number(11..13)?, number(14..16)?, number(17..19)?);
+    if hour > 23 || minute > 59 || second > 60 {
+        return Err(invalid());
+    }
+
+    // Skip any fractional seconds to find the offset
+    let rest = &trimmed[19..];
+    let offset = rest.trim_start_matches(|c: char| c == '.' || c.is_ascii_digit());
+    let offset_seconds = match offset {
+        "" | "Z" | "z" => 0,
+        _ if offset.len() == 6 && offset.starts_with(['+', '-']) => {
+            let offset_part = |range: std::ops::Range<usize>| {
+                offset
+                    .get(range)
+                    .and_then(|digits| digits.parse::<i64>().ok())
+                    .ok_or_else(invalid)
+            };
+            let (offset_hours, offset_minutes) = (offset_part(1..3)?, offset_part(4..6)?);
+            let seconds = offset_hours * 3600 + offset_minutes * 60;
+
+            if offset.starts_with('-') {
+                -seconds
+            } else {
+                seconds
+            }
+        }
+        _ => return Err(invalid()),
+    };
+
+    Ok(date_seconds + hour * 3600 + minute * 60 + second - offset_seconds)
+}
+
+/// Format unix epoch seconds as an RFC3339 UTC timestamp (`"2024-12-20T14:30:00Z"`).
+pub(crate) fn format_timestamp(epoch_seconds: i64) -> String {
+    let (year, month, day) = civil_from_days(epoch_seconds.div_euclid(SECONDS_PER_DAY));
+    let seconds_of_day = epoch_seconds.rem_euclid(SECONDS_PER_DAY);
+
+    format!(
+        "{year:04}-{month:02}-{day:02}T{:02}:{:02}:{:02}Z",
+        seconds_of_day / 3600,
+        seconds_of_day % 3600 / 60,
+        seconds_of_day % 60
+    )
+}
@@ -1,7 +1,12 @@
+use futures::StreamExt;
 use mockito::Server;
 use tradestation::{
-    accounting::{accounts::AccountType, Account, AssetType},
-    ClientBuilder, ClientEnvironment, Token,
+    accounting::{
+        accounts::AccountType, Account, AssetType, MultipleAccounts, OrderStateMachine,
+        OrderStatus, OrderStatusCategory,
+    },
+    responses::account::{StreamOrdersResp, StreamWalletsResp},
+    ClientBuilder, ClientEnvironment, Error, Token,
 };
 
 /// Account is the core abstraction around this API
@@ -481,3 +486,192 @@ fn test_get_positions_mocked() {
     // Ensure the mock was called
     mock.assert();
 }
+
+#[test]
+/// This test ensures that the parsing of
+/// getting `Wallet`(s) is correct.
+fn test_get_wallets_mocked() {
+    let mut account = generate_mock_account();
+    account.account_type = AccountType::Crypto;
+
+    // Mock the `wallets` endpoint with balances sent as numbers.
+    let mut server = Server::new();
+    let mock = server
+        .mock("GET", "/brokerage/accounts/11111111/wallets")
+        .with_status(200)
+        .with_body(
+            "{\"Wallets\":[{\"AccountID\":\"11111111\",\"Currency\":\"BTC\",\"Balance\":0.5,\"BalanceAvailableForTrading\":0.5,\"BalanceAvailableForWithdrawal\":0.25,\"Interest\":0,\"StakedBalance\":0},{\"AccountID\":\"11111111\",\"Currency\":\"ETH\",\"Balance\":\"2.75\",\"BalanceAvailableForTrading\":\"2.75\",\"BalanceAvailableForWithdrawal\":\"2.75\"}],\"Errors\":[]}"
+        )
+        .expect(2)
+        .create();
+
+    let rt = tokio::runtime::Runtime::new().unwrap();
+    rt.block_on(async {
+        let client = ClientBuilder::new()
+            .environment(ClientEnvironment::Mock(server.url()))
+            .with_token(Token::dummy())
+            .build()
+            .await
+            .unwrap();
+
+        // Make sure we can parse the mocked response into `Vec<Wallet>`
+        match account.get_wallets(&client).await {
+            Ok(wallets) => {
+                assert_eq!(wallets.len(), 2);
+                assert_eq!(wallets[0].account_id, account.account_id);
+                assert_eq!(wallets[0].currency, "BTC");
+                assert_eq!(wallets[0].balance, "0.5");
+                assert_eq!(
+                    wallets[0].balance_available_for_withdrawal.as_deref(),
+                    Some("0.25")
+                );
+                assert_eq!(wallets[1].balance, "2.75");
+                assert_eq!(wallets[1].staked_balance, None);
+            }
+            Err(e) => {
+                panic!("Failed to parse `Wallet`: {e:?}")
+            }
+        }
+
+        // Only the crypto account should have it's wallets fetched
+        let accounts = vec![account.clone(), generate_mock_account()];
+        let wallets = accounts.get_wallets(&client).await.unwrap();
+        assert_eq!(wallets.len(), 2);
+    });
+
+    // Ensure the mock was called
+    mock.assert();
+}
+
+#[test]
+/// This test ensures that the parsing of
+/// streaming `Wallet`(s) is correct.
+fn test_stream_wallets_mocked() {
+    let account = generate_mock_account();
+
+    // Mock the `stream/wallets` endpoint with newline
+    // delimited wallet updates, and a heartbeat.
+    let mut server = Server::new();
+    let mock = server
+        .mock("GET", "/brokerage/stream/accounts/11111111/wallets")
+        .with_status(200)
+        .with_body(
+            "{\"AccountID\":\"11111111\",\"Currency\":\"BTC\",\"Balance\":0.5,\"BalanceAvailableForTrading\":0.5}\n{\"StreamStatus\":\"EndSnapshot\"}\n{\"Heartbeat\":1,\"Timestamp\":\"2025-04-02T23:59:22Z\"}\n"
+        )
+        .create();
+
+    let rt = tokio::runtime::Runtime::new().unwrap();
+    rt.block_on(async {
+        let client = ClientBuilder::new()
+            .environment(ClientEnvironment::Mock(server.url()))
+            .with_token(Token::dummy())
+            .build()
+            .await
+            .unwrap();
+
+        let events: Vec<StreamWalletsResp> = account
+            .stream_wallets(&client)
+            .take(3)
+            .map(|event| event.unwrap())
+            .collect()
+            .await;
+
+        assert!(
+            matches!(&events[0], StreamWalletsResp::Wallet(wallet) if wallet.currency == "BTC" && wallet.balance == "0.5")
+        );
+        assert!(matches!(events[1], StreamWalletsResp::Status(_)));
+        assert!(matches!(events[2], StreamWalletsResp::Heartbeat(_)));
+    });
+
+    // Ensure the mock was called
+    mock.assert();
+}
+
+#[test]
+/// This test ensures that order statuses streamed for an `Order`
+/// are classified, and impossible transitions between them are flagged.
+fn test_order_state_machine_mocked() {
+    let account = generate_mock_account();
+    let order_json = |status: &str| {
+        format!(
+            "{{\"AccountID\":\"11111111\",\"CommissionFee\":\"0\",\"Currency\":\"USD\",\"Duration\":\"DAY\",\"Legs\":[{{\"QuantityOrdered\":\"100\",\"ExecQuantity\":\"0\",\"QuantityRemaining\":\"100\",\"BuyOrSell\":\"Buy\",\"Symbol\":\"PLTR\",\"AssetType\":\"STOCK\"}}],\"LimitPrice\":\"75.00\",\"OrderID\":\"1111111111\",\"OpenedDateTime\":\"2025-01-24T18:05:03Z\",\"OrderType\":\"Limit\",\"PriceUsedForBuyingPower\":\"75.00\",\"Status\":\"{status}\",\"StatusDescription\":\"\",\"ConversionRate\":\"1\",\"UnbundledRouteFee\":\"0\"}}"
+        )
+    };
+
+    // Mock the `stream/orders` endpoint with an order being filled,
+    // a fill correction, and then a late acknowledgement.
+    let mut server = Server::new();
+    let mock = server
+        .mock(
+            "GET",
+            "/brokerage/stream/accounts/11111111/orders/1111111111",
+        )
+        .with_status(200)
+        .with_body(format!(
+            "{}\n{}\n{}\n{}\n{}\n",
+            order_json("ACK"),
+            order_json("FPR"),
+            order_json("FLL"),
+            order_json("COR"),
+            order_json("ACK"),
+        ))
+        .create();
+
+    let rt = tokio::runtime::Runtime::new().unwrap();
+    rt.block_on(async {
+        let client = ClientBuilder::new()
+            .environment(ClientEnvironment::Mock(server.url()))
+            .with_token(Token::dummy())
+            .build()
+            .await
+            .unwrap();
+
+        let statuses: Vec<OrderStatus> = account
+            .stream_orders_by_id(&client, vec!["1111111111"])
+            .map(|event| match event.unwrap() {
+                StreamOrdersResp::Order(order) => order.status,
+                other => panic!("Expected an order, got {other:?}"),
+            })
+            .collect()
+            .await;
+
+        let mut state_machine = OrderStateMachine::new();
+        for status in &statuses[..4] {
+            state_machine
+                .advance(status.clone())
+                .expect("The order should be able to be filled");
+        }
+        assert_eq!(state_machine.status(), Some(&OrderStatus::FLL));
+        assert!(state_machine.is_terminal());
+
+        // A filled order can't be reopened
+        let error = state_machine
+            .advance(statuses[4].clone())
+            .expect_err("The late acknowledgement should be flagged");
+        assert!(matches!(error, Error::InvalidOrderTransition(_)));
+        assert_eq!(state_machine.status(), Some(&OrderStatus::FLL));
+    });
+
+    // Ensure the mock was called
+    mock.assert();
+
+    assert_eq!(
+        OrderStatus::FPR.category(),
+        OrderStatusCategory::PartiallyFilled
+    );
+    assert_eq!(
+        OrderStatus::RJC.category(),
+        OrderStatusCategory::Informational
+    );
+    assert!(OrderStatus::UCN.is_open());
+    assert!(OrderStatus::DOA.is_rejected() && OrderStatus::DOA.is_terminal());
+    assert!(OrderStatus::FLP.is_cancelled());
+    assert!(!OrderStateMachine::is_valid_transition(
+        &OrderStatus::FPR,
+        &OrderStatus::ACK
+    ));
+    assert_eq!(
+        OrderStatus::FLL.description(),
+        "Filled: the order was filled"
+    );
+}
@@ -1,13 +1,26 @@
+use futures::StreamExt;
 use mockito::Server;
+use std::time::Duration as StdDuration;
 use tradestation::{
-    accounting::AssetType,
-    execution::{Duration, TradeAction},
-    market_data::OptionSpreadType,
+    accounting::{
+        accounts::AccountType, orders::TrailingStop, Account, AssetType, LogicOp, OptionType,
+        Predicate,
+    },
+    execution::{
+        ActivationTriggerKey, AdvancedOrderOptionsBuilder, AssetTypeLimits, BracketGroupBuilder,
+        BracketOrderBuilder, BracketPrice, Duration, EmergencyFilter, MarketActivationRuleBuilder,
+        OcoGroupBuilder, OptionSpreadOrderBuilder, OrderEvent, PositionEffect, RiskPolicy,
+        RiskViolation, TradeAction,
+    },
+    market_data::{
+        Format, IncrementSchedule, IncrementStyle, OptionSpreadType, OptionSymbol,
+        OptionTradeAction, PriceFormat, RoundingMode,
+    },
     orders::{
-        Order, OrderRequestBuilder, OrderRequestLeg, OrderTicket, OrderTimeInForce, OrderType,
-        OrderUpdate,
+        BPWarningStatus, Order, OrderRelationship, OrderRequestBuilder, OrderRequestGroupBuilder,
+        OrderRequestLeg, OrderTicket, OrderTimeInForce, OrderType, OrderUpdate, PegValue,
     },
-    ClientBuilder, ClientEnvironment, Token,
+    ClientBuilder, ClientEnvironment, Error, Token,
 };
 
 #[test]
@@ -431,3 +444,2193 @@ fn test_cancel_order_mocked() {
     // Ensure the mock was called
     mock.assert();
 }
+
+#[test]
+/// This test ensures option leg symbols are only validated
+/// when building an `OrderRequest` opts into it.
+fn test_order_request_option_symbol_validation() {
+    let order_req = |symbol: &str, validate: bool| {
+        OrderRequestBuilder::new()
+            .account_id("11111111")
+            .legs(vec![OrderRequestLeg {
+                symbol: symbol.into(),
+                quantity: "1".into(),
+                trade_action: TradeAction::BuyToOpen,
+                strike_price: None,
+                option_type: None,
+                expiration_date: None,
+            }])
+            .order_type(OrderType::Market)
+            .time_in_force(OrderTimeInForce {
+                duration: Duration::DAY,
+                expiration: None,
+            })
+            .validate_option_symbols(validate)
+            .build()
+    };
+
+    // Symbols the parser doesn't know are sent as is by default
+    assert!(order_req("INTC 2504€1C23", false).is_ok());
+    assert!(matches!(
+        order_req("INTC 2504€1C23", true),
+        Err(Error::InvalidOptionSymbol(_))
+    ));
+
+    // Equity and futures option symbols pass validation
+    assert!(order_req("INTC 250411C23", true).is_ok());
+    assert!(order_req("CLZ24 P72.5", true).is_ok());
+
+    // Legs built from an `OptionSymbol` are always valid
+    let leg = OptionSymbol::parse("INTC 250411C23")
+        .unwrap()
+        .order_leg(1, TradeAction::BuyToOpen);
+    assert_eq!(leg.symbol, "INTC 250411C23");
+    assert!(order_req(&leg.symbol, true).is_ok());
+}
+
+#[test]
+/// This test ensures prices are rounded, validated, and formatted
+/// onto the ticks of a symbol's `PriceFormat`, and that orders and
+/// order updates with prices off a tick are rejected.
+fn test_price_format_ticks() {
+    // The price format of an option, with a tick of 0.01 under 3.00 and 0.05 from 3.00 and up
+    let option_format = PriceFormat {
+        format: Format::Decimal,
+        decimals: Some("2".into()),
+        fraction: None,
+        sub_fraction: None,
+        increment_style: IncrementStyle::Schedule,
+        increment: None,
+        increment_schedule: Some(vec![
+            IncrementSchedule {
+                increment: "0.05".into(),
+                starts_at: "3".into(),
+            },
+            IncrementSchedule {
+                increment: "0.01".into(),
+                starts_at: "0".into(),
+            },
+        ]),
+        point_value: "100".into(),
+    };
+
+    // Schedule tier boundaries
+    assert_eq!(option_format.tick_size(0.0).unwrap(), 0.01);
+    assert_eq!(option_format.tick_size(2.99).unwrap(), 0.01);
+    assert_eq!(option_format.tick_size(3.0).unwrap(), 0.05);
+    assert_eq!(option_format.tick_size(25.0).unwrap(), 0.05);
+    assert_eq!(option_format.tick_size(-3.5).unwrap(), 0.05);
+
+    // Rounding up, down, and to the nearest tick on either side of a boundary
+    for (price, mode, expected) in [
+        (2.333, RoundingMode::Up, 2.34),
+        (2.333, RoundingMode::Down, 2.33),
+        (2.335, RoundingMode::Nearest, 2.34),
+        (2.995, RoundingMode::Up, 3.0),
+        (2.995, RoundingMode::Down, 2.99),
+        (3.01, RoundingMode::Up, 3.05),
+        (3.01, RoundingMode::Down, 3.0),
+        (3.01, RoundingMode::Nearest, 3.0),
+        (3.03, RoundingMode::Nearest, 3.05),
+        (3.1, RoundingMode::Up, 3.1),
+        (3.1, RoundingMode::Down, 3.1),
+        // Credits round their size the other way, so up is still towards positive
+        (-3.12, RoundingMode::Up, -3.1),
+        (-3.12, RoundingMode::Down, -3.15),
+        (-3.12, RoundingMode::Nearest, -3.1),
+    ] {
+        assert_eq!(
+            option_format.round_price(price, mode).unwrap(),
+            expected,
+            "{price} rounded {mode:?}"
+        );
+    }
+
+    assert!(option_format.validate_price("2.99").is_ok());
+    assert!(option_format.validate_price("3.05").is_ok());
+    assert!(option_format.validate_price("-1.16").is_ok());
+    assert!(matches!(
+        option_format.validate_price("3.12"),
+        Err(Error::PriceNotOnTick(_))
+    ));
+    assert!(matches!(
+        option_format.validate_price("2.995"),
+        Err(Error::PriceNotOnTick(_))
+    ));
+    assert!(matches!(
+        option_format.validate_price("three"),
+        Err(Error::InvalidNumber(_))
+    ));
+    assert_eq!(option_format.format_price(3.1).unwrap(), "3.10");
+    assert_eq!(option_format.format_price(-1.16).unwrap(), "-1.16");
+
+    // The price format of corn futures, quoted in eighths
+    let fraction_format = PriceFormat {
+        format: Format::Fraction,
+        decimals: None,
+        fraction: Some("8".into()),
+        sub_fraction: None,
+        increment_style: IncrementStyle::Simple,
+        increment: Some("0.25".into()),
+        increment_schedule: None,
+        point_value: "50".into(),
+    };
+    assert_eq!(fraction_format.format_price(534.5).unwrap(), "534 4/8");
+    assert_eq!(fraction_format.format_price(534.125).unwrap(), "534 1/8");
+    assert_eq!(fraction_format.format_price(534.0).unwrap(), "534");
+    assert_eq!(fraction_format.format_price(-2.5).unwrap(), "-2 4/8");
+    assert_eq!(fraction_format.format_price(534.999).unwrap(), "535");
+
+    // The price format of 5 year treasury note futures, quoted in quarters of a 32nd
+    let sub_fraction_format = PriceFormat {
+        format: Format::SubFraction,
+        decimals: None,
+        fraction: Some("32".into()),
+        sub_fraction: Some("4".into()),
+        increment_style: IncrementStyle::Simple,
+        increment: Some("0.0078125".into()),
+        increment_schedule: None,
+        point_value: "1000".into(),
+    };
+    assert_eq!(sub_fraction_format.tick_size(110.0).unwrap(), 0.0078125);
+    assert_eq!(sub_fraction_format.format_price(110.5).unwrap(), "110'16.0");
+    assert_eq!(
+        sub_fraction_format.format_price(110.5078125).unwrap(),
+        "110'16.2"
+    );
+    assert_eq!(
+        sub_fraction_format.format_price(110.515625).unwrap(),
+        "110'16.5"
+    );
+    assert_eq!(
+        sub_fraction_format.format_price(125.9296875).unwrap(),
+        "125'29.7"
+    );
+    assert_eq!(
+        sub_fraction_format
+            .round_price(110.51, RoundingMode::Nearest)
+            .unwrap(),
+        110.5078125
+    );
+
+    // A simple increment must have an increment
+    let missing_increment = PriceFormat {
+        increment: None,
+        ..sub_fraction_format.clone()
+    };
+    assert!(matches!(
+        missing_increment.tick_size(110.0),
+        Err(Error::InvalidPriceFormat(_))
+    ));
+
+    // Orders are validated against the price format when building
+    let order_req = |limit_price: &str, stop_price: &str| {
+        OrderRequestBuilder::new()
+            .account_id("11111111")
+            .symbol("TLT 250409P90")
+            .trade_action(TradeAction::BuyToOpen)
+            .quantity("1")
+            .order_type(OrderType::StopLimit)
+            .limit_price(limit_price)
+            .stop_price(stop_price)
+            .price_format(option_format.clone())
+            .time_in_force(OrderTimeInForce {
+                duration: Duration::DAY,
+                expiration: None,
+            })
+            .build()
+    };
+    assert!(order_req("3.15", "2.99").is_ok());
+    assert!(matches!(
+        order_req("3.12", "2.99"),
+        Err(Error::PriceNotOnTick(_))
+    ));
+    assert!(matches!(
+        order_req("3.15", "3.01"),
+        Err(Error::PriceNotOnTick(_))
+    ));
+
+    // As are order updates
+    let update = OrderUpdate::new().limit_price("3.12").stop_price("2.995");
+    assert!(matches!(
+        update.validate_prices(&option_format),
+        Err(Error::PriceNotOnTick(_))
+    ));
+
+    let rounded_up = update
+        .clone()
+        .round_prices(&option_format, RoundingMode::Up)
+        .unwrap();
+    assert_eq!(rounded_up.limit_price.as_deref(), Some("3.15"));
+    assert_eq!(rounded_up.stop_price.as_deref(), Some("3"));
+    assert!(rounded_up.validate_prices(&option_format).is_ok());
+
+    let rounded_down = update
+        .round_prices(&option_format, RoundingMode::Down)
+        .unwrap();
+    assert_eq!(rounded_down.limit_price.as_deref(), Some("3.1"));
+    assert_eq!(rounded_down.stop_price.as_deref(), Some("2.99"));
+    assert!(rounded_down.validate_prices(&option_format).is_ok());
+}
+
+#[test]
+/// This test ensures the option spread order builders
+/// build the same legs as the real iron condor order
+/// confirmed in `test_stock_option_confirm_order_mocked`.
+fn test_option_spread_order_builders() {
+    let expiration = OptionSymbol::parse("INTC 250411P18").unwrap().expiration;
+    let strikes = ["18", "20.5", "23", "25.5"].map(String::from);
+
+    let order_req = OptionSpreadOrderBuilder::from_strikes(
+        OptionSpreadType::IronCondor,
+        "INTC",
+        &[expiration],
+        OptionType::Put,
+        &strikes,
+    )
+    .unwrap()
+    .account_id("11111111")
+    .quantity(5)
+    .order_type(OrderType::Limit)
+    .limit_price("-1.16")
+    .time_in_force(OrderTimeInForce {
+        duration: Duration::GTC,
+        expiration: None,
+    })
+    .build()
+    .unwrap();
+
+    let legs = order_req.legs.unwrap();
+    let expected = [
+        ("INTC 250411P18", TradeAction::BuyToOpen),
+        ("INTC 250411P20.5", TradeAction::SellToOpen),
+        ("INTC 250411C23", TradeAction::SellToOpen),
+        ("INTC 250411C25.5", TradeAction::BuyToOpen),
+    ];
+    assert_eq!(legs.len(), expected.len());
+    for (leg, (symbol, trade_action)) in legs.iter().zip(expected) {
+        assert_eq!(leg.symbol, symbol);
+        assert_eq!(
+            std::mem::discriminant(&leg.trade_action),
+            std::mem::discriminant(&trade_action)
+        );
+        assert_eq!(leg.quantity, "5");
+    }
+
+    // Closing a bought calendar sells it, buying back the near leg and selling the far leg
+    let order_req = OptionSpreadOrderBuilder::calendar(
+        OptionSymbol::parse("INTC 250411C23").unwrap(),
+        OptionSymbol::parse("INTC 250516C23").unwrap(),
+    )
+    .account_id("11111111")
+    .trade_action(OptionTradeAction::Sell)
+    .position_effect(PositionEffect::Close)
+    .order_type(OrderType::Market)
+    .time_in_force(OrderTimeInForce {
+        duration: Duration::DAY,
+        expiration: None,
+    })
+    .build()
+    .unwrap();
+
+    let legs = order_req.legs.unwrap();
+    assert!(matches!(legs[0].trade_action, TradeAction::BuyToClose));
+    assert!(matches!(legs[1].trade_action, TradeAction::SellToClose));
+
+    // An iron condor's puts must be below it's calls
+    assert!(OptionSpreadOrderBuilder::iron_condor(
+        OptionSymbol::parse("INTC 250411C18").unwrap(),
+        OptionSymbol::parse("INTC 250411C20.5").unwrap(),
+        OptionSymbol::parse("INTC 250411P23").unwrap(),
+        OptionSymbol::parse("INTC 250411P25.5").unwrap(),
+    )
+    .account_id("11111111")
+    .order_type(OrderType::Market)
+    .time_in_force(OrderTimeInForce {
+        duration: Duration::DAY,
+        expiration: None,
+    })
+    .build()
+    .is_err());
+
+    // A leg quantity which doesn't fit is an error, rather than overflowing
+    assert!(matches!(
+        OptionSpreadOrderBuilder::butterfly(
+            OptionSymbol::parse("INTC 250411C18").unwrap(),
+            OptionSymbol::parse("INTC 250411C20.5").unwrap(),
+            OptionSymbol::parse("INTC 250411C23").unwrap(),
+        )
+        .account_id("11111111")
+        .quantity(u32::MAX / 2 + 1)
+        .order_type(OrderType::Market)
+        .time_in_force(OrderTimeInForce {
+            duration: Duration::DAY,
+            expiration: None,
+        })
+        .build(),
+        Err(Error::QuantityTooLarge(_))
+    ));
+}
+
+#[test]
+/// This test ensures the bracket order builder offsets the
+/// exits from the entry price in the correct direction, and
+/// that mismatched exit orders fail validation.
+fn test_bracket_order_builder() {
+    // The price format of `@ES`, which trades in ticks of 0.25
+    let price_format = PriceFormat {
+        format: Format::Decimal,
+        decimals: Some("2".into()),
+        fraction: None,
+        sub_fraction: None,
+        increment_style: IncrementStyle::Simple,
+        increment: Some("0.25".into()),
+        increment_schedule: None,
+        point_value: "50".into(),
+    };
+
+    // Short 2 `@ESZ24` at market, with a 20 tick target and a 1% stop loss
+    let order_req = BracketOrderBuilder::new()
+        .account_id("11111111F")
+        .symbol("ESZ24")
+        .trade_action(TradeAction::Sell)
+        .quantity("2")
+        .order_type(OrderType::Market)
+        .reference_price("5000.00")
+        .take_profit(BracketPrice::Ticks(20))
+        .stop_loss(BracketPrice::Percent(1.0))
+        .price_format(price_format.clone())
+        .time_in_force(OrderTimeInForce {
+            duration: Duration::DAY,
+            expiration: None,
+        })
+        .exit_time_in_force(OrderTimeInForce {
+            duration: Duration::GTC,
+            expiration: None,
+        })
+        .build()
+        .unwrap();
+
+    let osos = order_req.osos.as_ref().unwrap();
+    assert_eq!(osos.len(), 1);
+    assert!(matches!(osos[0].r#type, OrderRelationship::BRK));
+
+    let (take_profit, stop_loss) = (&osos[0].orders[0], &osos[0].orders[1]);
+    assert!(matches!(take_profit.order_type, OrderType::Limit));
+    assert_eq!(take_profit.limit_price.as_deref(), Some("4995"));
+    assert!(matches!(stop_loss.order_type, OrderType::StopMarket));
+    assert_eq!(stop_loss.stop_price.as_deref(), Some("5050"));
+    for exit in &osos[0].orders {
+        assert_eq!(exit.trade_action, Some(TradeAction::Buy));
+        assert_eq!(exit.quantity.as_deref(), Some("2"));
+        assert!(matches!(exit.time_in_force.duration, Duration::GTC));
+    }
+
+    // A trailing stop on it's own is just sent by the entry
+    let order_req = BracketOrderBuilder::new()
+        .account_id("11111111")
+        .symbol("MSFT")
+        .trade_action(TradeAction::Buy)
+        .quantity("10")
+        .order_type(OrderType::Market)
+        .trailing_stop(TrailingStop {
+            amount: Some("2.50".into()),
+            percent: None,
+        })
+        .time_in_force(OrderTimeInForce {
+            duration: Duration::DAY,
+            expiration: None,
+        })
+        .build()
+        .unwrap();
+
+    let oso = &order_req.osos.as_ref().unwrap()[0];
+    assert!(matches!(oso.r#type, OrderRelationship::NORMAL));
+    let trailing_stop = oso.orders[0]
+        .advanced_options
+        .as_ref()
+        .and_then(|options| options.trailing_stop.as_ref())
+        .unwrap();
+    assert_eq!(trailing_stop.amount.as_deref(), Some("2.50"));
+
+    // Offsets need an entry price, and ticks need a price format
+    let market_entry = || {
+        BracketOrderBuilder::new()
+            .account_id("11111111")
+            .symbol("MSFT")
+            .trade_action(TradeAction::Buy)
+            .quantity("10")
+            .order_type(OrderType::Market)
+            .time_in_force(OrderTimeInForce {
+                duration: Duration::DAY,
+                expiration: None,
+            })
+    };
+    assert!(market_entry()
+        .take_profit(BracketPrice::Percent(2.0))
+        .build()
+        .is_err());
+    assert!(market_entry()
+        .reference_price("420.00")
+        .take_profit(BracketPrice::Ticks(10))
+        .build()
+        .is_err());
+
+    // Exits have to close the entry's position
+    let mut order_req = market_entry()
+        .take_profit(BracketPrice::Price("430.00".into()))
+        .build()
+        .unwrap();
+    assert!(order_req.validate_bracket().is_ok());
+    order_req.osos.as_mut().unwrap()[0].orders[0].trade_action = Some(TradeAction::SellShort);
+    assert!(order_req.validate_bracket().is_err());
+    order_req.osos.as_mut().unwrap()[0].orders[0].trade_action = Some(TradeAction::Sell);
+    order_req.osos.as_mut().unwrap()[0].orders[0].quantity = Some("5".into());
+    assert!(order_req.validate_bracket().is_err());
+}
+
+#[test]
+/// This test ensures the OCO and bracket group builders
+/// reject groups breaking the rules of their group type.
+fn test_order_group_builders() {
+    let order = |account_id: &str, trade_action, order_type| {
+        OrderRequestBuilder::new()
+            .account_id(account_id)
+            .symbol("XLRE")
+            .trade_action(trade_action)
+            .quantity("1000")
+            .order_type(order_type)
+            .time_in_force(OrderTimeInForce {
+                duration: Duration::GTC,
+                expiration: None,
+            })
+    };
+    let entry = || {
+        order("11111111", TradeAction::SellShort, OrderType::Market)
+            .build()
+            .unwrap()
+    };
+    let take_profit = |trade_action| {
+        order("11111111", trade_action, OrderType::Limit)
+            .limit_price("35.75")
+            .build()
+            .unwrap()
+    };
+    let stop_loss = |trade_action| {
+        order("11111111", trade_action, OrderType::StopMarket)
+            .stop_price("46.50")
+            .build()
+            .unwrap()
+    };
+
+    // The same bracket as `OrderRequestGroup::place`'s example
+    let group = BracketGroupBuilder::new()
+        .entry(entry())
+        .take_profit(take_profit(TradeAction::BuyToCover))
+        .stop_loss(stop_loss(TradeAction::BuyToCover))
+        .build()
+        .unwrap();
+    assert_eq!(group.order_requests.len(), 3);
+    assert!(matches!(group.group_type, OrderRelationship::BRK));
+    assert!(group.validate().is_ok());
+
+    // The exits have to close the entry's position
+    assert!(BracketGroupBuilder::new()
+        .entry(entry())
+        .take_profit(take_profit(TradeAction::Sell))
+        .stop_loss(stop_loss(TradeAction::Sell))
+        .build()
+        .is_err());
+
+    // A bracket needs both a take profit and a stop loss
+    assert!(BracketGroupBuilder::new()
+        .take_profit(take_profit(TradeAction::BuyToCover))
+        .take_profit(take_profit(TradeAction::BuyToCover))
+        .build()
+        .is_err());
+
+    // OCO orders can be on either side, but need the same account and at least 2 orders
+    let breakout = |account_id: &str, trade_action, price: &str| {
+        order(account_id, trade_action, OrderType::StopMarket)
+            .stop_price(price)
+            .build()
+            .unwrap()
+    };
+    let oco = OcoGroupBuilder::new()
+        .order_request(breakout("11111111", TradeAction::Buy, "40.00"))
+        .order_request(breakout("11111111", TradeAction::SellShort, "30.00"))
+        .build()
+        .unwrap();
+    assert!(matches!(oco.group_type, OrderRelationship::OCO));
+    assert!(OcoGroupBuilder::new()
+        .order_request(breakout("11111111", TradeAction::Buy, "40.00"))
+        .order_request(breakout("22222222", TradeAction::SellShort, "30.00"))
+        .build()
+        .is_err());
+    assert!(OcoGroupBuilder::new()
+        .order_request(breakout("11111111", TradeAction::Buy, "40.00"))
+        .build()
+        .is_err());
+
+    // OCO orders can't be market orders, which would fill and cancel the rest right away
+    assert!(OcoGroupBuilder::new()
+        .order_request(breakout("11111111", TradeAction::Buy, "40.00"))
+        .order_request(entry())
+        .build()
+        .is_err());
+
+    // OCO exits for the same position need the same trade action and quantity
+    assert!(OcoGroupBuilder::new()
+        .order_request(take_profit(TradeAction::BuyToCover))
+        .order_request(stop_loss(TradeAction::BuyToCover))
+        .build()
+        .is_ok());
+    assert!(OcoGroupBuilder::new()
+        .order_request(take_profit(TradeAction::BuyToCover))
+        .order_request(stop_loss(TradeAction::Buy))
+        .build()
+        .is_err());
+    assert!(OcoGroupBuilder::new()
+        .order_request(take_profit(TradeAction::BuyToCover))
+        .order_request(
+            order("11111111", TradeAction::BuyToCover, OrderType::StopMarket)
+                .stop_price("46.50")
+                .quantity("500")
+                .build()
+                .unwrap()
+        )
+        .build()
+        .is_err());
+
+    // OCO orders can't close both a long and a short position in the same symbol
+    assert!(OcoGroupBuilder::new()
+        .order_request(take_profit(TradeAction::BuyToCover))
+        .order_request(stop_loss(TradeAction::SellToClose))
+        .build()
+        .is_err());
+}
+
+#[test]
+/// This test ensures order groups are validated before
+/// they're confirmed or placed, so invalid groups aren't sent.
+fn test_invalid_order_group_not_sent_mocked() {
+    let mut server = Server::new();
+    let confirm_mock = server
+        .mock("POST", "/orderexecution/ordergroupconfirm")
+        .expect(0)
+        .create();
+    let place_mock = server
+        .mock("POST", "/orderexecution/ordergroups")
+        .expect(0)
+        .create();
+
+    let rt = tokio::runtime::Runtime::new().unwrap();
+    rt.block_on(async {
+        let client = ClientBuilder::new()
+            .environment(ClientEnvironment::Mock(server.url()))
+            .with_token(Token::dummy())
+            .build()
+            .await
+            .unwrap();
+
+        // An OCO group with a market order, built without the `OcoGroupBuilder`'s validation
+        let order = |trade_action, order_type| {
+            OrderRequestBuilder::new()
+                .account_id("11111111")
+                .symbol("MSFT")
+                .trade_action(trade_action)
+                .quantity("10")
+                .order_type(order_type)
+                .time_in_force(OrderTimeInForce {
+                    duration: Duration::GTC,
+                    expiration: None,
+                })
+                .build()
+                .unwrap()
+        };
+        let group = OrderRequestGroupBuilder::new()
+            .order_requests(vec![
+                order(TradeAction::Sell, OrderType::Limit),
+                order(TradeAction::Sell, OrderType::Market),
+            ])
+            .group_type(OrderRelationship::OCO)
+            .build()
+            .unwrap();
+
+        assert!(matches!(
+            Order::confirm_group(&client, &group).await,
+            Err(Error::InvalidOrderGroup(_))
+        ));
+        assert!(matches!(
+            group.clone().confirm(&client).await,
+            Err(Error::InvalidOrderGroup(_))
+        ));
+        assert!(matches!(
+            Order::place_group(&client, &group).await,
+            Err(Error::InvalidOrderGroup(_))
+        ));
+        assert!(matches!(
+            group.place(&client).await,
+            Err(Error::InvalidOrderGroup(_))
+        ));
+    });
+
+    // Ensure neither endpoint was called
+    confirm_mock.assert();
+    place_mock.assert();
+}
+
+#[test]
+/// This test ensures market activation rules are chained
+/// in order, and their trigger keys are validated against
+/// the activation triggers TradeStation supports.
+fn test_market_activation_rule_builder_mocked() {
+    let mut server = Server::new();
+    let mock = server
+        .mock("GET", "/orderexecution/activationtriggers")
+        .with_status(200)
+        .with_body(
+            "{\"ActivationTriggers\":[{\"Key\":\"STT\",\"Name\":\"Single Trade Tick\",\"Description\":\"One trade tick must print within your stop price to trigger your stop.\"},{\"Key\":\"DTT\",\"Name\":\"Double Trade Tick\",\"Description\":\"Two consecutive trade ticks must print within your stop price to trigger your stop.\"}]}"
+        )
+        .expect(2)
+        .create();
+
+    let rt = tokio::runtime::Runtime::new().unwrap();
+    rt.block_on(async {
+        let client = ClientBuilder::new()
+            .environment(ClientEnvironment::Mock(server.url()))
+            .with_token(Token::dummy())
+            .build()
+            .await
+            .unwrap();
+
+        let rules = MarketActivationRuleBuilder::new()
+            .rule("SPY", Predicate::Gt, "600.00")
+            .and("QQQ", Predicate::Gte, "520.00")
+            .trigger_key(ActivationTriggerKey::DTT)
+            .build_validated(&client)
+            .await
+            .unwrap();
+        assert_eq!(rules.len(), 2);
+        assert_eq!(rules[1].logic_operator, Some(LogicOp::And));
+
+        // The rules serialize with TradeStation's field names
+        let json = serde_json::to_value(&rules[1]).unwrap();
+        assert_eq!(json["RuleType"], "Price");
+        assert_eq!(json["TriggerKey"], "DTT");
+        assert_eq!(json["LogicOperator"], "And");
+
+        // `TTT` isn't one of the mocked activation triggers
+        assert!(MarketActivationRuleBuilder::new()
+            .rule("SPY", Predicate::Gt, "600.00")
+            .trigger_key(ActivationTriggerKey::TTT)
+            .build_validated(&client)
+            .await
+            .is_err());
+    });
+
+    // Rules have to start with `rule` before being chained
+    assert!(MarketActivationRuleBuilder::new()
+        .and("SPY", Predicate::Gt, "600.00")
+        .build()
+        .is_err());
+    assert!(MarketActivationRuleBuilder::new()
+        .rule("SPY", Predicate::Gt, "six hundred")
+        .build()
+        .is_err());
+
+    // Ensure the mock was called
+    mock.assert();
+}
+
+#[test]
+/// This test ensures unset advanced options are left out of
+/// requests, and conflicting options fail to build the order.
+fn test_advanced_order_options_builder() {
+    let order = |order_type| {
+        OrderRequestBuilder::new()
+            .account_id("11111111")
+            .symbol("MSFT")
+            .trade_action(TradeAction::Buy)
+            .quantity("100")
+            .order_type(order_type)
+            .limit_price("420.00")
+            .time_in_force(OrderTimeInForce {
+                duration: Duration::DAY,
+                expiration: None,
+            })
+    };
+
+    // Only the options which are set are sent
+    let options = AdvancedOrderOptionsBuilder::new()
+        .all_or_none()
+        .peg_value(PegValue::Mid)
+        .build()
+        .unwrap();
+    let order_req = order(OrderType::Limit)
+        .advanced_options(options.clone())
+        .build()
+        .unwrap();
+    let json = serde_json::to_value(order_req.advanced_options.unwrap()).unwrap();
+    assert_eq!(
+        json,
+        serde_json::json!({ "AllOrNone": true, "PegValue": "MID" })
+    );
+
+    // Pegs are only for limit orders
+    assert!(order(OrderType::StopLimit)
+        .advanced_options(options)
+        .build()
+        .is_err());
+
+    // The shown quantity has to hide some of the order
+    let show_only = |quantity: &str| {
+        order(OrderType::Limit)
+            .advanced_options(
+                AdvancedOrderOptionsBuilder::new()
+                    .show_only_quantity(quantity)
+                    .build()
+                    .unwrap(),
+            )
+            .build()
+    };
+    assert!(show_only("10").is_ok());
+    assert!(show_only("100").is_err());
+
+    // Trailing stops are only for stop market orders, by amount or percent
+    let trailing_stop = |order_type, amount: Option<&str>, percent: Option<&str>| {
+        order(order_type)
+            .advanced_options(
+                AdvancedOrderOptionsBuilder::new()
+                    .trailing_stop(TrailingStop {
+                        amount: amount.map(String::from),
+                        percent: percent.map(String::from),
+                    })
+                    .build()
+                    .unwrap(),
+            )
+            .build()
+    };
+    assert!(trailing_stop(OrderType::StopMarket, Some("1.50"), None).is_ok());
+    assert!(trailing_stop(OrderType::Limit, Some("1.50"), None).is_err());
+    assert!(trailing_stop(OrderType::StopMarket, Some("1.50"), Some("2")).is_err());
+
+    // Adding liquidity requires book only
+    assert!(AdvancedOrderOptionsBuilder::new()
+        .add_liquidity()
+        .build()
+        .is_err());
+    assert!(AdvancedOrderOptionsBuilder::new()
+        .add_liquidity()
+        .book_only()
+        .build()
+        .is_ok());
+}
+
+#[test]
+/// This test ensures orders breaking the `RiskPolicy` attached
+/// to the client are blocked, and orders within it are placed.
+fn test_risk_policy_mocked() {
+    let mut server = Server::new();
+    let symbols_mock = server
+        .mock("GET", "/marketdata/symbols/TLT")
+        .with_status(200)
+        .with_body(
+            "{\"Symbols\":[{\"AssetType\":\"STOCK\",\"Country\":\"United States\",\"Currency\":\"USD\",\"Description\":\"iShares 20+ Year Treasury Bond ETF\",\"Exchange\":\"NASDAQ\",\"Symbol\":\"TLT\",\"Root\":\"TLT\",\"PriceFormat\":{\"Format\":\"Decimal\",\"Decimals\":\"2\",\"IncrementStyle\":\"Simple\",\"Increment\":\"0.01\",\"PointValue\":\"1\"},\"QuantityFormat\":{\"Format\":\"Decimal\",\"Decimals\":\"0\",\"IncrementStyle\":\"Simple\",\"Increment\":\"1\",\"MinimumTradeQuantity\":\"1\"}}],\"Errors\":[]}"
+        )
+        .expect(1)
+        .create();
+    let quotes_mock = server
+        .mock("GET", "/marketdata/quotes/TLT")
+        .with_status(200)
+        .with_body(
+            "{\"Quotes\":[{\"Symbol\":\"TLT\",\"Open\":\"92.37\",\"High\":\"92.37\",\"Low\":\"90.66\",\"PreviousClose\":\"91.49\",\"Last\":\"92.7001\",\"Ask\":\"92.73\",\"AskSize\":\"100\",\"Bid\":\"92.67\",\"BidSize\":\"500\",\"NetChange\":\"1.2101\",\"NetChangePct\":\"1.32265821401246\",\"High52Week\":\"101.64\",\"High52WeekTimestamp\":\"2024-09-17T00:00:00Z\",\"Low52Week\":\"84.89\",\"Low52WeekTimestamp\":\"2025-01-14T00:00:00Z\",\"Volume\":\"43488407\",\"PreviousVolume\":\"39800072\",\"Close\":\"91.43\",\"DailyOpenInterest\":\"0\",\"TradeTime\":\"2025-04-02T23:59:22Z\",\"TickSizeTier\":\"0\",\"MarketFlags\":{\"IsDelayed\":false,\"IsHardToBorrow\":false,\"IsBats\":false,\"IsHalted\":false},\"LastSize\":\"200\",\"LastVenue\":\"TRF\",\"VWAP\":\"91.3955921617407\"}]}"
+        )
+        .expect(2)
+        .create();
+    let orders_mock = server
+        .mock("GET", "/brokerage/accounts/11111111/orders")
+        .with_status(200)
+        .with_body("{\"Orders\":[],\"Errors\":[]}")
+        .expect(2)
+        .create();
+    let place_mock = server
+        .mock("POST", "/orderexecution/orders")
+        .with_status(200)
+        .with_body(
+            "{\"Orders\":[{\"Message\":\"Sent order: Buy 100 TLT @ 92.50 Limit\",\"OrderID\":\"5555555555\"}]}"
+        )
+        .expect(1)
+        .create();
+
+    let rt = tokio::runtime::Runtime::new().unwrap();
+    rt.block_on(async {
+        let client = ClientBuilder::new()
+            .environment(ClientEnvironment::Mock(server.url()))
+            .with_token(Token::dummy())
+            .build()
+            .await
+            .unwrap();
+
+        let order = |quantity: &str, limit_price: &str| {
+            OrderRequestBuilder::new()
+                .account_id("11111111")
+                .symbol("TLT")
+                .trade_action(TradeAction::Buy)
+                .quantity(quantity)
+                .order_type(OrderType::Limit)
+                .limit_price(limit_price)
+                .time_in_force(OrderTimeInForce {
+                    duration: Duration::DAY,
+                    expiration: None,
+                })
+                .build()
+                .unwrap()
+        };
+        let blocked_by = |policy: RiskPolicy, order_req| {
+            let client = client.clone();
+            async move {
+                client.set_risk_policy(policy);
+                match Order::place(&client, &order_req).await {
+                    Err(Error::RiskViolation(violation)) => violation,
+                    other => panic!("Expected a risk violation, but got {other:?}"),
+                }
+            }
+        };
+
+        assert_eq!(
+            blocked_by(RiskPolicy::new().deny_symbol("tlt"), order("100", "92.50")).await,
+            RiskViolation::SymbolDenied("TLT".into())
+        );
+        assert_eq!(
+            blocked_by(RiskPolicy::new().allow_symbol("IEF"), order("100", "92.50")).await,
+            RiskViolation::SymbolNotAllowed("TLT".into())
+        );
+        assert_eq!(
+            blocked_by(
+                RiskPolicy::new()
+                    .max_quantity(1_000.0)
+                    .max_quantity_for_symbol("TLT", 50.0),
+                order("100", "92.50")
+            )
+            .await,
+            RiskViolation::QuantityExceeded {
+                symbol: "TLT".into(),
+                quantity: 100.0,
+                max: 50.0,
+            }
+        );
+        assert!(matches!(
+            blocked_by(
+                RiskPolicy::new().price_collar_percent(5.0),
+                order("100", "80.00")
+            )
+            .await,
+            RiskViolation::PriceOutsideCollar { price, .. } if price == 80.0
+        ));
+        assert_eq!(
+            blocked_by(
+                RiskPolicy::new().max_order_notional(5_000.0),
+                order("100", "92.50")
+            )
+            .await,
+            RiskViolation::NotionalExceeded {
+                symbol: "TLT".into(),
+                notional: 9_250.0,
+                max: 5_000.0,
+            }
+        );
+        assert_eq!(
+            blocked_by(
+                RiskPolicy::new().asset_type_limits(
+                    AssetType::Stock,
+                    AssetTypeLimits {
+                        max_quantity: Some(10.0),
+                        max_order_notional: None,
+                    },
+                ),
+                order("100", "92.50")
+            )
+            .await,
+            RiskViolation::AssetTypeQuantityExceeded {
+                asset_type: AssetType::Stock,
+                symbol: "TLT".into(),
+                quantity: 100.0,
+                max: 10.0,
+            }
+        );
+        assert_eq!(
+            blocked_by(RiskPolicy::new().max_open_orders(0), order("100", "92.50")).await,
+            RiskViolation::OpenOrdersExceeded {
+                account_id: "11111111".into(),
+                open_orders: 0,
+                new_orders: 1,
+                max: 0,
+            }
+        );
+
+        // Orders within every rule are placed
+        client.set_risk_policy(
+            RiskPolicy::new()
+                .allow_symbol("TLT")
+                .max_quantity(100.0)
+                .max_order_notional(10_000.0)
+                .price_collar_percent(5.0)
+                .max_open_orders(1),
+        );
+        let tickets = Order::place(&client, &order("100", "92.50"))
+            .await
+            .expect("Order within the risk policy should be placed");
+        assert_eq!(tickets[0].order_id, "5555555555");
+
+        // Clearing the policy stops checking orders
+        client.clear_risk_policy();
+        assert!(client.risk_policy().is_none());
+    });
+
+    // Symbol details are cached across every check
+    symbols_mock.assert();
+    quotes_mock.assert();
+    orders_mock.assert();
+    place_mock.assert();
+}
+
+#[test]
+/// This test ensures orders the risk policy needs a last price to check
+/// are blocked, rather than passed, when the symbol has no quote.
+fn test_risk_policy_without_quote_mocked() {
+    let mut server = Server::new();
+    let symbols_mock = server
+        .mock("GET", "/marketdata/symbols/TLT")
+        .with_status(200)
+        .with_body(
+            "{\"Symbols\":[{\"AssetType\":\"STOCK\",\"Country\":\"United States\",\"Currency\":\"USD\",\"Description\":\"iShares 20+ Year Treasury Bond ETF\",\"Exchange\":\"NASDAQ\",\"Symbol\":\"TLT\",\"Root\":\"TLT\",\"PriceFormat\":{\"Format\":\"Decimal\",\"Decimals\":\"2\",\"IncrementStyle\":\"Simple\",\"Increment\":\"0.01\",\"PointValue\":\"1\"},\"QuantityFormat\":{\"Format\":\"Decimal\",\"Decimals\":\"0\",\"IncrementStyle\":\"Simple\",\"Increment\":\"1\",\"MinimumTradeQuantity\":\"1\"}}],\"Errors\":[]}"
+        )
+        .expect(1)
+        .create();
+    let quotes_mock = server
+        .mock("GET", "/marketdata/quotes/TLT")
+        .with_status(200)
+        .with_body("{\"Quotes\":[]}")
+        .expect(3)
+        .create();
+    let place_mock = server
+        .mock("POST", "/orderexecution/orders")
+        .expect(0)
+        .create();
+
+    let rt = tokio::runtime::Runtime::new().unwrap();
+    rt.block_on(async {
+        let client = ClientBuilder::new()
+            .environment(ClientEnvironment::Mock(server.url()))
+            .with_token(Token::dummy())
+            .build()
+            .await
+            .unwrap();
+
+        let order = |order_type: OrderType, limit_price: Option<&str>| {
+            let order_req = OrderRequestBuilder::new()
+                .account_id("11111111")
+                .symbol("TLT")
+                .trade_action(TradeAction::Buy)
+                .quantity("100")
+                .time_in_force(OrderTimeInForce {
+                    duration: Duration::DAY,
+                    expiration: None,
+                });
+            match limit_price {
+                Some(limit_price) => order_req.order_type(order_type).limit_price(limit_price),
+                None => order_req.order_type(order_type),
+            }
+            .build()
+            .unwrap()
+        };
+        let blocked_by = |policy: RiskPolicy, order_req| {
+            let client = client.clone();
+            async move {
+                client.set_risk_policy(policy);
+                match Order::place(&client, &order_req).await {
+                    Err(Error::RiskViolation(violation)) => violation,
+                    other => panic!("Expected a risk violation, but got {other:?}"),
+                }
+            }
+        };
+
+        // A market order can't be valued without the last price
+        assert_eq!(
+            blocked_by(
+                RiskPolicy::new().max_order_notional(1_000_000.0),
+                order(OrderType::Market, None)
+            )
+            .await,
+            RiskViolation::PriceUnavailable("TLT".into())
+        );
+        assert_eq!(
+            blocked_by(
+                RiskPolicy::new().asset_type_limits(
+                    AssetType::Stock,
+                    AssetTypeLimits {
+                        max_quantity: None,
+                        max_order_notional: Some(1_000_000.0),
+                    },
+                ),
+                order(OrderType::Market, None)
+            )
+            .await,
+            RiskViolation::PriceUnavailable("TLT".into())
+        );
+
+        // A limit order can't be collared without the last price
+        assert_eq!(
+            blocked_by(
+                RiskPolicy::new().price_collar_percent(5.0),
+                order(OrderType::Limit, Some("92.50"))
+            )
+            .await,
+            RiskViolation::PriceUnavailable("TLT".into())
+        );
+    });
+
+    // Ensure none of the orders were sent
+    symbols_mock.assert();
+    quotes_mock.assert();
+    place_mock.assert();
+}
+
+#[test]
+/// This test ensures live clients refuse orders without being armed for
+/// live trading, and the kill switch blocks orders in every environment.
+fn test_trading_interlocks_mocked() {
+    let mut server = Server::new();
+    let place_mock = server
+        .mock("POST", "/orderexecution/orders")
+        .with_status(200)
+        .with_body(
+            "{\"Orders\":[{\"Message\":\"Sent order: Buy 100 PLTR @ 75.00 Limit\",\"OrderID\":\"5555555555\"}]}"
+        )
+        .expect(1)
+        .create();
+
+    let order_req = OrderRequestBuilder::new()
+        .account_id("11111111")
+        .symbol("PLTR")
+        .trade_action(TradeAction::Buy)
+        .quantity("100")
+        .order_type(OrderType::Limit)
+        .limit_price("75.00")
+        .time_in_force(OrderTimeInForce {
+            duration: Duration::DAY,
+            expiration: None,
+        })
+        .build()
+        .unwrap();
+
+    let rt = tokio::runtime::Runtime::new().unwrap();
+    rt.block_on(async {
+        // Live clients which weren't armed never send the order
+        let live_client = ClientBuilder::new()
+            .environment(ClientEnvironment::Live)
+            .with_token(Token::dummy())
+            .build()
+            .await
+            .unwrap();
+        assert!(!live_client.live_trading_enabled());
+        assert!(matches!(
+            Order::place(&live_client, &order_req).await,
+            Err(Error::LiveTradingNotEnabled)
+        ));
+        assert!(matches!(
+            OrderTicket::from_id("5555555555")
+                .replace(&live_client, OrderUpdate::new().limit_price("76.00"))
+                .await,
+            Err(Error::LiveTradingNotEnabled)
+        ));
+
+        // Armed live clients only send orders for their allowed accounts
+        let live_client = ClientBuilder::new()
+            .environment(ClientEnvironment::Live)
+            .with_token(Token::dummy())
+            .enable_live_trading()
+            .live_trading_accounts(["22222222"])
+            .build()
+            .await
+            .unwrap();
+        assert!(live_client.live_trading_enabled());
+        match Order::place(&live_client, &order_req).await {
+            Err(Error::LiveTradingAccountNotAllowed(account_id)) => {
+                assert_eq!(account_id, "11111111")
+            }
+            other => panic!("Expected the account to not be allowed, but got {other:?}"),
+        }
+
+        // The kill switch blocks orders from every clone of the client
+        let client = ClientBuilder::new()
+            .environment(ClientEnvironment::Mock(server.url()))
+            .with_token(Token::dummy())
+            .build()
+            .await
+            .unwrap();
+        let cloned_client = client.clone();
+        client.engage_kill_switch();
+        assert!(cloned_client.kill_switch_engaged());
+        assert!(matches!(
+            Order::place(&cloned_client, &order_req).await,
+            Err(Error::KillSwitchEngaged)
+        ));
+        assert!(matches!(
+            OrderTicket::from_id("5555555555")
+                .replace(&cloned_client, OrderUpdate::new().limit_price("76.00"))
+                .await,
+            Err(Error::KillSwitchEngaged)
+        ));
+
+        // Releasing the kill switch lets orders through again
+        cloned_client.release_kill_switch();
+        let tickets = Order::place(&client, &order_req)
+            .await
+            .expect("Order should be placed once the kill switch is released");
+        assert_eq!(tickets[0].order_id, "5555555555");
+    });
+
+    // Ensure only the order placed after releasing the kill switch was sent
+    place_mock.assert();
+}
+
+#[test]
+/// This test ensures placing orders with a client in dry-run mode
+/// confirms them instead, returning tickets marked as dry-run.
+fn test_dry_run_mocked() {
+    let mut server = Server::new();
+    let confirm_mock = server
+        .mock("POST", "/orderexecution/orderconfirm")
+        .with_status(200)
+        .with_body(
+            "{\"Confirmations\":[{\"OrderAssetCategory\":\"EQUITY\",\"Currency\":\"USD\",\"Route\":\"Intelligent\",\"TimeInForce\":{\"Duration\":\"DAY\"},\"AccountID\":\"11111111\",\"OrderConfirmID\":\"i+wE/m+IrUX886i9/51TaH\",\"EstimatedPrice\":\"92.59\",\"EstimatedCost\":\"9259\",\"DebitCreditEstimatedCost\":\"-9259\",\"EstimatedCommission\":\"0\",\"SummaryMessage\":\"Sell Short 100 NVDA @ Market\"}]}"
+        )
+        .expect(1)
+        .create();
+    let group_confirm_mock = server
+        .mock("POST", "/orderexecution/ordergroupconfirm")
+        .with_status(200)
+        .with_body(
+            "{\"Confirmations\":[{\"OrderAssetCategory\":\"EQUITY\",\"Currency\":\"USD\",\"Route\":\"Intelligent\",\"TimeInForce\":{\"Duration\":\"GTC\"},\"AccountID\":\"11111111\",\"OrderConfirmID\":\"AAAAAAAAAAAAAAAAAAAAA1\",\"EstimatedPrice\":\"95.00\",\"EstimatedCost\":\"9500\",\"EstimatedCommission\":\"0\",\"SummaryMessage\":\"Buy to Cover 100 NVDA @ 85.00 Limit\"},{\"OrderAssetCategory\":\"EQUITY\",\"Currency\":\"USD\",\"Route\":\"Intelligent\",\"TimeInForce\":{\"Duration\":\"GTC\"},\"AccountID\":\"11111111\",\"OrderConfirmID\":\"AAAAAAAAAAAAAAAAAAAAA2\",\"EstimatedPrice\":\"100.00\",\"EstimatedCost\":\"10000\",\"EstimatedCommission\":\"0\",\"SummaryMessage\":\"Buy to Cover 100 NVDA @ 100.00 Stop Market\"}]}"
+        )
+        .expect(1)
+        .create();
+    let place_mock = server
+        .mock("POST", "/orderexecution/orders")
+        .expect(0)
+        .create();
+    let place_group_mock = server
+        .mock("POST", "/orderexecution/ordergroups")
+        .expect(0)
+        .create();
+
+    let rt = tokio::runtime::Runtime::new().unwrap();
+    rt.block_on(async {
+        let client = ClientBuilder::new()
+            .environment(ClientEnvironment::Mock(server.url()))
+            .with_token(Token::dummy())
+            .dry_run()
+            .build()
+            .await
+            .unwrap();
+        assert!(client.is_dry_run());
+
+        let order_req = OrderRequestBuilder::new()
+            .account_id("11111111")
+            .symbol("NVDA")
+            .trade_action(TradeAction::SellShort)
+            .quantity("100")
+            .order_type(OrderType::Market)
+            .time_in_force(OrderTimeInForce {
+                duration: Duration::DAY,
+                expiration: None,
+            })
+            .build()
+            .unwrap();
+        let tickets = Order::place(&client, &order_req).await.unwrap();
+        assert_eq!(tickets.len(), 1);
+        assert!(tickets[0].dry_run);
+        assert_eq!(tickets[0].order_id, "i+wE/m+IrUX886i9/51TaH");
+        assert_eq!(tickets[0].message, "Dry run: Sell Short 100 NVDA @ Market");
+
+        let group = BracketGroupBuilder::new()
+            .take_profit(
+                OrderRequestBuilder::new()
+                    .account_id("11111111")
+                    .symbol("NVDA")
+                    .trade_action(TradeAction::BuyToCover)
+                    .quantity("100")
+                    .order_type(OrderType::Limit)
+                    .limit_price("85.00")
+                    .time_in_force(OrderTimeInForce {
+                        duration: Duration::GTC,
+                        expiration: None,
+                    })
+                    .build()
+                    .unwrap(),
+            )
+            .stop_loss(
+                OrderRequestBuilder::new()
+                    .account_id("11111111")
+                    .symbol("NVDA")
+                    .trade_action(TradeAction::BuyToCover)
+                    .quantity("100")
+                    .order_type(OrderType::StopMarket)
+                    .stop_price("100.00")
+                    .time_in_force(OrderTimeInForce {
+                        duration: Duration::GTC,
+                        expiration: None,
+                    })
+                    .build()
+                    .unwrap(),
+            )
+            .build()
+            .unwrap();
+        let tickets = group.place(&client).await.unwrap();
+        assert_eq!(tickets.len(), 2);
+        assert!(tickets.iter().all(|ticket| ticket.dry_run));
+
+        // Live clients in dry-run mode don't need live trading enabled to place orders
+        let live_client = ClientBuilder::new()
+            .environment(ClientEnvironment::Live)
+            .with_token(Token::dummy())
+            .build()
+            .await
+            .unwrap();
+        live_client.set_dry_run(true);
+        assert!(live_client.is_dry_run());
+        assert!(!live_client.live_trading_enabled());
+
+        // But replacing orders still sends them, so it isn't exempt
+        assert!(matches!(
+            OrderTicket::from_id("5555555555")
+                .replace(&live_client, OrderUpdate::new().limit_price("76.00"))
+                .await,
+            Err(Error::LiveTradingNotEnabled)
+        ));
+    });
+
+    // Ensure orders were only confirmed, and never placed
+    confirm_mock.assert();
+    group_confirm_mock.assert();
+    place_mock.assert();
+    place_group_mock.assert();
+}
+
+#[test]
+/// This test ensures a confirmed order can be reviewed,
+/// then placed with it's `OrderConfirmID`.
+fn test_confirm_then_place_mocked() {
+    let mut server = Server::new();
+    let confirm_mock = server
+        .mock("POST", "/orderexecution/orderconfirm")
+        .with_status(200)
+        .with_body(
+            "{\"Confirmations\":[{\"OrderAssetCategory\":\"EQUITY\",\"Currency\":\"USD\",\"Route\":\"Intelligent\",\"TimeInForce\":{\"Duration\":\"DAY\"},\"AccountID\":\"11111111\",\"OrderConfirmID\":\"i+wE/m+IrUX886i9/51TaH\",\"EstimatedPrice\":\"92.59\",\"EstimatedCost\":\"9259\",\"DebitCreditEstimatedCost\":\"-9259\",\"EstimatedCommission\":\"1.5\",\"SummaryMessage\":\"Sell Short 100 NVDA @ Market\"}]}"
+        )
+        .expect(1)
+        .create();
+    let place_mock = server
+        .mock("POST", "/orderexecution/orders")
+        .match_body(mockito::Matcher::PartialJsonString(
+            "{\"OrderConfirmID\":\"i+wE/m+IrUX886i9/51TaH\",\"BuyingPowerWarning\":\"Confirmed\"}"
+                .into(),
+        ))
+        .with_status(200)
+        .with_body(
+            "{\"Orders\":[{\"Message\":\"Sent order: Sell Short 100 NVDA @ Market\",\"OrderID\":\"5555555555\"}]}"
+        )
+        .expect(1)
+        .create();
+
+    let rt = tokio::runtime::Runtime::new().unwrap();
+    rt.block_on(async {
+        let client = ClientBuilder::new()
+            .environment(ClientEnvironment::Mock(server.url()))
+            .with_token(Token::dummy())
+            .build()
+            .await
+            .unwrap();
+
+        let confirmed = OrderRequestBuilder::new()
+            .account_id("11111111")
+            .symbol("NVDA")
+            .trade_action(TradeAction::SellShort)
+            .quantity("100")
+            .order_type(OrderType::Market)
+            .time_in_force(OrderTimeInForce {
+                duration: Duration::DAY,
+                expiration: None,
+            })
+            .build()
+            .unwrap()
+            .confirm_to_place(&client)
+            .await
+            .unwrap();
+
+        assert_eq!(confirmed.confirmations().len(), 1);
+        assert_eq!(
+            confirmed.order_confirm_ids(),
+            vec!["i+wE/m+IrUX886i9/51TaH"]
+        );
+        assert_eq!(confirmed.estimated_cost().unwrap(), 9259.0);
+        assert_eq!(confirmed.estimated_commission().unwrap(), 1.5);
+        assert!(confirmed.buying_power_warning_status().is_none());
+
+        let confirmed = confirmed.buying_power_warning(BPWarningStatus::Confirmed);
+        assert!(matches!(
+            confirmed.buying_power_warning_status(),
+            Some(BPWarningStatus::Confirmed)
+        ));
+
+        let tickets = confirmed.place(&client).await.unwrap();
+        assert_eq!(tickets[0].order_id, "5555555555");
+    });
+
+    // Ensure the order was confirmed, then placed with it's confirm id
+    confirm_mock.assert();
+    place_mock.assert();
+}
+
+#[test]
+/// This test ensures a confirmed group isn't created when TradeStation
+/// doesn't return a confirmation for every order in the group.
+fn test_confirm_group_missing_confirmation_mocked() {
+    let mut server = Server::new();
+    let confirm_mock = server
+        .mock("POST", "/orderexecution/ordergroupconfirm")
+        .with_status(200)
+        .with_body(
+            "{\"Confirmations\":[{\"OrderAssetCategory\":\"EQUITY\",\"Currency\":\"USD\",\"Route\":\"Intelligent\",\"TimeInForce\":{\"Duration\":\"GTC\"},\"AccountID\":\"11111111\",\"OrderConfirmID\":\"i+wE/m+IrUX886i9/51TaH\",\"EstimatedPrice\":\"450\",\"EstimatedCost\":\"4500\",\"DebitCreditEstimatedCost\":\"4500\",\"EstimatedCommission\":\"0\",\"SummaryMessage\":\"Sell 10 MSFT @ 450.00 Lmt\"}]}"
+        )
+        .expect(1)
+        .create();
+    let place_mock = server
+        .mock("POST", "/orderexecution/ordergroups")
+        .expect(0)
+        .create();
+
+    let rt = tokio::runtime::Runtime::new().unwrap();
+    rt.block_on(async {
+        let client = ClientBuilder::new()
+            .environment(ClientEnvironment::Mock(server.url()))
+            .with_token(Token::dummy())
+            .build()
+            .await
+            .unwrap();
+
+        let take_profit = OrderRequestBuilder::new()
+            .account_id("11111111")
+            .symbol("MSFT")
+            .trade_action(TradeAction::Sell)
+            .quantity("10")
+            .order_type(OrderType::Limit)
+            .limit_price("450.00")
+            .time_in_force(OrderTimeInForce {
+                duration: Duration::GTC,
+                expiration: None,
+            })
+            .build()
+            .unwrap();
+        let stop_loss = OrderRequestBuilder::new()
+            .account_id("11111111")
+            .symbol("MSFT")
+            .trade_action(TradeAction::Sell)
+            .quantity("10")
+            .order_type(OrderType::StopMarket)
+            .stop_price("400.00")
+            .time_in_force(OrderTimeInForce {
+                duration: Duration::GTC,
+                expiration: None,
+            })
+            .build()
+            .unwrap();
+        let group = OrderRequestGroupBuilder::new()
+            .order_requests(vec![take_profit, stop_loss])
+            .group_type(OrderRelationship::OCO)
+            .build()
+            .unwrap();
+
+        let Err(Error::UnknownTradeStationAPIError(reason)) = group.confirm_to_place(&client).await
+        else {
+            panic!("a group missing a confirmation shouldn't be confirmed");
+        };
+        assert_eq!(
+            reason,
+            "TradeStation returned 1 confirmations for 2 orders."
+        );
+    });
+
+    // Ensure the group was confirmed, but never placed
+    confirm_mock.assert();
+    place_mock.assert();
+}
+
+#[test]
+/// This test ensures order submissions failing without a definitive outcome
+/// are reconciled against the account's orders, and retries of orders which
+/// weren't placed reuse their correlation id.
+fn test_idempotent_submission_mocked() {
+    let order_json = |order_id: &str, quantity: &str, opened: &str| {
+        format!(
+            "{{\"AccountID\":\"11111111\",\"CommissionFee\":\"0\",\"Currency\":\"USD\",\"Duration\":\"DAY\",\"Legs\":[{{\"QuantityOrdered\":\"{quantity}\",\"ExecQuantity\":\"0\",\"QuantityRemaining\":\"{quantity}\",\"BuyOrSell\":\"Buy\",\"Symbol\":\"PLTR\",\"AssetType\":\"STOCK\"}}],\"LimitPrice\":\"75.00\",\"OrderID\":\"{order_id}\",\"OpenedDateTime\":\"{opened}\",\"OrderType\":\"Limit\",\"PriceUsedForBuyingPower\":\"75.00\",\"Status\":\"ACK\",\"StatusDescription\":\"Received\",\"ConversionRate\":\"1\",\"UnbundledRouteFee\":\"0\"}}"
+        )
+    };
+    let order_req = OrderRequestBuilder::new()
+        .account_id("11111111")
+        .symbol("PLTR")
+        .trade_action(TradeAction::Buy)
+        .quantity("100")
+        .order_type(OrderType::Limit)
+        .limit_price("75.00")
+        .time_in_force(OrderTimeInForce {
+            duration: Duration::DAY,
+            expiration: None,
+        })
+        .build()
+        .unwrap();
+
+    // The order was placed, but the response was lost
+    let mut server = Server::new();
+    let place_mock = server
+        .mock("POST", "/orderexecution/orders")
+        .with_status(200)
+        .with_body("")
+        .expect(1)
+        .create();
+    let orders_mock = server
+        .mock("GET", "/brokerage/accounts/11111111/orders")
+        .with_status(200)
+        .with_body(format!(
+            "{{\"Orders\":[{},{},{},{},{}],\"Errors\":[]}}",
+            order_json("1111111111", "100", "2025-01-24T18:05:03Z"),
+            order_json("2222222222", "100", "2099-01-01T00:00:00Z"),
+            // The same symbol and quantity, but a different side, order type, or price
+            order_json("5555555555", "100", "2099-01-01T00:00:00Z")
+                .replace("\"BuyOrSell\":\"Buy\"", "\"BuyOrSell\":\"Sell\""),
+            order_json("6666666666", "100", "2099-01-01T00:00:00Z")
+                .replace("\"OrderType\":\"Limit\"", "\"OrderType\":\"StopLimit\""),
+            order_json("7777777777", "100", "2099-01-01T00:00:00Z")
+                .replace("\"LimitPrice\":\"75.00\"", "\"LimitPrice\":\"74.00\""),
+        ))
+        .expect(1)
+        .create();
+
+    let rt = tokio::runtime::Runtime::new().unwrap();
+    rt.block_on(async {
+        let client = ClientBuilder::new()
+            .environment(ClientEnvironment::Mock(server.url()))
+            .with_token(Token::dummy())
+            .build()
+            .await
+            .unwrap();
+
+        let tickets = Order::place(&client, &order_req)
+            .await
+            .expect("The placed order should be found");
+        assert_eq!(tickets.len(), 1);
+        assert_eq!(tickets[0].order_id, "2222222222");
+    });
+    place_mock.assert();
+    orders_mock.assert();
+
+    // The order wasn't placed, so retrying it is safe
+    let mut server = Server::new();
+    let place_mock = server
+        .mock("POST", "/orderexecution/orders")
+        .with_status(200)
+        .with_body("")
+        .expect(1)
+        .create();
+    let orders_mock = server
+        .mock("GET", "/brokerage/accounts/11111111/orders")
+        .with_status(200)
+        .with_body(format!(
+            "{{\"Orders\":[{}],\"Errors\":[]}}",
+            order_json("3333333333", "50", "2099-01-01T00:00:00Z"),
+        ))
+        .expect(1)
+        .create();
+
+    rt.block_on(async {
+        let client = ClientBuilder::new()
+            .environment(ClientEnvironment::Mock(server.url()))
+            .with_token(Token::dummy())
+            .build()
+            .await
+            .unwrap();
+
+        let correlation_id = match Order::place(&client, &order_req).await {
+            Err(Error::OrderNotPlaced(reason)) => reason.split('`').nth(1).unwrap().to_string(),
+            other => panic!("Expected the order to not be placed, but got {other:?}"),
+        };
+        assert!(!correlation_id.is_empty() && correlation_id.len() <= 22);
+        place_mock.assert();
+        orders_mock.assert();
+
+        // Retrying the order reconciles it again, then resends it with the same correlation id
+        server.reset();
+        let orders_mock = server
+            .mock("GET", "/brokerage/accounts/11111111/orders")
+            .with_status(200)
+            .with_body("{\"Orders\":[],\"Errors\":[]}")
+            .expect(1)
+            .create();
+        let retry_mock = server
+            .mock("POST", "/orderexecution/orders")
+            .match_body(mockito::Matcher::PartialJsonString(format!(
+                "{{\"OrderConfirmID\":\"{correlation_id}\"}}"
+            )))
+            .with_status(200)
+            .with_body(
+                "{\"Orders\":[{\"Message\":\"Sent order: Buy 100 PLTR @ 75.00 Limit\",\"OrderID\":\"4444444444\"}]}"
+            )
+            .expect(1)
+            .create();
+
+        let tickets = Order::place(&client, &order_req).await.unwrap();
+        assert_eq!(tickets[0].order_id, "4444444444");
+
+        orders_mock.assert();
+        retry_mock.assert();
+    });
+}
+
+#[test]
+/// This test ensures identical orders placed at the same time are
+/// each sent, rather than reconciled against each other.
+fn test_concurrent_identical_orders_mocked() {
+    let mut server = Server::new();
+    let place_mock = server
+        .mock("POST", "/orderexecution/orders")
+        .with_status(200)
+        .with_body(
+            "{\"Orders\":[{\"Message\":\"Sent order: Buy 100 PLTR @ 75.00 Limit\",\"OrderID\":\"1111111111\"}]}"
+        )
+        .expect(2)
+        .create();
+    let orders_mock = server
+        .mock("GET", "/brokerage/accounts/11111111/orders")
+        .expect(0)
+        .create();
+
+    let rt = tokio::runtime::Runtime::new().unwrap();
+    rt.block_on(async {
+        let client = ClientBuilder::new()
+            .environment(ClientEnvironment::Mock(server.url()))
+            .with_token(Token::dummy())
+            .build()
+            .await
+            .unwrap();
+
+        let order_req = OrderRequestBuilder::new()
+            .account_id("11111111")
+            .symbol("PLTR")
+            .trade_action(TradeAction::Buy)
+            .quantity("100")
+            .order_type(OrderType::Limit)
+            .limit_price("75.00")
+            .time_in_force(OrderTimeInForce {
+                duration: Duration::DAY,
+                expiration: None,
+            })
+            .build()
+            .unwrap();
+
+        let (first, second) = futures::join!(
+            Order::place(&client, &order_req),
+            Order::place(&client, &order_req)
+        );
+        assert!(first.is_ok() && second.is_ok());
+    });
+
+    // Ensure both orders were sent, without searching the account's orders
+    place_mock.assert();
+    orders_mock.assert();
+}
+
+#[test]
+/// This test ensures retrying an order reconciles it's earlier submission before
+/// the risk policy and kill switch are checked, so they can't hide it was placed.
+fn test_retry_reconciled_before_checks_mocked() {
+    let order_json = "{\"AccountID\":\"11111111\",\"CommissionFee\":\"0\",\"Currency\":\"USD\",\"Duration\":\"DAY\",\"Legs\":[{\"QuantityOrdered\":\"100\",\"ExecQuantity\":\"0\",\"QuantityRemaining\":\"100\",\"BuyOrSell\":\"Buy\",\"Symbol\":\"PLTR\",\"AssetType\":\"STOCK\"}],\"LimitPrice\":\"75.00\",\"OrderID\":\"1111111111\",\"OpenedDateTime\":\"2099-01-01T00:00:00Z\",\"OrderType\":\"Limit\",\"PriceUsedForBuyingPower\":\"75.00\",\"Status\":\"ACK\",\"StatusDescription\":\"Received\",\"ConversionRate\":\"1\",\"UnbundledRouteFee\":\"0\"}";
+    let order_req = OrderRequestBuilder::new()
+        .account_id("11111111")
+        .symbol("PLTR")
+        .trade_action(TradeAction::Buy)
+        .quantity("100")
+        .order_type(OrderType::Limit)
+        .limit_price("75.00")
+        .time_in_force(OrderTimeInForce {
+            duration: Duration::DAY,
+            expiration: None,
+        })
+        .build()
+        .unwrap();
+
+    // The submission fails, and the order doesn't show up in time
+    let mut server = Server::new();
+    let place_mock = server
+        .mock("POST", "/orderexecution/orders")
+        .with_status(200)
+        .with_body("")
+        .expect(1)
+        .create();
+    let orders_mock = server
+        .mock("GET", "/brokerage/accounts/11111111/orders")
+        .with_status(200)
+        .with_body("{\"Orders\":[],\"Errors\":[]}")
+        .expect(1)
+        .create();
+
+    let rt = tokio::runtime::Runtime::new().unwrap();
+    rt.block_on(async {
+        let client = ClientBuilder::new()
+            .environment(ClientEnvironment::Mock(server.url()))
+            .with_token(Token::dummy())
+            .build()
+            .await
+            .unwrap();
+
+        assert!(matches!(
+            Order::place(&client, &order_req).await,
+            Err(Error::OrderNotPlaced(_))
+        ));
+        place_mock.assert();
+        orders_mock.assert();
+
+        // The order shows up late, counting against a policy attached before the retry
+        server.reset();
+        let orders_mock = server
+            .mock("GET", "/brokerage/accounts/11111111/orders")
+            .with_status(200)
+            .with_body(format!("{{\"Orders\":[{order_json}],\"Errors\":[]}}"))
+            .expect(1)
+            .create();
+        let retry_mock = server
+            .mock("POST", "/orderexecution/orders")
+            .expect(0)
+            .create();
+        client.set_risk_policy(RiskPolicy::new().max_open_orders(1));
+        client.engage_kill_switch();
+
+        let tickets = Order::place(&client, &order_req)
+            .await
+            .expect("The placed order should be found, without checking it again");
+        assert_eq!(tickets[0].order_id, "1111111111");
+
+        // Once reconciled, the order is checked like any other
+        assert!(matches!(
+            Order::place(&client, &order_req).await,
+            Err(Error::KillSwitchEngaged)
+        ));
+
+        orders_mock.assert();
+        retry_mock.assert();
+    });
+}
+
+#[test]
+/// This test ensures that an `OrderTicket`'s lifecycle is
+/// followed on the order stream, until the order is done.
+fn test_order_lifecycle_mocked() {
+    let order_json = |order_id: &str, status: &str, description: &str, filled: &str| {
+        format!(
+            "{{\"AccountID\":\"11111111\",\"CommissionFee\":\"0\",\"Currency\":\"USD\",\"Duration\":\"DAY\",\"Legs\":[{{\"QuantityOrdered\":\"100\",\"ExecQuantity\":\"{filled}\",\"QuantityRemaining\":\"100\",\"BuyOrSell\":\"Buy\",\"Symbol\":\"PLTR\",\"AssetType\":\"STOCK\"}}],\"LimitPrice\":\"75.00\",\"OrderID\":\"{order_id}\",\"OpenedDateTime\":\"2025-01-24T18:05:03Z\",\"OrderType\":\"Limit\",\"PriceUsedForBuyingPower\":\"75.00\",\"Status\":\"{status}\",\"StatusDescription\":\"{description}\",\"ConversionRate\":\"1\",\"UnbundledRouteFee\":\"0\"}}"
+        )
+    };
+
+    // Mock the order streams with newline delimited order updates,
+    // including repeated updates, and heartbeats which are skipped.
+    let mut server = Server::new();
+    let filled_mock = server
+        .mock(
+            "GET",
+            "/brokerage/stream/accounts/11111111/orders/1111111111",
+        )
+        .with_status(200)
+        .with_body(format!(
+            "{}\n{}\n{{\"Heartbeat\":1,\"Timestamp\":\"2025-01-24T18:05:04Z\"}}\n{}\n{}\n{}\n",
+            order_json("1111111111", "ACK", "Received", "0"),
+            order_json("1111111111", "ACK", "Received", "0"),
+            order_json("1111111111", "FPR", "Partial Fill (Alive)", "40"),
+            order_json("1111111111", "FPR", "Partial Fill (Alive)", "40"),
+            order_json("1111111111", "FLL", "Filled", "100"),
+        ))
+        .expect(1)
+        .create();
+    let rejected_mock = server
+        .mock(
+            "GET",
+            "/brokerage/stream/accounts/11111111/orders/2222222222",
+        )
+        .with_status(200)
+        .with_body(format!(
+            "{}\n{}\n",
+            order_json("2222222222", "ACK", "Received", "0"),
+            order_json("2222222222", "REJ", "Insufficient buying power", "0"),
+        ))
+        .expect(1)
+        .create();
+    let ended_mock = server
+        .mock(
+            "GET",
+            "/brokerage/stream/accounts/11111111/orders/3333333333",
+        )
+        .with_status(200)
+        .with_body(format!(
+            "{}\n",
+            order_json("3333333333", "ACK", "Received", "0")
+        ))
+        .expect(1)
+        .create();
+
+    let rt = tokio::runtime::Runtime::new().unwrap();
+    rt.block_on(async {
+        let client = ClientBuilder::new()
+            .environment(ClientEnvironment::Mock(server.url()))
+            .with_token(Token::dummy())
+            .build()
+            .await
+            .unwrap();
+
+        let ticket = OrderTicket::from_id("1111111111");
+        let events: Vec<OrderEvent> = ticket
+            .lifecycle_events(&client, "11111111")
+            .map(|event| event.unwrap())
+            .collect()
+            .await;
+        assert_eq!(events.len(), 3);
+        assert!(matches!(&events[0], OrderEvent::Acknowledged(_)));
+        assert!(matches!(
+            &events[1],
+            OrderEvent::PartiallyFilled { filled_quantity, .. } if *filled_quantity == 40.0
+        ));
+        assert!(matches!(&events[2], OrderEvent::Filled(order) if order.order_id == "1111111111"));
+        assert!(events[2].is_terminal());
+
+        let event = OrderTicket::from_id("2222222222")
+            .wait_for_terminal(&client, "11111111", StdDuration::from_secs(5))
+            .await
+            .expect("The rejection should be returned");
+        assert!(matches!(
+            event,
+            OrderEvent::Rejected { ref reason, .. } if reason == "Insufficient buying power"
+        ));
+
+        let error = OrderTicket::from_id("3333333333")
+            .wait_for_terminal(&client, "11111111", StdDuration::from_secs(5))
+            .await
+            .expect_err("The stream ended before the order was done");
+        assert!(matches!(error, Error::StreamIssue(_)));
+    });
+
+    // Ensure the mocks were called
+    filled_mock.assert();
+    rejected_mock.assert();
+    ended_mock.assert();
+}
+
+#[test]
+/// This test ensures following a dry-run ticket fails right away, since
+/// it's order was only confirmed, instead of waiting on the order stream.
+fn test_dry_run_ticket_lifecycle_mocked() {
+    let mut server = Server::new();
+    let stream_mock = server
+        .mock("GET", mockito::Matcher::Regex("^/brokerage/stream/".into()))
+        .expect(0)
+        .create();
+
+    let rt = tokio::runtime::Runtime::new().unwrap();
+    rt.block_on(async {
+        let client = ClientBuilder::new()
+            .environment(ClientEnvironment::Mock(server.url()))
+            .with_token(Token::dummy())
+            .build()
+            .await
+            .unwrap();
+
+        let ticket = OrderTicket {
+            message: "Dry run: Buy 100 PLTR @ 75.00 Limit".into(),
+            order_id: "i+wE/m+IrUX886i9/51TaH".into(),
+            error: None,
+            dry_run: true,
+        };
+
+        let waited = tokio::time::timeout(
+            StdDuration::from_secs(5),
+            ticket.wait_for_terminal(&client, "11111111", StdDuration::from_secs(60)),
+        )
+        .await
+        .expect("A dry-run ticket shouldn't be waited on");
+        assert!(matches!(waited, Err(Error::DryRunTicket(id)) if id == ticket.order_id));
+
+        let events = ticket.lifecycle_events(&client, "11111111");
+        tokio::pin!(events);
+        assert!(matches!(
+            events.next().await,
+            Some(Err(Error::DryRunTicket(_)))
+        ));
+        assert!(events.next().await.is_none());
+    });
+
+    // Ensure the order stream was never opened
+    stream_mock.assert();
+}
+
+#[test]
+/// This test ensures that every open `Order` is canceled, and every
+/// `Position` is closed with the correct `TradeAction`, reporting
+/// each cancellation and closing order whether it failed or not.
+fn test_flatten_all_mocked() {
+    let account = Account {
+        account_id: String::from("11111111"),
+        currency: String::from("USD"),
+        account_type: AccountType::Margin,
+        account_detail: None,
+    };
+    let order_json = |order_id: &str, symbol: &str, asset_type: &str, status: &str| {
+        format!(
+            "{{\"AccountID\":\"11111111\",\"CommissionFee\":\"0\",\"Currency\":\"USD\",\"Duration\":\"DAY\",\"Legs\":[{{\"QuantityOrdered\":\"100\",\"ExecQuantity\":\"0\",\"QuantityRemaining\":\"100\",\"BuyOrSell\":\"Sell\",\"Symbol\":\"{symbol}\",\"AssetType\":\"{asset_type}\"}}],\"LimitPrice\":\"95.00\",\"OrderID\":\"{order_id}\",\"OpenedDateTime\":\"2025-01-24T18:05:03Z\",\"OrderType\":\"Limit\",\"PriceUsedForBuyingPower\":\"95.00\",\"Status\":\"{status}\",\"StatusDescription\":\"\",\"ConversionRate\":\"1\",\"UnbundledRouteFee\":\"0\"}}"
+        )
+    };
+    let position_json = |symbol: &str, asset_type: &str, long_short: &str, quantity: &str| {
+        format!(
+            "{{\"AccountID\":\"11111111\",\"AveragePrice\":\"83.205\",\"AssetType\":\"{asset_type}\",\"Last\":\"90.6\",\"Bid\":\"90.55\",\"Ask\":\"90.6\",\"ConversionRate\":\"1\",\"DayTradeRequirement\":\"0\",\"InitialRequirement\":\"0\",\"MaintenanceMargin\":\"0\",\"PositionID\":\"222222225\",\"LongShort\":\"{long_short}\",\"Quantity\":\"{quantity}\",\"Symbol\":\"{symbol}\",\"Timestamp\":\"2025-02-28T15:13:05Z\",\"TodaysProfitLoss\":\"-11.8\",\"TotalCost\":\"166.41\",\"MarketValue\":\"181.2\",\"MarkToMarketPrice\":\"96.5\",\"UnrealizedProfitLoss\":\"14.79\",\"UnrealizedProfitLossPercent\":\"8.888\",\"UnrealizedProfitLossQty\":\"7.4\"}}"
+        )
+    };
+
+    // Mock an open and a filled stock order, and an open option order left alone by the
+    // filter, then a long and a short stock position, and an option position.
+    let mut server = Server::new();
+    let orders_mock = server
+        .mock("GET", "/brokerage/accounts/11111111/orders")
+        .with_status(200)
+        .with_body(format!(
+            "{{\"Orders\":[{},{},{}],\"Errors\":[]}}",
+            order_json("1111111111", "PLTR", "STOCK", "ACK"),
+            order_json("2222222222", "PLTR", "STOCK", "FLL"),
+            order_json("3333333333", "PLTR 250321C100", "STOCKOPTION", "ACK"),
+        ))
+        // Once to cancel the orders, and again to reconcile the failed closing order
+        .expect(2)
+        .create();
+    let cancel_mock = server
+        .mock("DELETE", "/orderexecution/orders/1111111111")
+        .with_status(200)
+        .with_body("{\"Message\":\"Cancel request sent\",\"OrderID\":\"1111111111\"}")
+        .expect(1)
+        .create();
+    let canceled_mock = server
+        .mock("GET", "/brokerage/accounts/11111111/orders/1111111111")
+        .with_status(200)
+        .with_body(format!(
+            "{{\"Orders\":[{}],\"Errors\":[]}}",
+            order_json("1111111111", "PLTR", "STOCK", "CAN"),
+        ))
+        .expect(1)
+        .create();
+    let positions_mock = server
+        .mock("GET", "/brokerage/accounts/11111111/positions")
+        .with_status(200)
+        .with_body(format!(
+            "{{\"Positions\":[{},{},{}],\"Errors\":[]}}",
+            position_json("PLTR", "STOCK", "Long", "100"),
+            position_json("TSLA", "STOCK", "Short", "-10"),
+            position_json("PLTR 250321C100", "STOCKOPTION", "Long", "1"),
+        ))
+        .expect(1)
+        .create();
+    let sell_mock = server
+        .mock("POST", "/orderexecution/orders")
+        .match_body(mockito::Matcher::PartialJsonString(
+            "{\"Symbol\":\"PLTR\",\"TradeAction\":\"SELL\",\"Quantity\":\"100\",\"OrderType\":\"Market\"}"
+                .into(),
+        ))
+        .with_status(200)
+        .with_body(
+            "{\"Orders\":[{\"Message\":\"Sent order: Sell 100 PLTR @ Market\",\"OrderID\":\"4444444444\"}]}",
+        )
+        .expect(1)
+        .create();
+    let cover_mock = server
+        .mock("POST", "/orderexecution/orders")
+        .match_body(mockito::Matcher::PartialJsonString(
+            "{\"Symbol\":\"TSLA\",\"TradeAction\":\"BUYTOCOVER\",\"Quantity\":\"10\",\"OrderType\":\"Market\"}"
+                .into(),
+        ))
+        .with_status(500)
+        .with_body("")
+        .expect(1)
+        .create();
+
+    let rt = tokio::runtime::Runtime::new().unwrap();
+    rt.block_on(async {
+        let client = ClientBuilder::new()
+            .environment(ClientEnvironment::Mock(server.url()))
+            .with_token(Token::dummy())
+            .build()
+            .await
+            .unwrap();
+
+        let report = account
+            .flatten_all(
+                &client,
+                &EmergencyFilter::new().asset_type(AssetType::Stock),
+            )
+            .await
+            .expect("The orders and positions should be fetched");
+
+        assert_eq!(report.cancellations.len(), 1);
+        assert_eq!(report.cancellations[0].order_id, "1111111111");
+        assert!(report.cancellations[0].result.is_ok());
+
+        assert_eq!(report.closures.len(), 2);
+        let sell = &report.closures[0];
+        assert_eq!(sell.trade_action, TradeAction::Sell);
+        assert_eq!(sell.result.as_ref().unwrap()[0].order_id, "4444444444");
+        let cover = &report.closures[1];
+        assert_eq!(cover.trade_action, TradeAction::BuyToCover);
+        assert_eq!(cover.quantity, "10");
+        assert!(matches!(cover.result, Err(Error::OrderNotPlaced(_))));
+
+        assert!(!report.is_success());
+        assert_eq!(report.failures().count(), 1);
+    });
+
+    // Ensure the mocks were called
+    orders_mock.assert();
+    cancel_mock.assert();
+    canceled_mock.assert();
+    positions_mock.assert();
+    sell_mock.assert();
+    cover_mock.assert();
+}
+
+#[test]
+/// This test ensures positions aren't closed while an order in
+/// their symbol couldn't be canceled, so it can't fill alongside the close.
+fn test_flatten_all_skips_uncanceled_symbols_mocked() {
+    let account = Account {
+        account_id: String::from("11111111"),
+        currency: String::from("USD"),
+        account_type: AccountType::Margin,
+        account_detail: None,
+    };
+    let order_json = |order_id: &str, symbol: &str, status: &str| {
+        format!(
+            "{{\"AccountID\":\"11111111\",\"CommissionFee\":\"0\",\"Currency\":\"USD\",\"Duration\":\"GTC\",\"Legs\":[{{\"QuantityOrdered\":\"10\",\"ExecQuantity\":\"0\",\"QuantityRemaining\":\"10\",\"BuyOrSell\":\"Sell\",\"Symbol\":\"{symbol}\",\"AssetType\":\"STOCK\"}}],\"StopPrice\":\"80.00\",\"OrderID\":\"{order_id}\",\"OpenedDateTime\":\"2025-01-24T18:05:03Z\",\"OrderType\":\"StopMarket\",\"PriceUsedForBuyingPower\":\"80.00\",\"Status\":\"{status}\",\"StatusDescription\":\"\",\"ConversionRate\":\"1\",\"UnbundledRouteFee\":\"0\"}}"
+        )
+    };
+    let position_json = |symbol: &str, quantity: &str| {
+        format!(
+            "{{\"AccountID\":\"11111111\",\"AveragePrice\":\"83.205\",\"AssetType\":\"STOCK\",\"Last\":\"90.6\",\"Bid\":\"90.55\",\"Ask\":\"90.6\",\"ConversionRate\":\"1\",\"DayTradeRequirement\":\"0\",\"InitialRequirement\":\"0\",\"MaintenanceMargin\":\"0\",\"PositionID\":\"222222225\",\"LongShort\":\"Long\",\"Quantity\":\"{quantity}\",\"Symbol\":\"{symbol}\",\"Timestamp\":\"2025-02-28T15:13:05Z\",\"TodaysProfitLoss\":\"-11.8\",\"TotalCost\":\"166.41\",\"MarketValue\":\"181.2\",\"MarkToMarketPrice\":\"96.5\",\"UnrealizedProfitLoss\":\"14.79\",\"UnrealizedProfitLossPercent\":\"8.888\",\"UnrealizedProfitLossQty\":\"7.4\"}}"
+        )
+    };
+
+    // Mock a working stop loss in PLTR which is canceled, and one in TSLA which can't be
+    let mut server = Server::new();
+    let orders_mock = server
+        .mock("GET", "/brokerage/accounts/11111111/orders")
+        .with_status(200)
+        .with_body(format!(
+            "{{\"Orders\":[{},{}],\"Errors\":[]}}",
+            order_json("1111111111", "PLTR", "ACK"),
+            order_json("2222222222", "TSLA", "ACK"),
+        ))
+        .expect(1)
+        .create();
+    let cancel_mock = server
+        .mock("DELETE", "/orderexecution/orders/1111111111")
+        .with_status(200)
+        .with_body("{\"Message\":\"Cancel request sent\",\"OrderID\":\"1111111111\"}")
+        .expect(1)
+        .create();
+    let failed_cancel_mock = server
+        .mock("DELETE", "/orderexecution/orders/2222222222")
+        .with_status(400)
+        .with_body("{\"Error\":\"BadRequest\",\"Message\":\"Order is not cancelable.\"}")
+        .expect(1)
+        .create();
+    let canceled_mock = server
+        .mock("GET", "/brokerage/accounts/11111111/orders/1111111111")
+        .with_status(200)
+        .with_body(format!(
+            "{{\"Orders\":[{}],\"Errors\":[]}}",
+            order_json("1111111111", "PLTR", "CAN"),
+        ))
+        .expect(1)
+        .create();
+    let positions_mock = server
+        .mock("GET", "/brokerage/accounts/11111111/positions")
+        .with_status(200)
+        .with_body(format!(
+            "{{\"Positions\":[{},{}],\"Errors\":[]}}",
+            position_json("PLTR", "10"),
+            position_json("TSLA", "10"),
+        ))
+        .expect(1)
+        .create();
+    let pltr_close_mock = server
+        .mock("POST", "/orderexecution/orders")
+        .match_body(mockito::Matcher::PartialJsonString(
+            "{\"Symbol\":\"PLTR\",\"TradeAction\":\"SELL\",\"Quantity\":\"10\"}".into(),
+        ))
+        .with_status(200)
+        .with_body(
+            "{\"Orders\":[{\"Message\":\"Sent order: Sell 10 PLTR @ Market\",\"OrderID\":\"3333333333\"}]}",
+        )
+        .expect(1)
+        .create();
+    let tsla_close_mock = server
+        .mock("POST", "/orderexecution/orders")
+        .match_body(mockito::Matcher::PartialJsonString(
+            "{\"Symbol\":\"TSLA\"}".into(),
+        ))
+        .expect(0)
+        .create();
+
+    let rt = tokio::runtime::Runtime::new().unwrap();
+    rt.block_on(async {
+        let client = ClientBuilder::new()
+            .environment(ClientEnvironment::Mock(server.url()))
+            .with_token(Token::dummy())
+            .build()
+            .await
+            .unwrap();
+
+        let report = account
+            .flatten_all(&client, &EmergencyFilter::new())
+            .await
+            .expect("The orders and positions should be fetched");
+
+        assert_eq!(report.cancellations.len(), 2);
+        assert_eq!(report.cancellations[1].symbols, vec!["TSLA"]);
+        assert!(report.cancellations[1].result.is_err());
+
+        assert_eq!(report.closures.len(), 2);
+        let pltr = &report.closures[0];
+        assert_eq!(pltr.result.as_ref().unwrap()[0].order_id, "3333333333");
+        let tsla = &report.closures[1];
+        assert_eq!(tsla.symbol, "TSLA");
+        assert!(matches!(tsla.result, Err(Error::PositionCloseSkipped(_))));
+
+        // Both the failed cancellation, and the skipped close, are failures
+        assert_eq!(report.failures().count(), 2);
+    });
+
+    // Ensure TSLA was never closed, since it's stop loss is still working
+    orders_mock.assert();
+    cancel_mock.assert();
+    failed_cancel_mock.assert();
+    canceled_mock.assert();
+    positions_mock.assert();
+    pltr_close_mock.assert();
+    tsla_close_mock.assert();
+}
+
+#[test]
+/// This test ensures closing orders placed by emergency controls bypass the kill
+/// switch and risk policy, but are only confirmed in dry-run mode.
+fn test_flatten_all_bypasses_blocks_mocked() {
+    let account = Account {
+        account_id: String::from("11111111"),
+        currency: String::from("USD"),
+        account_type: AccountType::Margin,
+        account_detail: None,
+    };
+
+    let mut server = Server::new();
+    let orders_mock = server
+        .mock("GET", "/brokerage/accounts/11111111/orders")
+        .with_status(200)
+        .with_body("{\"Orders\":[],\"Errors\":[]}")
+        .expect(2)
+        .create();
+    let positions_mock = server
+        .mock("GET", "/brokerage/accounts/11111111/positions")
+        .with_status(200)
+        .with_body(
+            "{\"Positions\":[{\"AccountID\":\"11111111\",\"AveragePrice\":\"83.205\",\"AssetType\":\"STOCK\",\"Last\":\"90.6\",\"Bid\":\"90.55\",\"Ask\":\"90.6\",\"ConversionRate\":\"1\",\"DayTradeRequirement\":\"0\",\"InitialRequirement\":\"0\",\"MaintenanceMargin\":\"0\",\"PositionID\":\"222222225\",\"LongShort\":\"Long\",\"Quantity\":\"100\",\"Symbol\":\"PLTR\",\"Timestamp\":\"2025-02-28T15:13:05Z\",\"TodaysProfitLoss\":\"-11.8\",\"TotalCost\":\"166.41\",\"MarketValue\":\"181.2\",\"MarkToMarketPrice\":\"96.5\",\"UnrealizedProfitLoss\":\"14.79\",\"UnrealizedProfitLossPercent\":\"8.888\",\"UnrealizedProfitLossQty\":\"7.4\"}],\"Errors\":[]}"
+        )
+        .expect(2)
+        .create();
+    let sell_mock = server
+        .mock("POST", "/orderexecution/orders")
+        .match_body(mockito::Matcher::PartialJsonString(
+            "{\"Symbol\":\"PLTR\",\"TradeAction\":\"SELL\",\"Quantity\":\"100\",\"OrderType\":\"Market\"}"
+                .into(),
+        ))
+        .with_status(200)
+        .with_body(
+            "{\"Orders\":[{\"Message\":\"Sent order: Sell 100 PLTR @ Market\",\"OrderID\":\"4444444444\"}]}",
+        )
+        .expect(1)
+        .create();
+    let confirm_mock = server
+        .mock("POST", "/orderexecution/orderconfirm")
+        .with_status(200)
+        .with_body(
+            "{\"Confirmations\":[{\"OrderAssetCategory\":\"EQUITY\",\"Currency\":\"USD\",\"Route\":\"Intelligent\",\"TimeInForce\":{\"Duration\":\"DAY\"},\"AccountID\":\"11111111\",\"OrderConfirmID\":\"i+wE/m+IrUX886i9/51TaH\",\"EstimatedPrice\":\"90.60\",\"EstimatedCost\":\"9060\",\"DebitCreditEstimatedCost\":\"9060\",\"EstimatedCommission\":\"0\",\"SummaryMessage\":\"Sell 100 PLTR @ Market\"}]}"
+        )
+        .expect(1)
+        .create();
+
+    let rt = tokio::runtime::Runtime::new().unwrap();
+    rt.block_on(async {
+        let client = ClientBuilder::new()
+            .environment(ClientEnvironment::Mock(server.url()))
+            .with_token(Token::dummy())
+            .build()
+            .await
+            .unwrap();
+        client.engage_kill_switch();
+        client.set_risk_policy(RiskPolicy::new().deny_symbol("PLTR"));
+
+        // Any other order is still blocked
+        let order_req = OrderRequestBuilder::new()
+            .account_id("11111111")
+            .symbol("PLTR")
+            .trade_action(TradeAction::Sell)
+            .quantity("100")
+            .order_type(OrderType::Market)
+            .time_in_force(OrderTimeInForce {
+                duration: Duration::DAY,
+                expiration: None,
+            })
+            .build()
+            .unwrap();
+        assert!(matches!(
+            Order::place(&client, &order_req).await,
+            Err(Error::KillSwitchEngaged)
+        ));
+
+        let report = account
+            .flatten_all(&client, &EmergencyFilter::new())
+            .await
+            .expect("The orders and positions should be fetched");
+        assert!(report.is_success());
+        assert_eq!(report.closures.len(), 1);
+        let tickets = report.closures[0].result.as_ref().unwrap();
+        assert_eq!(tickets[0].order_id, "4444444444");
+        assert!(!tickets[0].dry_run);
+
+        // In dry-run mode the closing order is only confirmed
+        client.set_dry_run(true);
+        let report = account
+            .flatten_all(&client, &EmergencyFilter::new())
+            .await
+            .expect("The orders and positions should be fetched");
+        let tickets = report.closures[0].result.as_ref().unwrap();
+        assert!(tickets[0].dry_run);
+        assert_eq!(tickets[0].order_id, "i+wE/m+IrUX886i9/51TaH");
+    });
+
+    // Ensure the position was closed once, then only confirmed in dry-run mode
+    orders_mock.assert();
+    positions_mock.assert();
+    sell_mock.assert();
+    confirm_mock.assert();
+}
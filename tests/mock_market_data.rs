@@ -1,7 +1,21 @@
-use mockito::Server;
+use futures::StreamExt;
+use mockito::{Matcher, Server};
 use tradestation::{
-    market_data::{BarUnit, GetBarsQueryBuilder, OptionTradeAction, OptionsLeg},
-    ClientBuilder, ClientEnvironment, Token,
+    market_data::{
+        analytics::years_until,
+        futures::ContractDates,
+        indicators::{Atr, BarSeries, BarValues, BollingerBands, Ema, Macd, Rsi, Sma, Vwap},
+        BackAdjustment, BarUnit, EuropeanOption, FuturesChain, GetBarsQueryBuilder,
+        LiveVolatilitySurface, OptionChain, OptionChainQueryBuilder, OptionQuoteLeg,
+        OptionQuoteQueryBuilder, OptionRiskRewardAnalysis, OptionSpreadType, OptionSymbol,
+        OptionSymbolBuilder, OptionSymbolExpiration, OptionTradeAction, OptionsLeg, PayoffEngine,
+        QuoteSide, QuoteSubscriptionManager, RollRule, RollSchedule, StreamBarsQueryBuilder,
+        VolatilitySurface,
+    },
+    orders::OptionType,
+    responses::market_data::{StreamBarsResp, StreamOptionChainResp},
+    stream::SharedStreamConfig,
+    ClientBuilder, ClientEnvironment, Error, Token,
 };
 
 #[test]
@@ -196,6 +210,89 @@ fn test_stock_get_symbol_details_mocked() {
     mock.assert();
 }
 
+#[test]
+/// This test ensures that concurrent lookups through the
+/// `SymbolDetailsCache` are batched into a single request,
+/// and that cached symbols aren't fetched again.
+fn test_symbol_details_cache_mocked() {
+    let mut server = Server::new();
+    let mock = server
+        .mock("GET", "/marketdata/symbols/NET,META")
+        .with_status(200)
+        .with_body(
+            "{\"Symbols\":[{\"AssetType\":\"STOCK\",\"Country\":\"United States\",\"Currency\":\"USD\",\"Description\":\"Meta Platforms Inc\",\"Exchange\":\"NASDAQ\",\"Symbol\":\"META\",\"Root\":\"META\",\"PriceFormat\":{\"Format\":\"Decimal\",\"Decimals\":\"2\",\"IncrementStyle\":\"Simple\",\"Increment\":\"0.01\",\"PointValue\":\"1\"},\"QuantityFormat\":{\"Format\":\"Decimal\",\"Decimals\":\"0\",\"IncrementStyle\":\"Simple\",\"Increment\":\"1\",\"MinimumTradeQuantity\":\"1\"}},{\"AssetType\":\"STOCK\",\"Country\":\"United States\",\"Currency\":\"USD\",\"Description\":\"CloudFlare Inc\",\"Exchange\":\"NYSE\",\"Symbol\":\"NET\",\"Root\":\"NET\",\"PriceFormat\":{\"Format\":\"Decimal\",\"Decimals\":\"2\",\"IncrementStyle\":\"Simple\",\"Increment\":\"0.01\",\"PointValue\":\"1\"},\"QuantityFormat\":{\"Format\":\"Decimal\",\"Decimals\":\"0\",\"IncrementStyle\":\"Simple\",\"Increment\":\"1\",\"MinimumTradeQuantity\":\"1\"}}],\"Errors\":[]}"
+        )
+        .expect(1)
+        .create();
+    let not_found_mock = server
+        .mock("GET", "/marketdata/symbols/ZZZZ")
+        .with_status(200)
+        .with_body("{\"Symbols\":[],\"Errors\":[]}")
+        .create();
+    // TradeStation returns the details of a continuous future under it's current contract
+    let renamed_mock = server
+        .mock("GET", "/marketdata/symbols/@ES")
+        .with_status(200)
+        .with_body(
+            "{\"Symbols\":[{\"AssetType\":\"FUTURE\",\"Country\":\"United States\",\"Currency\":\"USD\",\"Description\":\"E-mini S&P 500 Continuous Contract [Dec24]\",\"Exchange\":\"CME\",\"FutureType\":\"Electronic\",\"Symbol\":\"ESZ24\",\"Root\":\"ES\",\"Underlying\":\"ESZ24\",\"PriceFormat\":{\"Format\":\"Decimal\",\"Decimals\":\"2\",\"IncrementStyle\":\"Simple\",\"Increment\":\"0.25\",\"PointValue\":\"50\"},\"QuantityFormat\":{\"Format\":\"Decimal\",\"Decimals\":\"0\",\"IncrementStyle\":\"Simple\",\"Increment\":\"1\",\"MinimumTradeQuantity\":\"1\"}}],\"Errors\":[]}"
+        )
+        .expect(1)
+        .create();
+
+    let rt = tokio::runtime::Runtime::new().unwrap();
+    rt.block_on(async {
+        let client = ClientBuilder::new()
+            .environment(ClientEnvironment::Mock(server.url()))
+            .with_token(Token::dummy())
+            .build()
+            .await
+            .unwrap();
+
+        // Look up CloudFlare Stock (NET) and Meta Stock (META) concurrently,
+        // which should share a single request.
+        let (price_format, point_value, details) = tokio::join!(
+            client.get_price_format("NET"),
+            client.get_point_value("META"),
+            client.get_cached_symbol_details(&["NET", "META"]),
+        );
+        assert_eq!(price_format.unwrap().tick_size(100.0).unwrap(), 0.01);
+        assert_eq!(point_value.unwrap(), 1.0);
+        let details = details.unwrap();
+        assert_eq!(details[0].symbol, "NET");
+        assert_eq!(details[1].symbol, "META");
+        assert_eq!(client.symbol_cache().len(), 2);
+
+        // Both symbols are cached now, so this shouldn't send another request
+        let details = client
+            .get_cached_symbol_details(&["meta", "NET"])
+            .await
+            .unwrap();
+        assert_eq!(details[0].symbol, "META");
+
+        // Symbols TradeStation returns no details for are an error
+        assert!(matches!(
+            client.get_price_format("ZZZZ").await,
+            Err(Error::SymbolNotFound(_))
+        ));
+
+        // Details returned under a different symbol are cached under
+        // both the requested and returned symbol
+        assert_eq!(client.get_point_value("@ES").await.unwrap(), 50.0);
+        assert_eq!(client.symbol_cache().len(), 4);
+        let details = client
+            .get_cached_symbol_details(&["@es", "ESZ24"])
+            .await
+            .unwrap();
+        assert_eq!(details[0].symbol, "ESZ24");
+        assert_eq!(details[1].symbol, "ESZ24");
+    });
+
+    // Ensure the mocks were called, and `NET,META` and `@ES` only once
+    mock.assert();
+    not_found_mock.assert();
+    renamed_mock.assert();
+}
+
 #[test]
 /// This test ensures that the parsing of
 /// getting `SymbolDetails` is correct.
@@ -360,3 +457,928 @@ fn test_analyze_option_risk_reward_mocked() {
     // Ensure the mock was called
     mock.assert();
 }
+
+#[test]
+/// This test ensures that the local `PayoffEngine` analysis
+/// matches TradeStation's risk vs reward analysis.
+fn test_run_option_risk_reward_locally_mocked() {
+    // Mock the `options/riskreward` endpoint with a raw JSON
+    // string which was a real response from the API.
+    let mut server = Server::new();
+    let mock = server
+        .mock("POST", "/marketdata/options/riskreward")
+        .with_status(200)
+        .with_body(
+            "{\"MaxGainIsInfinite\":true,\"AdjustedMaxGain\":\"0\",\"MaxLossIsInfinite\":false,\"AdjustedMaxLoss\":\"-4400\",\"BreakevenPoints\":[\"88.6\",\"97.4\"]}"
+        )
+        .create();
+
+    let rt = tokio::runtime::Runtime::new().unwrap();
+    rt.block_on(async {
+        let client = ClientBuilder::new()
+            .environment(ClientEnvironment::Mock(server.url()))
+            .with_token(Token::dummy())
+            .build()
+            .await
+            .unwrap();
+
+        // The same long TLT straddle as `test_analyze_option_risk_reward_mocked`
+        let price = 4.40;
+        let option_legs = vec![
+            OptionsLeg {
+                symbol: "TLT 250516C93".into(),
+                quantity: 10,
+                trade_action: OptionTradeAction::Buy,
+            },
+            OptionsLeg {
+                symbol: "TLT 250516P93".into(),
+                quantity: 10,
+                trade_action: OptionTradeAction::Buy,
+            },
+        ];
+
+        let analysis = client
+            .analyze_options_risk_reward(price, option_legs.clone())
+            .await
+            .unwrap();
+        let local_analysis = OptionRiskRewardAnalysis::run_locally(price, &option_legs).unwrap();
+        assert_eq!(
+            local_analysis.max_gain_is_infinite,
+            analysis.max_gain_is_infinite
+        );
+        assert_eq!(local_analysis.adjusted_max_gain, analysis.adjusted_max_gain);
+        assert_eq!(
+            local_analysis.max_loss_is_infinite,
+            analysis.max_loss_is_infinite
+        );
+        assert_eq!(local_analysis.adjusted_max_loss, analysis.adjusted_max_loss);
+        assert_eq!(local_analysis.breakeven_points, analysis.breakeven_points);
+
+        let engine = PayoffEngine::new(price, &option_legs).unwrap();
+        assert_eq!(engine.spread_quantity(), 10);
+        assert!((engine.cost() - 4400.0).abs() < 1e-9);
+
+        // The straddle loses the most at the strike, and gains moving away from it
+        let curve = engine.payoff_curve(83.0, 103.0, 4);
+        let payoffs: Vec<f64> = curve.iter().map(|point| point.profit_and_loss).collect();
+        for (payoff, expected) in payoffs.iter().zip([5600.0, 600.0, -4400.0, 600.0, 5600.0]) {
+            assert!((payoff - expected).abs() < 1e-9);
+        }
+
+        // Expired legs are worth their intrinsic value before "expiration"
+        let surface = engine.profit_and_loss_surface(&[93.0, 100.0], &[0.0], 0.2);
+        assert!((surface.profit_and_loss[0][0] + 4400.0).abs() < 1e-9);
+        assert!((surface.profit_and_loss[0][1] - 2600.0).abs() < 1e-9);
+        assert!(surface.to_csv().starts_with("days_forward,93,100\n0,"));
+    });
+
+    // Ensure the mock was called
+    mock.assert();
+}
+
+#[test]
+/// This test ensures that the `PayoffEngine` finds the limited gain,
+/// loss, and breakeven point of a vertical spread.
+fn test_payoff_engine_vertical_spread() {
+    // Bull call spread on SPY for a $4 debit
+    let option_legs = vec![
+        OptionsLeg {
+            symbol: "SPY 300118C100".into(),
+            quantity: 2,
+            trade_action: OptionTradeAction::Buy,
+        },
+        OptionsLeg {
+            symbol: "SPY 300118C110".into(),
+            quantity: 2,
+            trade_action: OptionTradeAction::Sell,
+        },
+    ];
+
+    let analysis = OptionRiskRewardAnalysis::run_locally(4.0, &option_legs).unwrap();
+    assert!(!analysis.max_gain_is_infinite);
+    assert!(!analysis.max_loss_is_infinite);
+    assert_eq!(analysis.adjusted_max_gain, "1200");
+    assert_eq!(analysis.adjusted_max_loss, "-800");
+    assert_eq!(analysis.breakeven_points, vec!["104"]);
+
+    let engine = PayoffEngine::new(4.0, &option_legs).unwrap();
+    assert!((engine.payoff_at_expiration(90.0) + 800.0).abs() < 1e-9);
+    assert!((engine.payoff_at_expiration(107.0) - 600.0).abs() < 1e-9);
+    assert!((engine.payoff_at_expiration(150.0) - 1200.0).abs() < 1e-9);
+
+    // Before expiration the spread is worth less than it's max gain, and more than it's max loss
+    let profit_and_loss = engine.profit_and_loss(105.0, 0.0, 0.2);
+    assert!(profit_and_loss > -800.0 && profit_and_loss < 1200.0);
+}
+
+#[test]
+/// This test ensures that the `QuoteSubscriptionManager` shares
+/// a single stream between subscribers, only fans out the symbols
+/// each subscriber asked for, and closes the stream once unused.
+fn test_quote_subscription_manager_mocked() {
+    // Mock the `stream/quotes` endpoint with newline
+    // delimited quote updates for both symbols.
+    let mut server = Server::new();
+    let mock = server
+        .mock("GET", "/marketdata/stream/quotes/QQQ,SPY")
+        .with_status(200)
+        .with_body(
+            "{\"Symbol\":\"SPY\",\"Last\":\"570.12\"}\n{\"Symbol\":\"QQQ\",\"Last\":\"490.33\"}\n{\"Heartbeat\":1,\"Timestamp\":\"2025-04-02T23:59:22Z\"}\n"
+        )
+        .expect_at_least(1)
+        .create();
+
+    let rt = tokio::runtime::Runtime::new().unwrap();
+    rt.block_on(async {
+        let client = ClientBuilder::new()
+            .environment(ClientEnvironment::Mock(server.url()))
+            .with_token(Token::dummy())
+            .build()
+            .await
+            .unwrap();
+
+        let manager = QuoteSubscriptionManager::new(&client);
+        let mut both = manager.subscribe(vec!["SPY", "QQQ"]).unwrap();
+        let mut spy_only = manager.subscribe(vec!["spy"]).unwrap();
+
+        // Both subscribers should be sharing the same stream
+        assert_eq!(manager.stream_count(), 1);
+        assert_eq!(manager.symbols(), vec!["QQQ", "SPY"]);
+
+        let timeout = std::time::Duration::from_secs(10);
+        let mut received = Vec::new();
+        while received.len() < 2 {
+            let update = tokio::time::timeout(timeout, both.recv())
+                .await
+                .expect("Timed out waiting for a quote update")
+                .unwrap();
+            if !received.contains(&update.symbol) {
+                received.push(update.symbol);
+            }
+        }
+
+        // The second subscriber should only ever see SPY updates
+        for _ in 0..2 {
+            let update = tokio::time::timeout(timeout, spy_only.recv())
+                .await
+                .expect("Timed out waiting for a quote update")
+                .unwrap();
+            assert_eq!(update.symbol, "SPY");
+        }
+
+        // Dropping every subscription should close the stream
+        drop(both);
+        drop(spy_only);
+        assert_eq!(manager.stream_count(), 0);
+    });
+
+    // Ensure the mock was called
+    mock.assert();
+}
+
+#[test]
+/// This test ensures that a slow `QuoteSubscription` only buffers
+/// up to it's buffer, skipping the oldest updates it missed.
+fn test_quote_subscription_buffer_mocked() {
+    // Mock the `stream/quotes` endpoint with more
+    // quote updates than the subscription buffers.
+    let mut server = Server::new();
+    let mock = server
+        .mock("GET", "/marketdata/stream/quotes/SPY")
+        .with_status(200)
+        .with_body(
+            (1..=5)
+                .map(|last| format!("{{\"Symbol\":\"SPY\",\"Last\":\"{last}\"}}\n"))
+                .collect::<String>(),
+        )
+        .expect_at_least(1)
+        .create();
+
+    let rt = tokio::runtime::Runtime::new().unwrap();
+    rt.block_on(async {
+        let client = ClientBuilder::new()
+            .environment(ClientEnvironment::Mock(server.url()))
+            .with_token(Token::dummy())
+            .build()
+            .await
+            .unwrap();
+
+        let manager = QuoteSubscriptionManager::new(&client);
+        let mut subscription = manager.subscribe_with_buffer(vec!["SPY"], 2).unwrap();
+
+        // Don't receive anything until the first stream was fully sent
+        while !mock.matched() {
+            tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+        }
+        tokio::time::sleep(std::time::Duration::from_millis(200)).await;
+
+        // Only the newest updates should still be buffered
+        let timeout = std::time::Duration::from_secs(10);
+        for expected in ["4", "5"] {
+            let update = tokio::time::timeout(timeout, subscription.recv())
+                .await
+                .expect("Timed out waiting for a quote update")
+                .unwrap();
+            assert_eq!(update.last.as_deref(), Some(expected));
+        }
+    });
+}
+
+#[test]
+/// This test ensures that every subscriber of a shared
+/// `Bar` stream receives every bar from a single stream.
+fn test_share_bars_stream_mocked() {
+    // Mock the `stream/barcharts` endpoint with newline
+    // delimited bars, which only allows a single connection.
+    let mut server = Server::new();
+    let mock = server
+        .mock("GET", "/marketdata/stream/barcharts/MSFT")
+        .match_query(Matcher::Any)
+        .with_status(200)
+        .with_chunked_body(|writer| {
+            // Give the test time to add every subscriber before streaming
+            std::thread::sleep(std::time::Duration::from_millis(250));
+            writer.write_all(
+                "{\"High\":\"396.36\",\"Low\":\"392.64\",\"Open\":\"393.92\",\"Close\":\"395.16\",\"TimeStamp\":\"2025-03-25T20:00:00Z\",\"TotalVolume\":\"15774968\",\"DownTicks\":131319,\"DownVolume\":6708452,\"OpenInterest\":\"0\",\"IsRealtime\":true,\"IsEndOfHistory\":false,\"TotalTicks\":262246,\"UnchangedTicks\":0,\"UnchangedVolume\":0,\"UpTicks\":130927,\"UpVolume\":9066516,\"Epoch\":1742932800000,\"BarStatus\":\"Closed\"}\n{\"High\":\"395.31\",\"Low\":\"388.57\",\"Open\":\"395\",\"Close\":\"389.97\",\"TimeStamp\":\"2025-03-26T20:00:00Z\",\"TotalVolume\":\"16132906\",\"DownTicks\":141585,\"DownVolume\":6585638,\"OpenInterest\":\"0\",\"IsRealtime\":true,\"IsEndOfHistory\":false,\"TotalTicks\":283674,\"UnchangedTicks\":0,\"UnchangedVolume\":0,\"UpTicks\":142089,\"UpVolume\":9547268,\"Epoch\":1743019200000,\"BarStatus\":\"Open\"}\n"
+                .as_bytes(),
+            )
+        })
+        .expect(1)
+        .create();
+
+    let rt = tokio::runtime::Runtime::new().unwrap();
+    rt.block_on(async {
+        let client = ClientBuilder::new()
+            .environment(ClientEnvironment::Mock(server.url()))
+            .with_token(Token::dummy())
+            .build()
+            .await
+            .unwrap();
+
+        let query = StreamBarsQueryBuilder::new()
+            .symbol("MSFT")
+            .unit(BarUnit::Minute)
+            .build()
+            .unwrap();
+
+        let mut strategy_feed = client.share_bars_stream(&query, SharedStreamConfig::new());
+        let mut chart_feed = strategy_feed.clone();
+        assert_eq!(strategy_feed.subscriber_count(), 2);
+
+        // Both subscribers should get both bars, then the end of the stream
+        for feed in [&mut strategy_feed, &mut chart_feed] {
+            for _ in 0..2 {
+                match feed.recv().await {
+                    Some(Ok(StreamBarsResp::Bar(_))) => {}
+                    other => panic!("Expected a streamed `Bar`, got: {other:?}"),
+                }
+            }
+            assert!(feed.recv().await.is_none());
+        }
+    });
+
+    // Ensure the mock was only called once
+    mock.assert();
+}
+
+/// The closes of Wilder's classic RSI example.
+const INDICATOR_CLOSES: [f64; 15] = [
+    44.34, 44.09, 44.15, 43.61, 44.33, 44.83, 45.10, 45.42, 45.84, 46.08, 45.89, 46.03, 45.61,
+    46.28, 46.28,
+];
+
+/// Build closed bars out of high, low, close, and volume values.
+fn indicator_bars(bars: &[(f64, f64, f64, f64)]) -> Vec<BarValues> {
+    bars.iter()
+        .enumerate()
+        .map(|(epoch, &(high, low, close, volume))| BarValues {
+            epoch: epoch as i64,
+            open: close,
+            high,
+            low,
+            close,
+            volume,
+            is_closed: true,
+        })
+        .collect()
+}
+
+/// Check two values are the same, within floating point error.
+fn assert_close(actual: f64, expected: f64) {
+    assert!(
+        (actual - expected).abs() < 1e-9,
+        "expected {expected}, got {actual}"
+    );
+}
+
+#[test]
+/// This test ensures that each indicator matches known values.
+fn test_indicator_values() {
+    let closes: Vec<BarValues> = INDICATOR_CLOSES
+        .iter()
+        .enumerate()
+        .map(|(epoch, &close)| BarValues::from_close(epoch as i64, close, true))
+        .collect();
+
+    let mut sma = BarSeries::new(Sma::new(5));
+    let mut ema = BarSeries::new(Ema::new(5));
+    let mut rsi = BarSeries::new(Rsi::new(14));
+    let mut macd = BarSeries::new(Macd::new(3, 6, 2));
+    let mut bollinger = BarSeries::new(BollingerBands::new(5, 2.0));
+    for bar in &closes {
+        sma.update_values(*bar);
+        ema.update_values(*bar);
+        rsi.update_values(*bar);
+        macd.update_values(*bar);
+        bollinger.update_values(*bar);
+    }
+
+    assert_close(*sma.value().unwrap(), 46.018);
+    assert_close(*ema.value().unwrap(), 46.02628217243307);
+    assert_close(*rsi.value().unwrap(), 70.46413502109705);
+
+    let macd = macd.value().unwrap();
+    assert_close(macd.macd, 0.19832370298215807);
+    assert_close(macd.signal, 0.1977389379847433);
+    assert_close(macd.histogram, 0.0005847649974147773);
+
+    let bands = bollinger.value().unwrap();
+    assert_close(bands.upper, 46.52418573666195);
+    assert_close(bands.middle, 46.018);
+    assert_close(bands.lower, 45.51181426333805);
+
+    // Not enough bars seen yet
+    let mut rsi = BarSeries::new(Rsi::new(14));
+    for bar in &closes[..14] {
+        assert_eq!(rsi.update_values(*bar), None);
+    }
+
+    let bars = indicator_bars(&[
+        (10.0, 9.0, 9.5, 100.0),
+        (11.0, 9.5, 10.5, 200.0),
+        (12.0, 10.5, 11.0, 300.0),
+        (11.5, 10.0, 11.0, 400.0),
+        (13.0, 11.5, 12.5, 500.0),
+    ]);
+    let mut atr = BarSeries::new(Atr::new(3));
+    let mut vwap = BarSeries::new(Vwap::new());
+    for bar in &bars {
+        atr.update_values(*bar);
+        vwap.update_values(*bar);
+    }
+
+    assert_close(*atr.value().unwrap(), 1.5925925925925923);
+    assert_close(*vwap.value().unwrap(), 11.244444444444445);
+}
+
+#[test]
+/// This test ensures that an open bar only produces a provisional
+/// value, and is only committed into the indicator once it closes,
+/// or once a newer bar shows up.
+fn test_indicator_open_bar_updates() {
+    let mut ema = BarSeries::new(Ema::new(3));
+    for (epoch, close) in [(1, 10.0), (2, 11.0), (3, 12.0)] {
+        ema.update_values(BarValues::from_close(epoch, close, true));
+    }
+    assert_close(*ema.value().unwrap(), 11.0);
+
+    // Updates of the open bar replace each other
+    assert_close(
+        ema.update_values(BarValues::from_close(4, 15.0, false))
+            .unwrap(),
+        13.0,
+    );
+    assert_close(
+        ema.update_values(BarValues::from_close(4, 13.0, false))
+            .unwrap(),
+        12.0,
+    );
+
+    // Closing the bar commits it's final value, sending it again changes nothing
+    assert_close(
+        ema.update_values(BarValues::from_close(4, 13.0, true))
+            .unwrap(),
+        12.0,
+    );
+    assert_close(
+        ema.update_values(BarValues::from_close(4, 99.0, true))
+            .unwrap(),
+        12.0,
+    );
+
+    // An open bar followed by a newer bar is committed with it's last update
+    ema.update_values(BarValues::from_close(5, 16.0, false));
+    assert_close(
+        ema.update_values(BarValues::from_close(6, 14.0, false))
+            .unwrap(),
+        14.0,
+    );
+
+    // Older bars, sent out of order, are ignored
+    assert_close(
+        ema.update_values(BarValues::from_close(2, 1.0, true))
+            .unwrap(),
+        14.0,
+    );
+    assert_close(
+        ema.update_values(BarValues::from_close(5, 1.0, false))
+            .unwrap(),
+        14.0,
+    );
+    assert_close(
+        ema.update_values(BarValues::from_close(6, 14.0, true))
+            .unwrap(),
+        14.0,
+    );
+}
+#[test]
+/// This test ensures that the implied volatility and greeks solved from a
+/// streamed `OptionChain` match the values TradeStation reported for it.
+fn test_compare_with_option_chain_mocked() {
+    // Mock the `stream/options/chains` endpoint with a 3 month at the money
+    // call and put on a $100 stock, priced at 25% volatility with a 5% rate.
+    let mut server = Server::new();
+    let mock = server
+        .mock("GET", "/marketdata/stream/options/chains/SPY")
+        .match_query(Matcher::UrlEncoded("expiration".into(), "2030-01-18".into()))
+        .with_status(200)
+        .with_body(
+            [
+                "{\"Delta\":\"0.5645\",\"Theta\":\"-0.0339\",\"Gamma\":\"0.0315\",\"Rho\":\"0.1271\",\"Vega\":\"0.1969\",\"ImpliedVolatility\":\"0.25\",\"IntrinsicValue\":\"0\",\"ExtrinsicValue\":\"5.5984\",\"TheoreticalValue\":\"5.5984\",\"DailyOpenInterest\":100,\"Ask\":\"5.65\",\"Bid\":\"5.55\",\"Mid\":\"5.5984\",\"AskSize\":10,\"BidSize\":10,\"Close\":\"5.6\",\"High\":\"5.7\",\"Last\":\"5.6\",\"Low\":\"5.5\",\"NetChange\":\"0\",\"NetChangePct\":\"0\",\"Open\":\"5.6\",\"PreviousClose\":\"5.6\",\"Volume\":50,\"Side\":\"Call\",\"Strikes\":[\"100\"],\"Legs\":[{\"Symbol\":\"SPY 300118C100\",\"Ratio\":1,\"StrikePrice\":\"100\",\"Expiration\":\"2030-01-18T00:00:00Z\",\"OptionType\":\"Call\",\"AssetType\":\"STOCKOPTION\"}]}\n",
+                "{\"Delta\":\"-0.4355\",\"Theta\":\"-0.0204\",\"Gamma\":\"0.0315\",\"Rho\":\"-0.1198\",\"Vega\":\"0.1969\",\"ImpliedVolatility\":\"0.25\",\"IntrinsicValue\":\"0\",\"ExtrinsicValue\":\"4.3562\",\"TheoreticalValue\":\"4.3562\",\"DailyOpenInterest\":100,\"Ask\":\"4.4\",\"Bid\":\"4.3\",\"Mid\":\"4.3562\",\"AskSize\":10,\"BidSize\":10,\"Close\":\"4.35\",\"High\":\"4.45\",\"Last\":\"4.35\",\"Low\":\"4.25\",\"NetChange\":\"0\",\"NetChangePct\":\"0\",\"Open\":\"4.35\",\"PreviousClose\":\"4.35\",\"Volume\":50,\"Side\":\"Put\",\"Strikes\":[\"100\"],\"Legs\":[{\"Symbol\":\"SPY 300118P100\",\"Ratio\":1,\"StrikePrice\":\"100\",\"Expiration\":\"2030-01-18T00:00:00Z\",\"OptionType\":\"Put\",\"AssetType\":\"STOCKOPTION\"}]}\n",
+            ]
+            .concat(),
+        )
+        .create();
+
+    let rt = tokio::runtime::Runtime::new().unwrap();
+    rt.block_on(async {
+        let client = ClientBuilder::new()
+            .environment(ClientEnvironment::Mock(server.url()))
+            .with_token(Token::dummy())
+            .build()
+            .await
+            .unwrap();
+
+        let query = OptionChainQueryBuilder::new()
+            .underlying("SPY")
+            .expiration("2030-01-18")
+            .build()
+            .unwrap();
+
+        let chains: Vec<OptionChain> = client
+            .stream_option_chain(&query)
+            .filter_map(|resp| async {
+                match resp.unwrap() {
+                    StreamOptionChainResp::OptionChain(chain) => Some(*chain),
+                    _ => None,
+                }
+            })
+            .collect()
+            .await;
+        assert_eq!(chains.len(), 2);
+
+        for (chain, option_type) in chains.iter().zip([OptionType::Call, OptionType::Put]) {
+            // Fix the time to expiration, so the test doesn't depend on today's date
+            let option = EuropeanOption::black_scholes(option_type, 100.0, 100.0, 0.25, 0.05);
+
+            // The solver should find the volatility the option was priced at
+            let implied_volatility = option
+                .implied_volatility_from_chain(chain, QuoteSide::Mid)
+                .unwrap();
+            assert!((implied_volatility - 0.25).abs() < 1e-4);
+
+            // Greeks are reported to 4 decimal places
+            let comparison = option.compare_with_chain(chain, QuoteSide::Mid).unwrap();
+            assert!(comparison.implied_volatility_difference().unwrap() < 1e-4);
+            assert!(comparison.greeks_difference().unwrap() < 1e-4);
+
+            // The bid and ask bracket the volatility of the mid
+            let bid_iv = option
+                .implied_volatility_from_chain(chain, QuoteSide::Bid)
+                .unwrap();
+            let ask_iv = option
+                .implied_volatility_from_chain(chain, QuoteSide::Ask)
+                .unwrap();
+            assert!(bid_iv < implied_volatility && implied_volatility < ask_iv);
+        }
+
+        // A malformed (non-ASCII) timezone offset is an error, not a panic
+        assert!(years_until("2030-01-18T00:00:00€123", 0).is_err());
+    });
+
+    // Ensure the mock was called
+    mock.assert();
+}
+
+/// Build a streamed `OptionChain` JSON line for a single option.
+fn option_chain_line(
+    expiration: &str,
+    option_type: &str,
+    strike: &str,
+    iv: &str,
+    delta: &str,
+) -> String {
+    format!(
+        "{{\"Delta\":\"{delta}\",\"Theta\":\"-0.05\",\"Gamma\":\"0.01\",\"Rho\":\"0.2\",\"Vega\":\"0.6\",\"ImpliedVolatility\":\"{iv}\",\"IntrinsicValue\":\"0\",\"ExtrinsicValue\":\"5\",\"TheoreticalValue\":\"5\",\"DailyOpenInterest\":100,\"Ask\":\"5.1\",\"Bid\":\"4.9\",\"Mid\":\"5\",\"AskSize\":10,\"BidSize\":10,\"Close\":\"5\",\"High\":\"5.2\",\"Last\":\"5\",\"Low\":\"4.8\",\"NetChange\":\"0\",\"NetChangePct\":\"0\",\"Open\":\"5\",\"PreviousClose\":\"5\",\"Volume\":50,\"Side\":\"{option_type}\",\"Strikes\":[\"{strike}\"],\"Legs\":[{{\"Symbol\":\"SPY\",\"Ratio\":1,\"StrikePrice\":\"{strike}\",\"Expiration\":\"{expiration}\",\"OptionType\":\"{option_type}\",\"AssetType\":\"STOCKOPTION\"}}]}}\n"
+    )
+}
+
+#[test]
+/// This test ensures that a `LiveVolatilitySurface` streams the
+/// option chains of each expiration into a single surface.
+fn test_live_volatility_surface_mocked() {
+    // Mock the `expirations` endpoint, and the `stream/options/chains`
+    // endpoint with a call and put at two strikes per expiration.
+    let mut server = Server::new();
+    let expirations_mock = server
+        .mock("GET", "/marketdata/options/expirations/SPY")
+        .with_status(200)
+        .with_body(
+            "{\"Expirations\":[{\"Date\":\"2030-01-18T00:00:00Z\",\"Type\":\"Monthly\"},{\"Date\":\"2030-02-15T00:00:00Z\",\"Type\":\"Monthly\"},{\"Date\":\"2030-03-15T00:00:00Z\",\"Type\":\"Monthly\"}]}"
+        )
+        .create();
+
+    let mut chain_mocks = Vec::new();
+    for (date, expiration, atm_iv) in [
+        ("2030-01-18", "2030-01-18T00:00:00Z", "0.2"),
+        ("2030-02-15", "2030-02-15T00:00:00Z", "0.22"),
+    ] {
+        let body = [
+            option_chain_line(expiration, "Call", "500", atm_iv, "0.5"),
+            option_chain_line(expiration, "Put", "500", atm_iv, "-0.5"),
+            option_chain_line(expiration, "Call", "550", "0.18", "0.25"),
+            option_chain_line(expiration, "Put", "450", "0.26", "-0.25"),
+        ]
+        .concat();
+
+        chain_mocks.push(
+            server
+                .mock("GET", "/marketdata/stream/options/chains/SPY")
+                .match_query(Matcher::UrlEncoded("expiration".into(), date.into()))
+                .with_status(200)
+                .with_body(body)
+                .expect_at_least(1)
+                .create(),
+        );
+    }
+
+    let rt = tokio::runtime::Runtime::new().unwrap();
+    rt.block_on(async {
+        let client = ClientBuilder::new()
+            .environment(ClientEnvironment::Mock(server.url()))
+            .with_token(Token::dummy())
+            .build()
+            .await
+            .unwrap();
+
+        let live_surface = LiveVolatilitySurface::from_next_expirations(
+            &client,
+            VolatilitySurface::new("SPY"),
+            2,
+            2,
+        )
+        .await
+        .unwrap();
+
+        // Wait for both expirations to be streamed into the surface
+        let surface = tokio::time::timeout(std::time::Duration::from_secs(10), async {
+            loop {
+                let surface = live_surface.snapshot();
+                if surface.atm_term_structure().len() == 2 && surface.skew().len() == 2 {
+                    break surface;
+                }
+                tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+            }
+        })
+        .await
+        .expect("Timed out waiting for the volatility surface");
+
+        assert_eq!(
+            surface.expirations(),
+            vec!["2030-01-18T00:00:00Z", "2030-02-15T00:00:00Z"]
+        );
+
+        let term_structure = surface.atm_term_structure();
+        assert!((term_structure[0].implied_volatility - 0.2).abs() < 1e-9);
+        assert!((term_structure[1].implied_volatility - 0.22).abs() < 1e-9);
+
+        for risk_reversal in surface.skew() {
+            assert!((risk_reversal.risk_reversal + 0.08).abs() < 1e-9);
+        }
+
+        // Missing strikes should be interpolated within the smile
+        let smile_iv = surface
+            .implied_volatility_at_strike("2030-01-18", 475.0)
+            .unwrap();
+        assert!((smile_iv - 0.23).abs() < 1e-9);
+
+        let grid = surface.grid(&[450.0, 500.0, 550.0]);
+        assert_eq!(grid.implied_volatilities.len(), 2);
+        assert!(grid
+            .to_csv()
+            .starts_with("expiration,450,500,550\n2030-01-18T00:00:00Z,0.26,0.2,0.18"));
+    });
+
+    // Ensure the mocks were called
+    expirations_mock.assert();
+    for mock in chain_mocks {
+        mock.assert();
+    }
+}
+
+#[test]
+/// This test ensures that equity and futures option symbols round trip
+/// between being parsed and formatted, and malformed symbols are rejected.
+fn test_option_symbol_round_trip() {
+    for symbol in [
+        "MSFT 241220C420",
+        "SPY 300118P512.5",
+        "TLT 250516C93",
+        "SPXW 240229P4950",
+        "CLZ24 C75",
+        "ESH25 P5100.25",
+    ] {
+        let parsed = OptionSymbol::parse(symbol).unwrap();
+        assert_eq!(parsed.to_string(), symbol);
+        assert_eq!(OptionSymbol::parse(&parsed.to_string()).unwrap(), parsed);
+    }
+
+    // Lowercase and padded symbols are normalized
+    let parsed = OptionSymbol::parse("  msft 241220c420 ").unwrap();
+    assert_eq!(parsed.to_string(), "MSFT 241220C420");
+
+    // Built symbols format the same as the symbols they're parsed from
+    let equity_option = OptionSymbolBuilder::new()
+        .root("SPY")
+        .expiration("2030-01-18")
+        .option_type(OptionType::Put)
+        .strike(512.5)
+        .build()
+        .unwrap();
+    assert_eq!(equity_option.to_string(), "SPY 300118P512.5");
+    assert_eq!(
+        equity_option.expiration,
+        OptionSymbolExpiration::Date {
+            year: 2030,
+            month: 1,
+            day: 18
+        }
+    );
+
+    let futures_option = OptionSymbolBuilder::new()
+        .root("CL")
+        .contract_month(2024, 12)
+        .option_type(OptionType::Call)
+        .strike(75.0)
+        .build()
+        .unwrap();
+    assert_eq!(futures_option.to_string(), "CLZ24 C75");
+    assert_eq!(futures_option.underlying(), "CLZ24");
+    assert!(futures_option.is_futures_option());
+    assert_eq!(futures_option.expiration_date(), None);
+
+    for malformed in [
+        "",
+        "MSFT",
+        "MSFT ",
+        " 241220C420",
+        "MSFT 241220X420",
+        "MSFT 241220C",
+        "MSFT 241220C-420",
+        "MSFT 241220C0",
+        "MSFT 241320C420",
+        "MSFT 240230C420",
+        "MSFT 24122C420",
+        "MSFT 2412€0C420",
+        "MSFT 241220C42O",
+        "CLZ C75",
+        "CLI24 C75",
+        "CLZ24 C",
+        "CLZ24 X75",
+    ] {
+        assert!(
+            matches!(
+                OptionSymbol::parse(malformed),
+                Err(Error::InvalidOptionSymbol(_))
+            ),
+            "{malformed:?} should be an invalid option symbol"
+        );
+        assert!(!OptionSymbol::is_valid(malformed));
+    }
+
+    // Leg symbols are only validated when opted into
+    let legs = vec![OptionQuoteLeg {
+        symbol: "MSFT 2412€0C420".into(),
+        ratio: 1,
+    }];
+    assert!(OptionQuoteQueryBuilder::new()
+        .legs(legs.clone())
+        .build()
+        .is_ok());
+    assert!(matches!(
+        OptionQuoteQueryBuilder::new()
+            .legs(legs)
+            .validate_option_symbols(true)
+            .build(),
+        Err(Error::InvalidOptionSymbol(_))
+    ));
+    assert!(OptionQuoteQueryBuilder::new()
+        .legs(vec![
+            equity_option.quote_leg(1),
+            futures_option.quote_leg(-1)
+        ])
+        .validate_option_symbols(true)
+        .build()
+        .is_ok());
+}
+
+/// Build a daily `Bar` JSON object closing at 4PM CT on a day in October 2024.
+fn october_2024_bar(day: u32, epoch: i64, close: &str) -> String {
+    format!(
+        "{{\"High\":\"{close}\",\"Low\":\"{close}\",\"Open\":\"{close}\",\"Close\":\"{close}\",\"TimeStamp\":\"2024-10-{day}T21:00:00Z\",\"TotalVolume\":\"1000\",\"DownTicks\":10,\"DownVolume\":500,\"OpenInterest\":\"0\",\"IsRealtime\":false,\"IsEndOfHistory\":false,\"TotalTicks\":20,\"UnchangedTicks\":0,\"UnchangedVolume\":0,\"UpTicks\":10,\"UpVolume\":500,\"Epoch\":{epoch},\"BarStatus\":\"Closed\"}}"
+    )
+}
+
+#[test]
+/// This test ensures that the bars of each futures contract are
+/// stitched into a back adjusted continuous series at each roll.
+fn test_get_continuous_bars_mocked() {
+    // Mock the `barcharts` endpoint for the November and December
+    // 2024 crude oil contracts, which overlap around the roll.
+    let mut server = Server::new();
+    let november_mock = server
+        .mock("GET", "/marketdata/barcharts/CLX24")
+        .match_query(Matcher::Any)
+        .with_status(200)
+        .with_body(format!(
+            "{{\"Bars\":[{},{},{}]}}",
+            october_2024_bar(17, 1729198800000, "69"),
+            october_2024_bar(18, 1729285200000, "70"),
+            october_2024_bar(21, 1729544400000, "70.5"),
+        ))
+        .create();
+    let december_mock = server
+        .mock("GET", "/marketdata/barcharts/CLZ24")
+        .match_query(Matcher::Any)
+        .with_status(200)
+        .with_body(format!(
+            "{{\"Bars\":[{},{},{},{}]}}",
+            october_2024_bar(17, 1729198800000, "70"),
+            october_2024_bar(18, 1729285200000, "71"),
+            october_2024_bar(21, 1729544400000, "72"),
+            october_2024_bar(22, 1729630800000, "73"),
+        ))
+        .create();
+
+    let rt = tokio::runtime::Runtime::new().unwrap();
+    rt.block_on(async {
+        let client = ClientBuilder::new()
+            .environment(ClientEnvironment::Mock(server.url()))
+            .with_token(Token::dummy())
+            .build()
+            .await
+            .unwrap();
+
+        // Roll from November into December on the last trading date of November
+        let contracts = FuturesChain::monthly("CL").contracts_from(2024, 11, 2);
+        let schedule = RollSchedule::new(
+            vec![
+                ContractDates {
+                    contract: contracts[0].clone(),
+                    expiration_date: Some("2024-10-22T00:00:00Z".into()),
+                    first_notice_date: None,
+                    last_trading_date: Some("2024-10-21T00:00:00Z".into()),
+                },
+                ContractDates {
+                    contract: contracts[1].clone(),
+                    expiration_date: Some("2024-11-20T00:00:00Z".into()),
+                    first_notice_date: None,
+                    last_trading_date: Some("2024-11-19T00:00:00Z".into()),
+                },
+            ],
+            RollRule::LastTradingDate,
+            0,
+        )
+        .unwrap();
+        assert_eq!(schedule.rolls()[0].date, "2024-10-21");
+
+        let query = GetBarsQueryBuilder::new()
+            .symbol("@CL")
+            .unit(BarUnit::Daily)
+            .first_date("2024-10-17")
+            .last_date("2024-10-22")
+            .build()
+            .unwrap();
+
+        let bars = client
+            .get_continuous_bars(&schedule, &query, BackAdjustment::Difference)
+            .await
+            .unwrap();
+
+        // November's bars are shifted up by the $1 gap to December at the roll
+        let closes: Vec<&str> = bars.iter().map(|bar| bar.close.as_str()).collect();
+        assert_eq!(closes, vec!["70", "71", "72", "73"]);
+    });
+
+    // Ensure the mocks were called
+    november_mock.assert();
+    december_mock.assert();
+}
+
+#[test]
+/// This test ensures that the parsing of
+/// getting crypto pair symbol names is correct.
+fn test_get_crypto_symbol_names_mocked() {
+    let mut server = Server::new();
+    let mock = server
+        .mock("GET", "/marketdata/symbollists/cryptopairs/symbolnames")
+        .with_status(200)
+        .with_body("{\"SymbolNames\":[\"BTCUSD\",\"ETHUSD\",\"LTCUSD\",\"USDCUSD\"]}")
+        .expect(2)
+        .create();
+
+    let rt = tokio::runtime::Runtime::new().unwrap();
+    rt.block_on(async {
+        let client = ClientBuilder::new()
+            .environment(ClientEnvironment::Mock(server.url()))
+            .with_token(Token::dummy())
+            .build()
+            .await
+            .unwrap();
+
+        let symbol_names = client.get_crypto_symbol_names().await.unwrap();
+        assert_eq!(symbol_names, vec!["BTCUSD", "ETHUSD", "LTCUSD", "USDCUSD"]);
+
+        let pairs = client.get_crypto_pairs().await.unwrap();
+        assert_eq!(pairs.len(), 4);
+        assert_eq!(pairs[0].base, "BTC");
+        assert_eq!(pairs[0].quote, "USD");
+        assert_eq!(pairs[3].base, "USDC");
+    });
+
+    // Ensure the mock was called
+    mock.assert();
+}
+
+#[test]
+/// This test ensures that the parsing of getting option spread
+/// types is correct, including spread types this crate doesn't
+/// know about, and the fallback to the static list when mocked.
+fn test_get_option_spread_types_mocked() {
+    let mut server = Server::new();
+    let mock = server
+        .mock("GET", "/marketdata/options/spreadtypes")
+        .with_status(200)
+        .with_body(
+            "{\"SpreadTypes\":[{\"Name\":\"Single\",\"StrikeInterval\":false,\"ExpirationInterval\":false},{\"Name\":\"Vertical\",\"StrikeInterval\":true,\"ExpirationInterval\":false},{\"Name\":\"Diagonal\",\"StrikeInterval\":true,\"ExpirationInterval\":true},{\"Name\":\"JadeLizard\",\"StrikeInterval\":true,\"ExpirationInterval\":false}]}"
+        )
+        .create();
+
+    let rt = tokio::runtime::Runtime::new().unwrap();
+    rt.block_on(async {
+        let client = ClientBuilder::new()
+            .environment(ClientEnvironment::Mock(server.url()))
+            .with_token(Token::dummy())
+            .build()
+            .await
+            .unwrap();
+
+        let spread_types = client.get_option_spread_types().await.unwrap();
+        assert_eq!(spread_types.len(), 4);
+        assert_eq!(
+            spread_types[1].spread_type(),
+            Some(OptionSpreadType::Vertical)
+        );
+        assert!(spread_types[2].involves_expiration_interval());
+
+        // Unknown spread types should still be returned
+        assert_eq!(spread_types[3].name, "JadeLizard");
+        assert_eq!(spread_types[3].spread_type(), None);
+        assert!(spread_types[3].involves_strike_interval());
+    });
+
+    // Ensure the mock was called
+    mock.assert();
+
+    // Without the endpoint mocked, the static list should be used instead
+    mock.remove();
+    rt.block_on(async {
+        let client = ClientBuilder::new()
+            .environment(ClientEnvironment::Mock(server.url()))
+            .with_token(Token::dummy())
+            .build()
+            .await
+            .unwrap();
+
+        let spread_types = client.get_option_spread_types().await.unwrap();
+        assert_eq!(spread_types.len(), OptionSpreadType::all().len());
+        for details in spread_types {
+            let spread_type = details.spread_type().unwrap();
+            assert_eq!(
+                details.involves_strike_interval(),
+                spread_type.involves_strike_interval()
+            );
+        }
+    });
+}